@@ -27,6 +27,7 @@ menu-redo-op=Wiederherstellen: { $op }
 menu-cut=Ausschneiden
 menu-copy=Kopieren
 menu-paste=Einfügen
+menu-find_replace=Suchen & Ersetzen…
 menu-delete=Löschen
 menu-rename=Umbenennen
 menu-paste-as=Einfügen als
@@ -39,6 +40,7 @@ menu-justifyleft=Linksbündig
 menu-justifyright=Rechtsbündig
 menu-justifycenter=Zentrieren
 menu-crop=Zuschneiden
+menu-free_transform=Frei transformieren
 menu-justify_line_center=Zeile zentrieren
 menu-justify_line_left=Zeile linksbündig
 menu-justify_line_right=Zeile rechtsbündig
@@ -63,6 +65,20 @@ menu-selection=Auswahl
 menu-select-all=Alles auswählen
 menu-select_nothing=Nichts
 menu-inverse_selection=Invertieren
+menu-modify_selection=Verändern
+menu-modify-selection-grow=Erweitern
+menu-modify-selection-shrink=Verkleinern
+menu-modify-selection-border=Umrandung
+menu-modify-selection-smooth=Glätten
+menu-select_similar=Ähnliches auswählen
+menu-save_selection=Auswahl speichern…
+menu-saved_selections=Gespeicherte Auswahlen
+menu-delete_saved_selection=Löschen
+menu-selection_from_layer=Auswahl aus Ebene
+menu-selection-operation-replace=Auswahl ersetzen
+menu-selection-operation-add=Zur Auswahl hinzufügen
+menu-selection-operation-subtract=Von Auswahl abziehen
+menu-selection-operation-intersect=Mit Auswahl schneiden
 
 menu-colors=Farben
 menu-ice-mode=Ice-Modus
@@ -139,6 +155,27 @@ tool-full-block=Block
 tool-half-block=Halbblock
 tool-outline=Outline
 tool-custom-brush=Benutzerdefinierter Pinsel
+tool-spray=Airbrush
+tool-spray-density=Dichte:
+tool-spray-falloff=Abfall:
+tool-spray-falloff-flat=Flach
+tool-spray-falloff-linear=Linear
+tool-spray-falloff-smooth=Weich
+tool-spray-step-shade=Schattierung aufbauen
+tool-spray-step-color=Farben aufbauen
+tool-dodge_burn=Abwedeln/Nachbelichten
+tool-dodge_burn-dodge=Abwedeln
+tool-dodge_burn-burn=Nachbelichten
+tool-dodge_burn-ramp=Verlauf (dunkel nach hell):
+tool-dodge_burn-add_step=Ausgewähltes Zeichen hinzufügen
+tool-dodge_burn-set_step=Gewähltes Zeichen und Vordergrundfarbe verwenden
+tool-brush-shape-label=Form:
+tool-brush-shape-square=Quadrat
+tool-brush-shape-circle=Kreis
+tool-brush-shape-aspect_circle=Kreis (Seitenverhältnis)
+tool-brush-shape-diagonal=Diagonal
+tool-brush-shape-custom=Aus Auswahl
+tool-brush-shape-from-selection=Auswahl als Form verwenden
 
 tool-select-label=Auswahlmodus:
 tool-select-normal=Rechteck
@@ -146,6 +183,9 @@ tool-select-character=Zeichen
 tool-select-attribute=Attribute
 tool-select-foreground=Vordergund
 tool-select-background=Hintergrund
+tool-select-magic_wand=Zauberstab
+tool-select-magic_wand-match=Verbundene Zellen mit gleichem:
+tool-select-magic_wand-tolerance=Farbtoleranz
 tool-select-description=Shift halten, um Auswahl hinzuzufügen. Control/Cmd zum Entfernen.
 
 tool-fill-exact_match_label=Exakte Übereinstimmung
@@ -172,6 +212,17 @@ tool-flip_name=Schalter
 tool-flip_tooltip=Vertikale oder horizontale Halbblöcke umschalten
 tool-tdf_name=The Draw Fonts
 tool-tdf_tooltip=Texteingabe mit The Draw Fonts
+tool-text_box_name=Textfeld
+tool-text_box_tooltip=Ein Textfeld aufziehen und einen Absatz eingeben oder einfügen
+tool-text_box-align-left=Links
+tool-text_box-align-center=Zentriert
+tool-text_box-align-right=Rechts
+tool-text_box-align-justify=Blocksatz
+tool-text_box-fill_background=Hintergrund füllen
+tool-text_box-use_tdf_font=TDF Font verwenden
+tool-text_box-text_label=Text
+tool-text_box-commit=Übernehmen
+tool-text_box-cancel=Abbrechen
 tool-line_name=Linie
 tool-line_tooltip=Linien malen
 tool-move_layer_name=Ebene verschieben
@@ -252,6 +303,40 @@ export-format-label=Format:
 
 select-character-title=Zeichen auswählen
 
+find-replace-title=Suchen & Ersetzen
+find-replace-find=Suchen
+find-replace-replace=Ersetzen durch
+find-replace-chars=Zeichen
+find-replace-foreground=Vordergrund
+find-replace-background=Hintergrund
+find-replace-font_page=Zeichensatz
+find-replace-bold=Fett
+find-replace-blink=Blinken
+find-replace-faint=Blass
+find-replace-italic=Kursiv
+find-replace-underline=Unterstrichen
+find-replace-double_underline=Doppelt unterstrichen
+find-replace-conceal=Verborgen
+find-replace-crossed_out=Durchgestrichen
+find-replace-double_height=Doppelte Höhe
+find-replace-overline=Überstrichen
+find-replace-flag-on=An
+find-replace-flag-off=Aus
+find-replace-any=Beliebig
+find-replace-keep=Beibehalten
+find-replace-scope=Bereich
+find-replace-scope-selection=Auswahl
+find-replace-scope-layer=Aktuelle Ebene
+find-replace-scope-all_layers=Alle Ebenen
+find-replace-scope-all_layers-tooltip=Sichtbare Ebenen, die nicht gesperrt sind
+find-replace-match_count={ $count ->
+    [0] Keine Treffer
+    [one] 1 Treffer
+   *[other] { $count } Treffer
+}
+find-replace-replace_all=Alle ersetzen
+find-replace-error-length=Die Ersetzung braucht ein Zeichen oder genauso viele Zeichen wie die Suche.
+
 select-outline-style-title=Outline Stil auswählen
 
 about-dialog-title=Über Icy Draw
@@ -274,6 +359,18 @@ edit-layer-dialog-is-x-offset-label=X Versatz:
 edit-layer-dialog-is-y-offset-label=Y Versatz:
 edit-layer-dialog-has-alpha-checkbox=Hat Alphakanal
 edit-layer-dialog-is-alpha-locked-checkbox=Alphakanal gesperrt
+edit-layer-dialog-mode-label=Füllmodus:
+edit-layer-dialog-mode-tooltip=Aufhellen und Abdunkeln vergleichen die Helligkeit der Palettenfarben. Transparente Zellen einer Maskenebene verdecken die Ebenen darunter.
+
+blend-mode-normal=Normal
+blend-mode-chars=Nur Zeichen
+blend-mode-attributes=Nur Attribute
+blend-mode-foreground=Nur Vordergrund
+blend-mode-background=Nur Hintergrund
+blend-mode-colors=Farben über Zeichen
+blend-mode-lighten=Aufhellen
+blend-mode-darken=Abdunkeln
+blend-mode-mask=Maske
 
 error-load-file=Fehler während des Dateiladens: { $error }
 
@@ -289,6 +386,7 @@ select-font-dialog-block-font=BLOCK
 select-font-dialog-outline-font=OUTLINE
 select-font-dialog-preview-text=HALLO
 select-font-dialog-edit-button=Font bearbeiten…
+select-font-dialog-glyph-sheet=Alle Zeichen
 
 layer_tool_title=Ebenen
 layer_tool_menu_layer_properties=Ebeneneigenschaften…
@@ -298,6 +396,42 @@ layer_tool_menu_duplicate_layer=Duplizieren
 layer_tool_menu_merge_layer=Ebene zusammenführen
 layer_tool_menu_delete_layer=Ebene löschen
 layer_tool_menu_clear_layer=Ebene leeren
+layer_tool_menu_ghost_layer=Geisteransicht
+layer_tool_menu_solo_layer=Ebene solo
+layer_tool_ghost=Geist
+layer_tool_ghost_opacity_tooltip=Deckkraft der Geisterebene, sie wird angezeigt solange die Ebene ausgeblendet ist
+layer_tool_visibility_tooltip=Sichtbarkeit umschalten, Alt-Klick zeigt nur diese Ebene
+layer_tool_menu_group_layer=In neue Gruppe verschieben
+layer_tool_menu_selection_from_layer=Auswahl aus Ebene
+layer_tool_menu_rename_group=Gruppe umbenennen
+layer_tool_menu_raise_group=Gruppe nach oben
+layer_tool_menu_lower_group=Gruppe nach unten
+layer_tool_menu_merge_group=Gruppe zusammenführen
+layer_tool_menu_ungroup=Gruppierung aufheben
+layer_tool_default_group_name=Gruppe
+rename-layer-group-dialog-title=Gruppe umbenennen
+
+save-selection-dialog-title=Auswahl speichern
+save-selection-default-name=Auswahl { $number }
+modify-selection-dialog-amount-label=Zellen
+
+image-convert-dialog-title=Bild umwandeln
+image-convert-dialog-mode=Zeichen
+image-convert-dialog-mode-full_block=Volle Blöcke
+image-convert-dialog-mode-half_block=Halbe Blöcke
+image-convert-dialog-mode-quarter_block=Viertelblöcke
+image-convert-dialog-mode-shade=Schattierungen
+image-convert-dialog-mode-ascii=Nur ASCII
+image-convert-dialog-dithering=Dithering
+image-convert-dialog-dithering-none=Keines
+image-convert-dialog-dithering-floyd_steinberg=Floyd-Steinberg
+image-convert-dialog-dithering-ordered=Geordnet
+image-convert-dialog-keep_aspect_ratio=Seitenverhältnis beibehalten
+image-convert-dialog-palette_mode=Palette
+image-convert-dialog-palette_mode-fixed16=DOS 16 Farben
+image-convert-dialog-palette_mode-free8=8 Farben aus dem Bild
+image-convert-dialog-palette_mode-free16=16 Farben aus dem Bild
+image-convert-dialog-palette_mode-rgb=64 Farben aus dem Bild
 
 channel_tool_title=Kanäle
 channel_tool_fg=Vordergrund
@@ -310,6 +444,14 @@ font_tool_no_fonts_label=
     Keine Fonts gefunden
     Installiere Fonts in das Fontverzeichnis
 font_tool_open_directory_button=Fontverzeichnis öffnen
+font_tool_letter_spacing=Zeichenabstand
+font_tool_space_width=Leerzeichenbreite
+font_tool_line_spacing=Zeilenabstand
+font_tool_kerning_label=Unterschneidungspaare
+font_tool_kerning_hint=Paar, z.B. AV
+font_tool_kerning_add=Hinzufügen
+font_tool_text_label=Mehrzeiliger Text
+font_tool_render_text=An Cursorposition rendern
 
 pipette_tool_char_code=Code { $code }
 pipette_tool_foreground=Vordergrund { $fg }
@@ -345,13 +487,34 @@ undo-bitfont-inverse=Invertieren
 undo-bitfont-clear=Leeren
 undo-bitfont-edit=Editieren
 undo-bitfont-resize=Größe ändern
+undo-bitfont-import=Zeichen importieren
+undo-bitfont-line=Linie
+undo-bitfont-rectangle=Rechteck
+undo-bitfont-ellipse=Ellipse
+undo-bitfont-fill=Füllen
+undo-bitfont-move-selection=Auswahl verschieben
+undo-bitfont-unicode=Unicode-Zuordnung
+undo-bitfont-copy-glyphs=Zeichen kopieren
+undo-bitfont-apply-patch=Zeichen-Patch anwenden
 undo-delete=Entfernen
 undo-backspace=Rücktaste
 
 undo-render_character=Zeichen rendern
+undo-render_text=Text rendern
 undo-delete_character=Zeichen löschen
 undo-select=Auswahl
 undo-plugin=Erweiterung { $title }
+undo-text_box=Textfeld
+undo-find_replace=Suchen & Ersetzen
+undo-free-transform=Frei transformieren
+undo-float-selection=Auswahl lösen
+undo-layer-group-visibility=Gruppensichtbarkeit umschalten
+undo-layer-group-lock=Gruppensperre umschalten
+undo-layer-group-move=Gruppe verschieben
+undo-layer-group-merge=Gruppe zusammenführen
+undo-layer-group-ungroup=Gruppierung aufheben
+undo-layer-group-rename=Gruppe umbenennen
+undo-layer-group-new=Ebene gruppieren
 
 font_selector-ansi_font=ANSI
 font_selector-library_font=LIBRARY
@@ -374,6 +537,16 @@ paste_mode-rotate=Rotieren
 paste_mode-flipx=X Spiegeln
 paste_mode-flipy=Y Spiegeln
 paste_mode-transparent=Transparent
+paste_mode-transform=Frei transformieren
+paste_mode-transform-description=Rechte oder untere Kante ziehen zum Skalieren, außerhalb der Ebene ziehen zum Drehen.
+paste_mode-transform-scale_x=Skalierung X:
+paste_mode-transform-scale_y=Skalierung Y:
+paste_mode-transform-skew_x=Neigung X:
+paste_mode-transform-skew_y=Neigung Y:
+paste_mode-transform-angle=Drehen:
+paste_mode-transform-reset=Zurücksetzen
+paste_mode-transform-apply=Anwenden
+paste_mode-transform-cancel=Abbrechen
 
 ask_close_file_dialog-description=Sollen die Änderungen in { $filename } gespeichert werden?
 ask_close_file_dialog-subdescription=Alle Änderungen gehen beim Verwerfen verloren.
@@ -395,6 +568,88 @@ font-editor-clear=Löschen
 font-editor-inverse=Invertieren
 font-editor-flip_x=X Spiegeln
 font-editor-flip_y=Y Spiegeln
+font-editor-import=Zeichen importieren…
+font-editor-export_image=Bild exportieren…
+font-editor-tool-pencil=Stift
+font-editor-tool-line=Linie
+font-editor-tool-rectangle=Rechteck
+font-editor-tool-filled_rectangle=Gefülltes Rechteck
+font-editor-tool-ellipse=Ellipse
+font-editor-tool-filled_ellipse=Gefüllte Ellipse
+font-editor-tool-fill=Füllen
+font-editor-tool-select=Auswahl (Auswahl ziehen, um sie zu verschieben)
+font-editor-deselect=Auswahl aufheben
+font-editor-reference=Referenz: { $glyph }
+font-editor-use_as_reference=Als Referenzzeichen verwenden
+font-editor-clear_reference=Referenz entfernen
+font-editor-glyph_count=Zeichen
+font-editor-table-page=Zeichen { $start }-{ $end }
+font-editor-unicode=Unicode-Zuordnung
+font-editor-unicode-set=Setzen
+font-editor-unicode-tooltip=Codepunkte des Zeichens, durch Leerzeichen getrennt: U+00C4, 0xC4 oder das Zeichen selbst
+font-editor-unicode-error=Ungültiger Codepunkt: { $code }
+font-editor-unicode-reset=Auf CP437 zurücksetzen
+font-editor-unicode_label=Unicode:
+font-editor-compare=Vergleichen
+font-editor-compare-tooltip=Strg+Klick auf Zeichen in der Tabelle markiert sie zum Kopieren und Patchen
+font-editor-compare-with=Vergleichen mit…
+font-editor-compare-font={ $name }: { $count } unterschiedliche Zeichen
+font-editor-compare-mark=Unterschiede markieren
+font-editor-compare-copy=Markierte Zeichen kopieren
+font-editor-compare-stop=Vergleich beenden
+font-editor-compare-clear_marks=Markierungen aufheben
+font-editor-compare-differs=In { $name }:
+font-editor-mark_glyph=Zeichen markieren
+font-editor-unmark_glyph=Markierung aufheben
+font-editor-patch-save=Patch speichern…
+font-editor-patch-apply=Patch anwenden…
+font-editor-patch-files=Zeichensätze patchen…
+glyph-patch-error-invalid=Ungültige Zeichen-Patch-Datei
+glyph-patch-error-format=Zeichensätze mit der Endung '{ $ext }' können nicht gespeichert werden
+glyph-patch-error-sequences=Die Unicode-Tabelle des Zeichensatzes enthält Zeichenfolgen, die verloren gehen würden
+glyph-patch-error-files={ $patched } Zeichensätze gepatcht, diese schlugen fehl:
+    { $errors }
+glyph-patch-confirm-title=Zeichensätze patchen
+glyph-patch-confirm-text=Diese { $count } Zeichensätze werden gepatcht, die Originale bleiben mit der Endung .bak erhalten:
+glyph-patch-confirm-patch=Patchen
+export-font-dialog-title=Font als Bild exportieren
+export-font-dialog-kind=Export
+export-font-dialog-kind-sheet=Zeichentabelle
+export-font-dialog-kind-text=Textprobe
+export-font-dialog-kind-document=Offenes Dokument
+export-font-dialog-grid=Gitterlinien
+export-font-dialog-grid_color=Gitterfarbe
+export-font-dialog-text=Text
+export-font-dialog-document=Dokument
+export-font-dialog-colors=Vorder-/Hintergrund
+export-font-dialog-scale=Skalierung
+export-font-dialog-default_text=Zwölf Boxkämpfer jagen Viktor quer über den großen Sylter Deich.
+    ZWÖLF BOXKÄMPFER JAGEN VIKTOR QUER ÜBER DEN GROSSEN SYLTER DEICH!
+    0123456789 ░▒▓█ ┌─┐│└┘
+import-glyphs-dialog-title=Zeichen importieren
+import-glyphs-dialog-cell_size=Zeichengröße
+import-glyphs-dialog-threshold=Schwellwert
+import-glyphs-dialog-invert=Invertieren
+import-glyphs-dialog-font_size=Schriftgröße
+import-glyphs-dialog-baseline=Grundlinie
+import-glyphs-dialog-fit_size=An Zeichenhöhe anpassen
+import-glyphs-dialog-origin=Rasterursprung
+import-glyphs-dialog-grid_cell=Rasterzelle
+import-glyphs-dialog-spacing=Abstand
+import-glyphs-dialog-columns=Spalten
+import-glyphs-dialog-nudge=Zeichen { $glyph } verschieben
+font-save-dialog-title=Font speichern
+font-save-dialog-format=Format
+font-save-dialog-unicode_table=Unicode-Tabelle einfügen
+font-save-dialog-unicode_encoding=Zeichen als Unicode-Codepunkte kodieren
+font-save-dialog-face_name=Schriftname
+font-save-dialog-height_mismatch=Der Font ist { $height } Pixel hoch, die Endung .{ $ext } deutet auf eine andere Höhe hin.
+font-format-psf1=PSF1
+font-format-psf2=PSF2
+font-format-raw=DOS Rohformat
+font-format-fnt=Windows FNT
+font-format-bdf=BDF
+font-format-error-width={ $format }-Fonts können nicht breiter als 8 Pixel sein, der Font ist { $width } Pixel breit.
 
 animation_editor_path_label=Datei:
 animation_editor_export_button=Export
@@ -485,6 +740,36 @@ tdf-editor-clear_char_button=Zeichen Löschen
 tdf-editor-cheat_sheet_key=Taste
 tdf-editor-cheat_sheet_code=Code
 tdf-editor-cheat_sheet_res=Res
+tdf-editor-new_font_name=Neuer Font
+tdf-editor-add_button=Neuen Font hinzufügen
+tdf-editor-delete_button=Font löschen
+tdf-editor-move_up_button=Font nach oben
+tdf-editor-move_down_button=Font nach unten
+tdf-editor-name_error-empty=Der Font braucht einen Namen
+tdf-editor-name_error-ascii=Nur ASCII-Zeichen sind erlaubt
+tdf-editor-name_error-length=Fontnamen dürfen höchstens { $max } Zeichen lang sein
+tdf-editor-save_error-name=Font '{ $name }' kann nicht gespeichert werden: { $error }
+tdf-editor-save_error-bundle=Die Font-Sammlung konnte nicht als gültige TDF-Datei geschrieben werden
+tdf-editor-generate_button=Aus Bit-Font erzeugen…
+generate-tdf-dialog-title=TDF-Font erzeugen
+generate-tdf-dialog-source=Quell-Font
+generate-tdf-dialog-open_file=Bit-Font oder TrueType-Font öffnen…
+generate-tdf-dialog-glyph_height=Zeichenhöhe
+generate-tdf-dialog-pixel_char=Pixelzeichen
+generate-tdf-dialog-half_blocks=Zwei Pixelzeilen pro Zeichen (Halbblöcke)
+generate-tdf-dialog-proportional=Leere Spalten entfernen (proportional)
+generate-tdf-dialog-gradient=Zeilenfarben
+generate-tdf-dialog-gradient-ice=Eis
+generate-tdf-dialog-gradient-fire=Feuer
+generate-tdf-dialog-gradient-steel=Stahl
+generate-tdf-dialog-gradient-plain=Einfach
+generate-tdf-dialog-background=Hintergrund
+generate-tdf-dialog-shadow=Schattenversatz
+generate-tdf-dialog-shadow_char=Schattenzeichen
+generate-tdf-dialog-shadow_color=Schattenfarbe
+generate-tdf-dialog-preview_text=Icy Draw
+generate-tdf-dialog-generate=Erzeugen
+generate-tdf-dialog-no_source=Der Quell-Font konnte nicht geladen werden
 
 settings-heading=Einstellungen
 settings-reset_button=Reset
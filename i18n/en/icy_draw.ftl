@@ -27,6 +27,7 @@ menu-redo-op=Redo: { $op }
 menu-cut=Cut
 menu-copy=Copy
 menu-paste=Paste
+menu-find_replace=Find & Replace…
 menu-delete=Delete
 menu-rename=Rename
 menu-paste-as=Paste as
@@ -39,6 +40,7 @@ menu-justifyleft=Justify Left
 menu-justifyright=Justify Right
 menu-justifycenter=Center
 menu-crop=Crop
+menu-free_transform=Free transform
 menu-justify_line_center=Center Line
 menu-justify_line_left=Left Justify Line
 menu-justify_line_right=Right Justify Line
@@ -63,6 +65,20 @@ menu-selection=Selection
 menu-select-all=Select All
 menu-select_nothing=Deselect
 menu-inverse_selection=Inverse
+menu-modify_selection=Modify
+menu-modify-selection-grow=Grow
+menu-modify-selection-shrink=Shrink
+menu-modify-selection-border=Border
+menu-modify-selection-smooth=Smooth
+menu-select_similar=Select similar
+menu-save_selection=Save selection…
+menu-saved_selections=Saved selections
+menu-delete_saved_selection=Delete
+menu-selection_from_layer=Selection from layer
+menu-selection-operation-replace=Replace selection
+menu-selection-operation-add=Add to selection
+menu-selection-operation-subtract=Subtract from selection
+menu-selection-operation-intersect=Intersect with selection
 
 menu-colors=Colors
 menu-ice-mode=Ice Mode
@@ -136,6 +152,27 @@ tool-full-block=Block
 tool-half-block=Half Block
 tool-outline=Outline
 tool-custom-brush=Custom brush
+tool-spray=Airbrush
+tool-spray-density=Density:
+tool-spray-falloff=Falloff:
+tool-spray-falloff-flat=Flat
+tool-spray-falloff-linear=Linear
+tool-spray-falloff-smooth=Smooth
+tool-spray-step-shade=Build up shades
+tool-spray-step-color=Build up colors
+tool-dodge_burn=Dodge/Burn
+tool-dodge_burn-dodge=Dodge
+tool-dodge_burn-burn=Burn
+tool-dodge_burn-ramp=Ramp (dark to bright):
+tool-dodge_burn-add_step=Add selected character
+tool-dodge_burn-set_step=Use the selected character and foreground color
+tool-brush-shape-label=Shape:
+tool-brush-shape-square=Square
+tool-brush-shape-circle=Circle
+tool-brush-shape-aspect_circle=Circle (aspect corrected)
+tool-brush-shape-diagonal=Diagonal
+tool-brush-shape-custom=From selection
+tool-brush-shape-from-selection=Use selection as shape
 
 tool-select-label=Selection mode:
 tool-select-normal=Rectangle
@@ -143,6 +180,9 @@ tool-select-character=Character
 tool-select-attribute=Attribute
 tool-select-foreground=Foreground
 tool-select-background=Background
+tool-select-magic_wand=Magic wand
+tool-select-magic_wand-match=Connected cells with same:
+tool-select-magic_wand-tolerance=Color tolerance
 tool-select-description=Hold shift to add to a selection. Control/Cmd to remove.
 
 tool-fill-exact_match_label=Exact match
@@ -169,6 +209,17 @@ tool-flip_name=Switcher
 tool-flip_tooltip=Switch vertical or horizontal half blocks
 tool-tdf_name=The Draw Fonts
 tool-tdf_tooltip=Text input using The Draw Fonts
+tool-text_box_name=Text box
+tool-text_box_tooltip=Draw a text box and type or paste a paragraph into it
+tool-text_box-align-left=Left
+tool-text_box-align-center=Center
+tool-text_box-align-right=Right
+tool-text_box-align-justify=Justify
+tool-text_box-fill_background=Fill background
+tool-text_box-use_tdf_font=Use TDF font
+tool-text_box-text_label=Text
+tool-text_box-commit=Commit
+tool-text_box-cancel=Cancel
 tool-line_name=Draw line
 tool-line_tooltip=Draw lines
 tool-move_layer_name=Move Layer
@@ -245,6 +296,40 @@ export-format-label=Format:
 
 select-character-title=Select Character
 
+find-replace-title=Find & Replace
+find-replace-find=Find
+find-replace-replace=Replace with
+find-replace-chars=Characters
+find-replace-foreground=Foreground
+find-replace-background=Background
+find-replace-font_page=Font page
+find-replace-bold=Bold
+find-replace-blink=Blink
+find-replace-faint=Faint
+find-replace-italic=Italic
+find-replace-underline=Underline
+find-replace-double_underline=Double underline
+find-replace-conceal=Concealed
+find-replace-crossed_out=Crossed out
+find-replace-double_height=Double height
+find-replace-overline=Overline
+find-replace-flag-on=On
+find-replace-flag-off=Off
+find-replace-any=Any
+find-replace-keep=Keep
+find-replace-scope=Scope
+find-replace-scope-selection=Selection
+find-replace-scope-layer=Current layer
+find-replace-scope-all_layers=All layers
+find-replace-scope-all_layers-tooltip=Visible layers that aren't locked
+find-replace-match_count={ $count ->
+    [0] No matches
+    [one] 1 match
+   *[other] { $count } matches
+}
+find-replace-replace_all=Replace all
+find-replace-error-length=The replacement needs one character or as many characters as the search.
+
 select-outline-style-title=Outline Font Style Type
 
 about-dialog-title=About Icy Draw
@@ -268,6 +353,18 @@ edit-layer-dialog-is-x-offset-label=X offset:
 edit-layer-dialog-is-y-offset-label=Y offset:
 edit-layer-dialog-has-alpha-checkbox=Has alpha
 edit-layer-dialog-is-alpha-locked-checkbox=Alpha locked
+edit-layer-dialog-mode-label=Blend mode:
+edit-layer-dialog-mode-tooltip=Lighten and darken compare the brightness of the palette colors. Transparent cells of a mask layer hide the layers below.
+
+blend-mode-normal=Normal
+blend-mode-chars=Chars only
+blend-mode-attributes=Attributes only
+blend-mode-foreground=Foreground only
+blend-mode-background=Background only
+blend-mode-colors=Colors over chars
+blend-mode-lighten=Lighten
+blend-mode-darken=Darken
+blend-mode-mask=Mask
 
 error-load-file=Error loading file: { $error }
 
@@ -283,6 +380,7 @@ select-font-dialog-block-font=BLOCK
 select-font-dialog-outline-font=OUTLINE
 select-font-dialog-preview-text=HELLO
 select-font-dialog-edit-button=Edit font…
+select-font-dialog-glyph-sheet=All glyphs
 
 layer_tool_title=Layers
 layer_tool_menu_layer_properties=Layer properties
@@ -292,6 +390,42 @@ layer_tool_menu_duplicate_layer=Duplicate layer
 layer_tool_menu_merge_layer=Merge layer
 layer_tool_menu_delete_layer=Delete layer
 layer_tool_menu_clear_layer=Clear layer
+layer_tool_menu_ghost_layer=Ghost view
+layer_tool_menu_solo_layer=Solo layer
+layer_tool_ghost=ghost
+layer_tool_ghost_opacity_tooltip=Opacity of the ghost layer, it is shown while the layer is hidden
+layer_tool_visibility_tooltip=Toggle visibility, alt-click to solo the layer
+layer_tool_menu_group_layer=Put into new group
+layer_tool_menu_selection_from_layer=Selection from layer
+layer_tool_menu_rename_group=Rename group
+layer_tool_menu_raise_group=Move group up
+layer_tool_menu_lower_group=Move group down
+layer_tool_menu_merge_group=Merge group
+layer_tool_menu_ungroup=Ungroup
+layer_tool_default_group_name=Group
+rename-layer-group-dialog-title=Rename group
+
+save-selection-dialog-title=Save selection
+save-selection-default-name=Selection { $number }
+modify-selection-dialog-amount-label=Cells
+
+image-convert-dialog-title=Convert image
+image-convert-dialog-mode=Characters
+image-convert-dialog-mode-full_block=Full blocks
+image-convert-dialog-mode-half_block=Half blocks
+image-convert-dialog-mode-quarter_block=Quarter blocks
+image-convert-dialog-mode-shade=Shades
+image-convert-dialog-mode-ascii=ASCII only
+image-convert-dialog-dithering=Dithering
+image-convert-dialog-dithering-none=None
+image-convert-dialog-dithering-floyd_steinberg=Floyd-Steinberg
+image-convert-dialog-dithering-ordered=Ordered
+image-convert-dialog-keep_aspect_ratio=Keep aspect ratio
+image-convert-dialog-palette_mode=Palette
+image-convert-dialog-palette_mode-fixed16=DOS 16 colors
+image-convert-dialog-palette_mode-free8=8 colors from image
+image-convert-dialog-palette_mode-free16=16 colors from image
+image-convert-dialog-palette_mode-rgb=64 colors from image
 
 channel_tool_title=Channels
 channel_tool_fg=Foreground
@@ -304,6 +438,14 @@ font_tool_no_fonts_label=
     No tdf fonts found.
     Install new fonts in the font directory
 font_tool_open_directory_button=Open font directory
+font_tool_letter_spacing=Letter spacing
+font_tool_space_width=Space width
+font_tool_line_spacing=Line spacing
+font_tool_kerning_label=Kerning pairs
+font_tool_kerning_hint=Pair, e.g. AV
+font_tool_kerning_add=Add
+font_tool_text_label=Multi-line text
+font_tool_render_text=Render at caret
 
 pipette_tool_char_code=Code { $code }
 pipette_tool_foreground=Foreground { $fg }
@@ -339,13 +481,34 @@ undo-bitfont-inverse=Inverse
 undo-bitfont-clear=Clear
 undo-bitfont-edit=Edit
 undo-bitfont-resize=Resize
+undo-bitfont-import=Import glyphs
+undo-bitfont-line=Line
+undo-bitfont-rectangle=Rectangle
+undo-bitfont-ellipse=Ellipse
+undo-bitfont-fill=Fill
+undo-bitfont-move-selection=Move selection
+undo-bitfont-unicode=Unicode mapping
+undo-bitfont-copy-glyphs=Copy glyphs
+undo-bitfont-apply-patch=Apply glyph patch
 undo-delete=Delete
 undo-backspace=Backspace
 
 undo-render_character=Render character
+undo-render_text=Render text
 undo-delete_character=Delete character
 undo-select=Select
 undo-plugin=Plugin { $title }
+undo-text_box=Text box
+undo-find_replace=Find & Replace
+undo-free-transform=Free transform
+undo-float-selection=Float selection
+undo-layer-group-visibility=Toggle group visibility
+undo-layer-group-lock=Toggle group lock
+undo-layer-group-move=Move group
+undo-layer-group-merge=Merge group
+undo-layer-group-ungroup=Ungroup layers
+undo-layer-group-rename=Rename group
+undo-layer-group-new=Group layer
 
 font_selector-ansi_font=ANSI
 font_selector-library_font=LIBRARY
@@ -368,6 +531,16 @@ paste_mode-rotate=Rotate
 paste_mode-flipx=Flip X
 paste_mode-flipy=Flip Y
 paste_mode-transparent=Transparent
+paste_mode-transform=Free transform
+paste_mode-transform-description=Drag the right or bottom edge to scale, drag outside of the layer to rotate.
+paste_mode-transform-scale_x=Scale X:
+paste_mode-transform-scale_y=Scale Y:
+paste_mode-transform-skew_x=Skew X:
+paste_mode-transform-skew_y=Skew Y:
+paste_mode-transform-angle=Rotate:
+paste_mode-transform-reset=Reset
+paste_mode-transform-apply=Apply
+paste_mode-transform-cancel=Cancel
 
 ask_close_file_dialog-description=Do you want to save the changes you made to { $filename }?
 ask_close_file_dialog-subdescription=Your changes will be lost if you don't save them.
@@ -389,6 +562,88 @@ font-editor-clear=Clear
 font-editor-inverse=Inverse
 font-editor-flip_x=Flip X
 font-editor-flip_y=Flip Y
+font-editor-import=Import glyphs…
+font-editor-export_image=Export image…
+font-editor-tool-pencil=Pencil
+font-editor-tool-line=Line
+font-editor-tool-rectangle=Rectangle
+font-editor-tool-filled_rectangle=Filled rectangle
+font-editor-tool-ellipse=Ellipse
+font-editor-tool-filled_ellipse=Filled ellipse
+font-editor-tool-fill=Fill
+font-editor-tool-select=Select (drag the selection to move it)
+font-editor-deselect=Deselect
+font-editor-reference=Reference: { $glyph }
+font-editor-use_as_reference=Use as reference glyph
+font-editor-clear_reference=Clear reference
+font-editor-glyph_count=Glyphs
+font-editor-table-page=Glyphs { $start }-{ $end }
+font-editor-unicode=Unicode mapping
+font-editor-unicode-set=Set
+font-editor-unicode-tooltip=Code points of the glyph, separated by spaces: U+00C4, 0xC4 or the character itself
+font-editor-unicode-error=Invalid code point: { $code }
+font-editor-unicode-reset=Reset to CP437
+font-editor-unicode_label=Unicode:
+font-editor-compare=Compare
+font-editor-compare-tooltip=Ctrl+click glyphs in the table to mark them for copying and patching
+font-editor-compare-with=Compare with…
+font-editor-compare-font={ $name }: { $count } different glyphs
+font-editor-compare-mark=Mark differences
+font-editor-compare-copy=Copy marked glyphs
+font-editor-compare-stop=Stop comparing
+font-editor-compare-clear_marks=Clear marks
+font-editor-compare-differs=In { $name }:
+font-editor-mark_glyph=Mark glyph
+font-editor-unmark_glyph=Unmark glyph
+font-editor-patch-save=Save patch…
+font-editor-patch-apply=Apply patch…
+font-editor-patch-files=Patch font files…
+glyph-patch-error-invalid=Invalid glyph patch file
+glyph-patch-error-format=Can't save fonts with the extension '{ $ext }'
+glyph-patch-error-sequences=The unicode table of the font has character sequences that would get lost
+glyph-patch-error-files=Patched { $patched } font files, these failed:
+    { $errors }
+glyph-patch-confirm-title=Patch font files
+glyph-patch-confirm-text=These { $count } font files will be patched, the original files are kept with a .bak extension:
+glyph-patch-confirm-patch=Patch
+export-font-dialog-title=Export font image
+export-font-dialog-kind=Export
+export-font-dialog-kind-sheet=Glyph sheet
+export-font-dialog-kind-text=Text specimen
+export-font-dialog-kind-document=Open document
+export-font-dialog-grid=Grid lines
+export-font-dialog-grid_color=Grid color
+export-font-dialog-text=Text
+export-font-dialog-document=Document
+export-font-dialog-colors=Foreground/background
+export-font-dialog-scale=Scale
+export-font-dialog-default_text=The quick brown fox jumps over the lazy dog.
+    THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG!
+    0123456789 ░▒▓█ ┌─┐│└┘
+import-glyphs-dialog-title=Import glyphs
+import-glyphs-dialog-cell_size=Glyph size
+import-glyphs-dialog-threshold=Threshold
+import-glyphs-dialog-invert=Invert
+import-glyphs-dialog-font_size=Font size
+import-glyphs-dialog-baseline=Baseline
+import-glyphs-dialog-fit_size=Fit to glyph height
+import-glyphs-dialog-origin=Grid origin
+import-glyphs-dialog-grid_cell=Grid cell
+import-glyphs-dialog-spacing=Spacing
+import-glyphs-dialog-columns=Columns
+import-glyphs-dialog-nudge=Nudge glyph { $glyph }
+font-save-dialog-title=Save font
+font-save-dialog-format=Format
+font-save-dialog-unicode_table=Include unicode table
+font-save-dialog-unicode_encoding=Encode glyphs as unicode code points
+font-save-dialog-face_name=Face name
+font-save-dialog-height_mismatch=The font is { $height } pixel high, the extension .{ $ext } suggests a different height.
+font-format-psf1=PSF1
+font-format-psf2=PSF2
+font-format-raw=Raw DOS font
+font-format-fnt=Windows FNT
+font-format-bdf=BDF
+font-format-error-width={ $format } fonts can't be wider than 8 pixel, the font is { $width } pixel wide.
 
 animation_editor_path_label=Path:
 animation_editor_export_button=Export
@@ -478,6 +733,36 @@ tdf-editor-clear_char_button=Clear Char
 tdf-editor-cheat_sheet_key=Key
 tdf-editor-cheat_sheet_code=Code
 tdf-editor-cheat_sheet_res=Res
+tdf-editor-new_font_name=New Font
+tdf-editor-add_button=Add a new font
+tdf-editor-delete_button=Delete font
+tdf-editor-move_up_button=Move font up
+tdf-editor-move_down_button=Move font down
+tdf-editor-name_error-empty=The font needs a name
+tdf-editor-name_error-ascii=Only ASCII characters are allowed
+tdf-editor-name_error-length=Font names can have at most { $max } characters
+tdf-editor-save_error-name=Can't save font '{ $name }': { $error }
+tdf-editor-save_error-bundle=The font bundle couldn't be written as valid TDF file
+tdf-editor-generate_button=Generate from bit font…
+generate-tdf-dialog-title=Generate TDF font
+generate-tdf-dialog-source=Source font
+generate-tdf-dialog-open_file=Open bit font or TrueType font…
+generate-tdf-dialog-glyph_height=Glyph height
+generate-tdf-dialog-pixel_char=Pixel character
+generate-tdf-dialog-half_blocks=Two pixel rows per character (half blocks)
+generate-tdf-dialog-proportional=Remove empty columns (proportional)
+generate-tdf-dialog-gradient=Row colors
+generate-tdf-dialog-gradient-ice=Ice
+generate-tdf-dialog-gradient-fire=Fire
+generate-tdf-dialog-gradient-steel=Steel
+generate-tdf-dialog-gradient-plain=Plain
+generate-tdf-dialog-background=Background
+generate-tdf-dialog-shadow=Shadow offset
+generate-tdf-dialog-shadow_char=Shadow character
+generate-tdf-dialog-shadow_color=Shadow color
+generate-tdf-dialog-preview_text=Icy Draw
+generate-tdf-dialog-generate=Generate
+generate-tdf-dialog-no_source=The source font couldn't be loaded
 
 settings-heading=Settings
 settings-reset_button=Reset
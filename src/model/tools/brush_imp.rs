@@ -1,13 +1,13 @@
 use eframe::egui::Response;
 use egui::{load::SizedTexture, Image, TextureHandle, Widget};
 use i18n_embed_fl::fl;
-use icy_engine::{editor::AtomicUndoGuard, AttributedChar, Layer, TextPane};
+use icy_engine::{ascii::CP437Converter, editor::AtomicUndoGuard, AttributedChar, Layer, Palette, TextAttribute, TextPane, UnicodeConverter};
 use icy_engine_egui::TerminalCalc;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
 
 use crate::{
     create_image,
-    paint::{BrushMode, ColorMode},
+    paint::{get_cell_aspect, get_footprint_outline, BrushMode, BrushShape, ColorMode, SHADE_GRADIENT},
     AnsiEditor, Event, Message,
 };
 
@@ -15,6 +15,52 @@ use super::{Position, Tool};
 
 pub static mut CUSTOM_BRUSH: Option<Layer> = None;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SprayFalloff {
+    Flat,
+    Linear,
+    Smooth,
+}
+
+impl SprayFalloff {
+    /// Hit probability factor for a cell at the normalized distance `d` (0 = center, 1 = rim).
+    pub fn get_factor(self, d: f32) -> f32 {
+        match self {
+            SprayFalloff::Flat => 1.0,
+            SprayFalloff::Linear => 1.0 - d,
+            SprayFalloff::Smooth => {
+                let f = 1.0 - d * d;
+                f * f
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SprayStep {
+    Shade,
+    Color,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DodgeBurn {
+    Dodge,
+    Burn,
+}
+
+/// One step of the dodge/burn ramp, the ramp is ordered from dark to bright.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RampStep {
+    pub ch: char,
+    pub foreground: u32,
+}
+
+impl RampStep {
+    pub fn new(ch: char, foreground: u32) -> Self {
+        Self { ch, foreground }
+    }
+}
+
 pub struct BrushTool {
     color_mode: ColorMode,
     size: i32,
@@ -25,6 +71,16 @@ pub struct BrushTool {
     custom_brush: Option<Layer>,
     image: Option<TextureHandle>,
     brush_mode: BrushMode,
+    shape: BrushShape,
+
+    spray_density: f32,
+    spray_falloff: SprayFalloff,
+    spray_step: SprayStep,
+
+    dodge_burn: DodgeBurn,
+    ramp: Vec<RampStep>,
+    /// Cells already stepped in the current stroke, each stroke moves a cell only once.
+    visited: HashSet<Position>,
 }
 
 impl Default for BrushTool {
@@ -36,17 +92,33 @@ impl Default for BrushTool {
             custom_brush: None,
             image: None,
             brush_mode: BrushMode::Shade,
+            shape: BrushShape::Square,
             char_code: Rc::new(RefCell::new('\u{00B0}')),
             cur_pos: Position::default(),
+            spray_density: 0.25,
+            spray_falloff: SprayFalloff::Smooth,
+            spray_step: SprayStep::Shade,
+            dodge_burn: DodgeBurn::Dodge,
+            ramp: vec![
+                RampStep::new('\u{00B0}', 8),
+                RampStep::new('\u{00B1}', 8),
+                RampStep::new('\u{00B2}', 8),
+                RampStep::new('\u{00DB}', 8),
+                RampStep::new('\u{00B2}', 7),
+                RampStep::new('\u{00DB}', 7),
+                RampStep::new('\u{00B2}', 15),
+                RampStep::new('\u{00DB}', 15),
+            ],
+            visited: HashSet::new(),
         }
     }
 }
 impl BrushTool {
-    fn paint_brush(&self, editor: &mut AnsiEditor, pos: Position) {
-        let mid = Position::new(-(self.size / 2), -(self.size / 2));
-
-        let center = pos + mid;
-        let gradient = ['\u{00B0}', '\u{00B1}', '\u{00B2}', '\u{00DB}'];
+    fn paint_brush(&mut self, editor: &mut AnsiEditor, pos: Position) {
+        if matches!(self.brush_mode, BrushMode::Spray) {
+            self.spray(editor, pos);
+            return;
+        }
         let caret_attr = editor.buffer_view.lock().get_caret().get_attribute();
         if matches!(self.brush_mode, BrushMode::Custom) {
             editor.join_overlay("brush");
@@ -62,9 +134,162 @@ impl BrushTool {
             Position::default()
         };
 
-        for y in 0..self.size {
-            for x in 0..self.size {
-                let pos = center + Position::new(x, y);
+        for cell in self.shape.get_footprint(self.size, get_cell_aspect(editor)) {
+            let pos = pos + cell;
+            if use_selection && !editor.buffer_view.lock().get_edit_state().get_is_selected(pos + offset) {
+                continue;
+            }
+            let ch = editor.get_char_from_cur_layer(pos);
+            let mut attribute = ch.attribute;
+            attribute.attr &= !icy_engine::attribute::INVISIBLE;
+
+            if self.color_mode.use_fore() {
+                attribute.set_foreground(caret_attr.get_foreground());
+            }
+            if self.color_mode.use_back() {
+                attribute.set_background(caret_attr.get_background());
+            }
+
+            match &self.brush_mode {
+                BrushMode::Shade => {
+                    let mut char_code = SHADE_GRADIENT[0];
+                    if ch.ch == SHADE_GRADIENT[SHADE_GRADIENT.len() - 1] {
+                        char_code = SHADE_GRADIENT[SHADE_GRADIENT.len() - 1];
+                    } else {
+                        for i in 0..SHADE_GRADIENT.len() - 1 {
+                            if ch.ch == SHADE_GRADIENT[i] {
+                                char_code = SHADE_GRADIENT[i + 1];
+                                break;
+                            }
+                        }
+                    }
+                    editor.set_char(pos, AttributedChar::new(char_code, attribute));
+                }
+                BrushMode::Char(ch) => {
+                    attribute.set_font_page(caret_attr.get_font_page());
+                    editor.set_char(pos, AttributedChar::new(*ch.borrow(), attribute));
+                }
+                BrushMode::Colorize => {
+                    editor.set_char(pos, AttributedChar::new(ch.ch, attribute));
+                }
+                BrushMode::DodgeBurn => {
+                    if !self.visited.insert(pos) {
+                        continue;
+                    }
+                    if let Some(step) = self.step_ramp(ch) {
+                        let mut attribute = ch.attribute;
+                        attribute.attr &= !icy_engine::attribute::INVISIBLE;
+                        attribute.set_foreground(step.foreground);
+                        attribute.set_font_page(caret_attr.get_font_page());
+                        editor.set_char(pos, AttributedChar::new(step.ch, attribute));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the ramp step the cell moves to, cells that aren't on the ramp enter it at the darkest step when dodging.
+    fn step_ramp(&self, ch: AttributedChar) -> Option<RampStep> {
+        if self.ramp.is_empty() {
+            return None;
+        }
+        let fg = ch.attribute.get_foreground();
+        let cur = self
+            .ramp
+            .iter()
+            .position(|step| step.ch == ch.ch && step.foreground == fg)
+            .or_else(|| self.ramp.iter().position(|step| step.ch == ch.ch));
+        let last = self.ramp.len() - 1;
+        let next = match (cur, self.dodge_burn) {
+            (Some(i), DodgeBurn::Dodge) => (i + 1).min(last),
+            (Some(i), DodgeBurn::Burn) => i.saturating_sub(1),
+            (None, DodgeBurn::Dodge) => 0,
+            (None, DodgeBurn::Burn) => return None,
+        };
+        Some(self.ramp[next])
+    }
+
+    fn show_ramp_ui(&mut self, ui: &mut egui::Ui, editor_opt: Option<&mut AnsiEditor>) {
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.dodge_burn, DodgeBurn::Dodge, fl!(crate::LANGUAGE_LOADER, "tool-dodge_burn-dodge"));
+            ui.selectable_value(&mut self.dodge_burn, DodgeBurn::Burn, fl!(crate::LANGUAGE_LOADER, "tool-dodge_burn-burn"));
+        });
+        ui.label(fl!(crate::LANGUAGE_LOADER, "tool-dodge_burn-ramp"));
+
+        let selected_step = editor_opt.map(|editor| {
+            let fg = editor.buffer_view.lock().get_caret().get_attribute().get_foreground();
+            RampStep::new(*self.char_code.borrow(), fg)
+        });
+        let step_count = self.ramp.len();
+        let mut remove = None;
+        let mut swap = None;
+        for (i, step) in self.ramp.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add_space(8.0);
+                let ch = CP437Converter::default().convert_to_unicode(AttributedChar::new(step.ch, TextAttribute::default()));
+                ui.label(egui::RichText::new(ch.to_string()).monospace());
+                let mut char_code = step.ch as u32;
+                if ui.add(egui::DragValue::new(&mut char_code).clamp_range(0..=255)).changed() {
+                    step.ch = char::from_u32(char_code).unwrap_or(step.ch);
+                }
+                ui.add(egui::DragValue::new(&mut step.foreground).clamp_range(0..=255));
+                if let Some(selected_step) = selected_step {
+                    if ui
+                        .small_button("✏")
+                        .on_hover_text(fl!(crate::LANGUAGE_LOADER, "tool-dodge_burn-set_step"))
+                        .clicked()
+                    {
+                        *step = selected_step;
+                    }
+                }
+                if ui.add_enabled(i > 0, egui::Button::new("⬆").small()).clicked() {
+                    swap = Some((i - 1, i));
+                }
+                if ui.add_enabled(i + 1 < step_count, egui::Button::new("⬇").small()).clicked() {
+                    swap = Some((i, i + 1));
+                }
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some((a, b)) = swap {
+            self.ramp.swap(a, b);
+        }
+        if let Some(i) = remove {
+            self.ramp.remove(i);
+        }
+
+        if let Some(selected_step) = selected_step {
+            if ui.button(fl!(crate::LANGUAGE_LOADER, "tool-dodge_burn-add_step")).clicked() {
+                self.ramp.push(selected_step);
+            }
+        }
+    }
+
+    fn spray(&mut self, editor: &mut AnsiEditor, pos: Position) {
+        let caret_attr = editor.buffer_view.lock().get_caret().get_attribute();
+        let use_selection = editor.buffer_view.lock().get_edit_state().is_something_selected();
+        editor.buffer_view.lock().get_edit_state_mut().set_is_buffer_dirty();
+
+        let offset = if let Some(layer) = editor.buffer_view.lock().get_edit_state().get_cur_layer() {
+            layer.get_offset()
+        } else {
+            Position::default()
+        };
+
+        let ramp = get_brightness_ramp(&editor.buffer_view.lock().get_buffer().palette);
+        let radius = self.size as f32 / 2.0;
+        let r = self.size / 2;
+        for y in -r..=r {
+            for x in -r..=r {
+                let d = ((x * x + y * y) as f32).sqrt() / radius;
+                if d > 1.0 || fastrand::f32() >= self.spray_density * self.spray_falloff.get_factor(d) {
+                    continue;
+                }
+                let pos = pos + Position::new(x, y);
                 if use_selection && !editor.buffer_view.lock().get_edit_state().get_is_selected(pos + offset) {
                     continue;
                 }
@@ -72,42 +297,58 @@ impl BrushTool {
                 let mut attribute = ch.attribute;
                 attribute.attr &= !icy_engine::attribute::INVISIBLE;
 
-                if self.color_mode.use_fore() {
-                    attribute.set_foreground(caret_attr.get_foreground());
-                }
-                if self.color_mode.use_back() {
-                    attribute.set_background(caret_attr.get_background());
-                }
-
-                match &self.brush_mode {
-                    BrushMode::Shade => {
-                        let mut char_code = gradient[0];
-                        if ch.ch == gradient[gradient.len() - 1] {
-                            char_code = gradient[gradient.len() - 1];
-                        } else {
-                            for i in 0..gradient.len() - 1 {
-                                if ch.ch == gradient[i] {
-                                    char_code = gradient[i + 1];
-                                    break;
-                                }
-                            }
+                match self.spray_step {
+                    SprayStep::Shade => {
+                        if self.color_mode.use_fore() {
+                            attribute.set_foreground(caret_attr.get_foreground());
+                        }
+                        if self.color_mode.use_back() {
+                            attribute.set_background(caret_attr.get_background());
                         }
+                        let char_code = if let Some(i) = SHADE_GRADIENT.iter().position(|c| *c == ch.ch) {
+                            SHADE_GRADIENT[(i + 1).min(SHADE_GRADIENT.len() - 1)]
+                        } else {
+                            SHADE_GRADIENT[0]
+                        };
                         editor.set_char(pos, AttributedChar::new(char_code, attribute));
                     }
-                    BrushMode::Char(ch) => {
-                        attribute.set_font_page(caret_attr.get_font_page());
-                        editor.set_char(center + Position::new(x, y), AttributedChar::new(*ch.borrow(), attribute));
-                    }
-                    BrushMode::Colorize => {
+                    SprayStep::Color => {
+                        if self.color_mode.use_fore() {
+                            attribute.set_foreground(step_color(&ramp, attribute.get_foreground(), caret_attr.get_foreground()));
+                        }
+                        if self.color_mode.use_back() {
+                            attribute.set_background(step_color(&ramp, attribute.get_background(), caret_attr.get_background()));
+                        }
                         editor.set_char(pos, AttributedChar::new(ch.ch, attribute));
                     }
-                    _ => {}
                 }
             }
         }
     }
 }
 
+/// The palette indices sorted from dark to bright.
+fn get_brightness_ramp(palette: &Palette) -> Vec<u32> {
+    let mut ramp: Vec<u32> = (0..palette.len() as u32).collect();
+    ramp.sort_by_key(|color| {
+        let (r, g, b) = palette.get_color(*color).get_rgb();
+        u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114
+    });
+    ramp
+}
+
+/// Moves a color one step along the brightness ramp towards the target color.
+fn step_color(ramp: &[u32], color: u32, target: u32) -> u32 {
+    let (Some(from), Some(to)) = (ramp.iter().position(|c| *c == color), ramp.iter().position(|c| *c == target)) else {
+        return target;
+    };
+    match from.cmp(&to) {
+        std::cmp::Ordering::Less => ramp[from + 1],
+        std::cmp::Ordering::Greater => ramp[from - 1],
+        std::cmp::Ordering::Equal => color,
+    }
+}
+
 impl Tool for BrushTool {
     fn get_icon(&self) -> &egui::Image<'static> {
         &super::icons::BRUSH_SVG
@@ -125,13 +366,16 @@ impl Tool for BrushTool {
         false
     }
 
-    fn show_ui(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, editor_opt: Option<&mut AnsiEditor>) -> Option<Message> {
+    fn show_ui(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, mut editor_opt: Option<&mut AnsiEditor>) -> Option<Message> {
         self.color_mode.show_ui(ui);
 
         ui.horizontal(|ui| {
             ui.label(fl!(crate::LANGUAGE_LOADER, "tool-size-label"));
             ui.add(egui::DragValue::new(&mut self.size).clamp_range(1..=20).speed(1));
         });
+        if !matches!(self.brush_mode, BrushMode::Spray | BrushMode::Custom) {
+            self.shape.show_ui(ui, editor_opt.as_deref_mut());
+        }
         /*
                 ui.radio_value(&mut self.brush_type, BrushType::Shade, fl!(crate::LANGUAGE_LOADER, "tool-shade"));
                 ui.horizontal(|ui| {
@@ -142,7 +386,41 @@ impl Tool for BrushTool {
                 });
                 ui.radio_value(&mut self.brush_type, BrushType::Color, fl!(crate::LANGUAGE_LOADER, "tool-colorize"));
         */
-        let result = self.brush_mode.show_ui(ui, editor_opt, self.char_code.clone(), crate::paint::BrushUi::Brush);
+        let result = self
+            .brush_mode
+            .show_ui(ui, editor_opt.as_deref_mut(), self.char_code.clone(), crate::paint::BrushUi::Brush);
+
+        if matches!(self.brush_mode, BrushMode::DodgeBurn) {
+            self.show_ramp_ui(ui, editor_opt);
+        }
+
+        if matches!(self.brush_mode, BrushMode::Spray) {
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "tool-spray-density"));
+                ui.add(egui::Slider::new(&mut self.spray_density, 0.01..=1.0).show_value(false));
+            });
+            ui.horizontal(|ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "tool-spray-falloff"));
+                ui.selectable_value(
+                    &mut self.spray_falloff,
+                    SprayFalloff::Flat,
+                    fl!(crate::LANGUAGE_LOADER, "tool-spray-falloff-flat"),
+                );
+                ui.selectable_value(
+                    &mut self.spray_falloff,
+                    SprayFalloff::Linear,
+                    fl!(crate::LANGUAGE_LOADER, "tool-spray-falloff-linear"),
+                );
+                ui.selectable_value(
+                    &mut self.spray_falloff,
+                    SprayFalloff::Smooth,
+                    fl!(crate::LANGUAGE_LOADER, "tool-spray-falloff-smooth"),
+                );
+            });
+            ui.radio_value(&mut self.spray_step, SprayStep::Shade, fl!(crate::LANGUAGE_LOADER, "tool-spray-step-shade"));
+            ui.radio_value(&mut self.spray_step, SprayStep::Color, fl!(crate::LANGUAGE_LOADER, "tool-spray-step-color"));
+        }
 
         unsafe {
             if CUSTOM_BRUSH.is_some() {
@@ -186,7 +464,24 @@ impl Tool for BrushTool {
     }
 
     fn handle_hover(&mut self, _ui: &egui::Ui, response: egui::Response, editor: &mut AnsiEditor, cur: Position, cur_abs: Position) -> egui::Response {
-        if matches!(self.brush_mode, BrushMode::Custom) {
+        if matches!(self.brush_mode, BrushMode::Spray) {
+            let r = self.size / 2;
+            if self.cur_pos != cur {
+                self.cur_pos = cur;
+                let lock = &mut editor.buffer_view.lock();
+                let get_tool_overlay_mask_mut = lock.get_edit_state_mut().get_tool_overlay_mask_mut();
+                get_tool_overlay_mask_mut.clear();
+                let radius = self.size as f32 / 2.0;
+                for y in -r..=r {
+                    for x in -r..=r {
+                        if ((x * x + y * y) as f32).sqrt() <= radius {
+                            get_tool_overlay_mask_mut.set_is_selected(cur_abs + Position::new(x, y), true);
+                        }
+                    }
+                }
+                lock.get_edit_state_mut().set_is_buffer_dirty();
+            }
+        } else if matches!(self.brush_mode, BrushMode::Custom) {
             editor.clear_overlay_layer();
             let lock = &mut editor.buffer_view.lock();
             if let Some(layer) = lock.get_edit_state_mut().get_overlay_layer() {
@@ -204,18 +499,14 @@ impl Tool for BrushTool {
                 }
             }
         } else {
-            let mid = Position::new(-(self.size / 2), -(self.size / 2));
-
-            if self.cur_pos != cur + mid {
-                self.cur_pos = cur + mid;
+            if self.cur_pos != cur {
+                self.cur_pos = cur;
+                let footprint = self.shape.get_footprint(self.size, get_cell_aspect(editor));
                 let lock = &mut editor.buffer_view.lock();
                 let get_tool_overlay_mask_mut = lock.get_edit_state_mut().get_tool_overlay_mask_mut();
                 get_tool_overlay_mask_mut.clear();
-                for y in 0..self.size {
-                    for x in 0..self.size {
-                        let pos = cur_abs + Position::new(x, y) + mid;
-                        get_tool_overlay_mask_mut.set_is_selected(pos, true);
-                    }
+                for cell in get_footprint_outline(&footprint) {
+                    get_tool_overlay_mask_mut.set_is_selected(cur_abs + cell, true);
                 }
                 lock.get_edit_state_mut().set_is_buffer_dirty();
             }
@@ -227,7 +518,7 @@ impl Tool for BrushTool {
     fn handle_click(&mut self, editor: &mut AnsiEditor, button: i32, pos: Position, _pos_abs: Position, _response: &Response) -> Option<Message> {
         if button == 1 {
             let _op: AtomicUndoGuard = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-paint-brush"));
-
+            self.visited.clear();
             self.paint_brush(editor, pos);
         }
         None
@@ -240,6 +531,7 @@ impl Tool for BrushTool {
 
     fn handle_drag_begin(&mut self, editor: &mut AnsiEditor, _response: &egui::Response) -> Event {
         self.undo_op = Some(editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-paint-brush")));
+        self.visited.clear();
         self.paint_brush(editor, editor.drag_pos.cur);
         Event::None
     }
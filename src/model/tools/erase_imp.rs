@@ -3,7 +3,10 @@ use i18n_embed_fl::fl;
 use icy_engine::{editor::AtomicUndoGuard, AttributedChar, TextAttribute};
 use icy_engine_egui::TerminalCalc;
 
-use crate::{AnsiEditor, Event, Message};
+use crate::{
+    paint::{get_cell_aspect, get_footprint_outline, BrushShape},
+    AnsiEditor, Event, Message,
+};
 
 use super::{Position, Tool};
 
@@ -15,6 +18,7 @@ pub enum EraseType {
 
 pub struct EraseTool {
     size: i32,
+    shape: BrushShape,
     brush_type: EraseType,
     undo_op: Option<AtomicUndoGuard>,
     cur_pos: Position,
@@ -24,6 +28,7 @@ impl Default for EraseTool {
     fn default() -> Self {
         Self {
             size: 3,
+            shape: BrushShape::Square,
             brush_type: crate::model::erase_imp::EraseType::Solid,
             undo_op: None,
             cur_pos: Position::default(),
@@ -33,9 +38,6 @@ impl Default for EraseTool {
 
 impl EraseTool {
     fn eraser(&self, editor: &mut AnsiEditor, pos: Position) {
-        let mid = Position::new(-(self.size / 2), -(self.size / 2));
-
-        let center = pos + mid;
         let gradient = ['\u{00DB}', '\u{00B2}', '\u{00B1}', '\u{00B0}', ' '];
         let use_selection = editor.buffer_view.lock().get_edit_state().is_something_selected();
         let offset = if let Some(layer) = editor.buffer_view.lock().get_edit_state().get_cur_layer() {
@@ -44,42 +46,40 @@ impl EraseTool {
             Position::default()
         };
         editor.buffer_view.lock().get_edit_state_mut().set_is_buffer_dirty();
-        for y in 0..self.size {
-            for x in 0..self.size {
-                let pos = center + Position::new(x, y);
-                if use_selection && !editor.buffer_view.lock().get_edit_state().get_is_selected(pos + offset) {
-                    continue;
-                }
-                match self.brush_type {
-                    EraseType::Shade => {
-                        let ch = editor.get_char_from_cur_layer(pos);
-
-                        let mut attribute = ch.attribute;
-
-                        let mut char_code = gradient[0];
-                        let mut found = false;
-                        if ch.ch == gradient[gradient.len() - 1] {
-                            char_code = gradient[gradient.len() - 1];
-                            attribute = TextAttribute::default();
-                            found = true;
-                        } else {
-                            for i in 0..gradient.len() - 1 {
-                                if ch.ch == gradient[i] {
-                                    char_code = gradient[i + 1];
-                                    found = true;
-                                    break;
-                                }
+        for cell in self.shape.get_footprint(self.size, get_cell_aspect(editor)) {
+            let pos = pos + cell;
+            if use_selection && !editor.buffer_view.lock().get_edit_state().get_is_selected(pos + offset) {
+                continue;
+            }
+            match self.brush_type {
+                EraseType::Shade => {
+                    let ch = editor.get_char_from_cur_layer(pos);
+
+                    let mut attribute = ch.attribute;
+
+                    let mut char_code = gradient[0];
+                    let mut found = false;
+                    if ch.ch == gradient[gradient.len() - 1] {
+                        char_code = gradient[gradient.len() - 1];
+                        attribute = TextAttribute::default();
+                        found = true;
+                    } else {
+                        for i in 0..gradient.len() - 1 {
+                            if ch.ch == gradient[i] {
+                                char_code = gradient[i + 1];
+                                found = true;
+                                break;
                             }
                         }
-
-                        if found {
-                            editor.set_char(pos, AttributedChar::new(char_code, attribute));
-                        }
                     }
-                    EraseType::Solid => {
-                        editor.set_char(pos, AttributedChar::invisible());
+
+                    if found {
+                        editor.set_char(pos, AttributedChar::new(char_code, attribute));
                     }
                 }
+                EraseType::Solid => {
+                    editor.set_char(pos, AttributedChar::invisible());
+                }
             }
         }
     }
@@ -101,11 +101,12 @@ impl Tool for EraseTool {
         false
     }
 
-    fn show_ui(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui, _editor_opt: Option<&mut AnsiEditor>) -> Option<Message> {
+    fn show_ui(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui, editor_opt: Option<&mut AnsiEditor>) -> Option<Message> {
         ui.horizontal(|ui| {
             ui.label(fl!(crate::LANGUAGE_LOADER, "tool-size-label"));
             ui.add(egui::DragValue::new(&mut self.size).clamp_range(1..=20).speed(1));
         });
+        self.shape.show_ui(ui, editor_opt);
         ui.radio_value(&mut self.brush_type, EraseType::Solid, fl!(crate::LANGUAGE_LOADER, "tool-solid"));
         ui.radio_value(&mut self.brush_type, EraseType::Shade, fl!(crate::LANGUAGE_LOADER, "tool-shade"));
         None
@@ -122,17 +123,14 @@ impl Tool for EraseTool {
     }
 
     fn handle_hover(&mut self, _ui: &egui::Ui, response: egui::Response, editor: &mut AnsiEditor, cur: Position, cur_abs: Position) -> egui::Response {
-        let mid: Position = Position::new(-(self.size / 2), -(self.size / 2));
-        if self.cur_pos != cur + mid {
-            self.cur_pos = cur + mid;
+        if self.cur_pos != cur {
+            self.cur_pos = cur;
+            let footprint = self.shape.get_footprint(self.size, get_cell_aspect(editor));
             let lock = &mut editor.buffer_view.lock();
             let get_tool_overlay_mask_mut = lock.get_edit_state_mut().get_tool_overlay_mask_mut();
             get_tool_overlay_mask_mut.clear();
-            for y in 0..self.size {
-                for x in 0..self.size {
-                    let pos = cur_abs + Position::new(x, y) + mid;
-                    get_tool_overlay_mask_mut.set_is_selected(pos, true);
-                }
+            for cell in get_footprint_outline(&footprint) {
+                get_tool_overlay_mask_mut.set_is_selected(cur_abs + cell, true);
             }
             lock.get_edit_state_mut().set_is_buffer_dirty();
         }
@@ -41,7 +41,6 @@ struct FillOperation {
     use_selection: bool,
     base_char: AttributedChar,
     new_char: AttributedChar,
-    visited: HashSet<Position>,
 }
 
 impl FillOperation {
@@ -65,57 +64,70 @@ impl FillOperation {
             offset,
             new_char: new_ch,
             use_exact_matching: fill_tool.use_exact_matching,
-            visited: HashSet::new(),
         }
     }
 
-    pub fn fill(&mut self, editor: &mut AnsiEditor, pos: Position) {
-        let mut pos_stack = vec![pos];
-
-        while let Some(pos) = pos_stack.pop() {
-            if pos.x < 0 || pos.y < 0 || pos.x >= self.size.width || pos.y >= self.size.height || !self.visited.insert(pos) {
-                continue;
-            }
+    pub fn fill(&self, editor: &mut AnsiEditor, pos: Position) {
+        flood_fill(self.size, pos, |pos| self.fill_char(editor, pos));
+    }
 
-            if !self.use_selection || editor.buffer_view.lock().get_edit_state().get_is_selected(pos + self.offset) {
-                let cur_char = editor.buffer_view.lock().get_edit_state().get_cur_layer().unwrap().get_char(pos);
+    fn fill_char(&self, editor: &mut AnsiEditor, pos: Position) -> bool {
+        if !self.use_selection || editor.buffer_view.lock().get_edit_state().get_is_selected(pos + self.offset) {
+            let cur_char = editor.buffer_view.lock().get_edit_state().get_cur_layer().unwrap().get_char(pos);
 
-                let mut repl_ch = cur_char;
+            let mut repl_ch = cur_char;
 
-                match &self.fill_type {
-                    BrushMode::Char(_) => {
-                        if self.use_exact_matching && cur_char != self.base_char || !self.use_exact_matching && cur_char.ch != self.base_char.ch {
-                            continue;
-                        }
-                        repl_ch.ch = self.new_char.ch;
-                        repl_ch.set_font_page(self.new_char.get_font_page());
+            match &self.fill_type {
+                BrushMode::Char(_) => {
+                    if self.use_exact_matching && cur_char != self.base_char || !self.use_exact_matching && cur_char.ch != self.base_char.ch {
+                        return false;
                     }
-                    BrushMode::Colorize => {
-                        if self.use_exact_matching && cur_char != self.base_char || !self.use_exact_matching && cur_char.attribute != self.base_char.attribute {
-                            continue;
-                        }
-                    }
-                    _ => {}
-                }
-                if self.color_mode.use_fore() {
-                    repl_ch.attribute.set_foreground(self.new_char.attribute.get_foreground());
-                    repl_ch.attribute.set_is_bold(self.new_char.attribute.is_bold());
+                    repl_ch.ch = self.new_char.ch;
+                    repl_ch.set_font_page(self.new_char.get_font_page());
                 }
-
-                if self.color_mode.use_back() {
-                    repl_ch.attribute.set_background(self.new_char.attribute.get_background());
+                BrushMode::Colorize => {
+                    if self.use_exact_matching && cur_char != self.base_char || !self.use_exact_matching && cur_char.attribute != self.base_char.attribute {
+                        return false;
+                    }
                 }
+                _ => {}
+            }
+            if self.color_mode.use_fore() {
+                repl_ch.attribute.set_foreground(self.new_char.attribute.get_foreground());
+                repl_ch.attribute.set_is_bold(self.new_char.attribute.is_bold());
+            }
 
-                repl_ch.set_font_page(editor.buffer_view.lock().get_caret().get_attribute().get_font_page());
-                repl_ch.attribute.attr &= !icy_engine::attribute::INVISIBLE;
-                editor.set_char(pos, repl_ch);
+            if self.color_mode.use_back() {
+                repl_ch.attribute.set_background(self.new_char.attribute.get_background());
             }
 
-            pos_stack.push(pos + Position::new(-1, 0));
-            pos_stack.push(pos + Position::new(1, 0));
-            pos_stack.push(pos + Position::new(0, -1));
-            pos_stack.push(pos + Position::new(0, 1));
+            repl_ch.set_font_page(editor.buffer_view.lock().get_caret().get_attribute().get_font_page());
+            repl_ch.attribute.attr &= !icy_engine::attribute::INVISIBLE;
+            editor.set_char(pos, repl_ch);
         }
+        true
+    }
+}
+
+/// Visits the 4-connected region around `pos` inside of `size`. `visit` returns true
+/// if the flood should spread from that cell to its neighbours.
+pub fn flood_fill(size: Size, pos: Position, mut visit: impl FnMut(Position) -> bool) {
+    let mut visited = HashSet::new();
+    let mut pos_stack = vec![pos];
+
+    while let Some(pos) = pos_stack.pop() {
+        if pos.x < 0 || pos.y < 0 || pos.x >= size.width || pos.y >= size.height || !visited.insert(pos) {
+            continue;
+        }
+
+        if !visit(pos) {
+            continue;
+        }
+
+        pos_stack.push(pos + Position::new(-1, 0));
+        pos_stack.push(pos + Position::new(1, 0));
+        pos_stack.push(pos + Position::new(0, -1));
+        pos_stack.push(pos + Position::new(0, 1));
     }
 }
 
@@ -165,7 +177,7 @@ impl Tool for FillTool {
             };
             if self.color_mode.use_fore() || self.color_mode.use_back() || matches!(self.fill_type, BrushMode::Char(_)) {
                 let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-bucket-fill"));
-                let mut op = FillOperation::new(self, editor, ch, AttributedChar::new(*self.char_code.borrow(), attr));
+                let op = FillOperation::new(self, editor, ch, AttributedChar::new(*self.char_code.borrow(), attr));
                 op.fill(editor, pos);
             }
         }
@@ -1,21 +1,46 @@
 use std::{fs, io::Read, path::Path, sync::Arc, thread};
 
-use crate::{AnsiEditor, Message, Settings};
+use crate::{AnsiEditor, Message, Settings, TdfFontLayout};
 
-use super::{Event, MKey, MModifiers, Position, Tool};
+use super::{text_box_imp::TextAlignment, Event, MKey, MModifiers, Position, Tool};
 use eframe::{
     egui::{self, Button, RichText},
     epaint::{FontFamily, FontId},
 };
 use egui::mutex::Mutex;
 use i18n_embed_fl::fl;
-use icy_engine::{editor::OperationType, Size, TextPane, TheDrawFont};
+use icy_engine::{
+    editor::{EditState, OperationType},
+    Buffer, Size, TextPane, TheDrawFont,
+};
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use walkdir::{DirEntry, WalkDir};
 pub struct FontTool {
     pub selected_font: Arc<Mutex<i32>>,
     pub fonts: Arc<Mutex<Vec<TheDrawFont>>>,
     pub sizes: Vec<Size>,
+
+    last_char: Option<u8>,
+    kerning_pair: String,
+    text: String,
+    alignment: TextAlignment,
+    /// The layout settings changed but weren't saved yet, they're saved when a drag or text edit ends.
+    layout_changed: bool,
+}
+
+impl Default for FontTool {
+    fn default() -> Self {
+        Self {
+            selected_font: Arc::new(Mutex::new(0)),
+            fonts: Arc::new(Mutex::new(Vec::new())),
+            sizes: Vec::new(),
+            last_char: None,
+            kerning_pair: String::new(),
+            text: String::new(),
+            alignment: TextAlignment::Left,
+            layout_changed: false,
+        }
+    }
 }
 
 impl FontTool {
@@ -43,6 +68,175 @@ impl FontTool {
             self.fonts = Arc::new(Mutex::new(load_fonts(tdf_dir.as_path())));
         }
     }
+
+    fn show_layout_ui(&mut self, ui: &mut egui::Ui, font_name: &str) {
+        let mut layout = Settings::get_tdf_font_layout(font_name);
+        let mut changed = false;
+        // drag values change every frame while dragging, the settings are saved once the edit ends
+        let mut finished = false;
+
+        egui::Grid::new("font_tool_layout_grid").num_columns(2).show(ui, |ui| {
+            ui.label(fl!(crate::LANGUAGE_LOADER, "font_tool_letter_spacing"));
+            let r = ui.add(egui::DragValue::new(&mut layout.letter_spacing).clamp_range(-8..=16));
+            changed |= r.changed();
+            finished |= is_edit_finished(&r);
+            ui.end_row();
+
+            let mut override_space = layout.space_width.is_some();
+            if ui.checkbox(&mut override_space, fl!(crate::LANGUAGE_LOADER, "font_tool_space_width")).changed() {
+                layout.space_width = if override_space { Some(1) } else { None };
+                changed = true;
+                finished = true;
+            }
+            if let Some(space_width) = &mut layout.space_width {
+                let r = ui.add(egui::DragValue::new(space_width).clamp_range(0..=32));
+                changed |= r.changed();
+                finished |= is_edit_finished(&r);
+            }
+            ui.end_row();
+
+            ui.label(fl!(crate::LANGUAGE_LOADER, "font_tool_line_spacing"));
+            let r = ui.add(egui::DragValue::new(&mut layout.line_spacing).clamp_range(-8..=16));
+            changed |= r.changed();
+            finished |= is_edit_finished(&r);
+            ui.end_row();
+        });
+
+        ui.label(fl!(crate::LANGUAGE_LOADER, "font_tool_kerning_label"));
+        let mut remove = None;
+        for (i, (left, right, offset)) in layout.kerning.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("{left}{right}")).font(FontId::new(14.0, FontFamily::Monospace)));
+                let r = ui.add(egui::DragValue::new(offset).clamp_range(-16..=16));
+                changed |= r.changed();
+                finished |= is_edit_finished(&r);
+                if ui.button("🗑").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            layout.kerning.remove(i);
+            changed = true;
+            finished = true;
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.kerning_pair)
+                    .char_limit(2)
+                    .desired_width(40.0)
+                    .hint_text(fl!(crate::LANGUAGE_LOADER, "font_tool_kerning_hint")),
+            );
+            let pair: Vec<char> = self.kerning_pair.chars().collect();
+            let is_valid = pair.len() == 2 && pair.iter().all(char::is_ascii);
+            if ui
+                .add_enabled(is_valid, Button::new(fl!(crate::LANGUAGE_LOADER, "font_tool_kerning_add")))
+                .clicked()
+            {
+                layout.set_kerning(pair[0], pair[1], -1);
+                self.kerning_pair.clear();
+                changed = true;
+                finished = true;
+            }
+        });
+
+        if changed {
+            Settings::set_tdf_font_layout(layout);
+            self.layout_changed = true;
+        }
+        if finished && self.layout_changed {
+            self.layout_changed = false;
+            if let Err(err) = Settings::save() {
+                log::error!("Error saving settings: {}", err);
+            }
+        }
+    }
+
+    /// Renders the text line by line at the caret, the lines are aligned to the widest line.
+    fn render_text(&mut self, editor: &mut AnsiEditor) {
+        let selected_font = *self.selected_font.lock();
+        if selected_font < 0 {
+            return;
+        }
+        let Some(font) = self.fonts.lock().get(selected_font as usize).cloned() else {
+            return;
+        };
+        let layout = Settings::get_tdf_font_layout(&font.name);
+        let lines: Vec<(Vec<u8>, Vec<i32>, i32)> = self
+            .text
+            .lines()
+            .map(|line| {
+                let line: Vec<u8> = line.chars().filter(char::is_ascii).map(|ch| ch as u8).collect();
+                let (offsets, width) = layout_line(&font, &layout, &line);
+                (line, offsets, width)
+            })
+            .collect();
+        let text_width = lines.iter().map(|(_, _, width)| *width).max().unwrap_or_default();
+        let line_height = (font.get_font_height() + layout.line_spacing).max(1);
+        let start = editor.get_caret_position();
+
+        let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-render_text"));
+        let outline_style = if editor.outline_font_mode {
+            usize::MAX
+        } else {
+            Settings::get_font_outline_style()
+        };
+        editor.buffer_view.lock().get_edit_state_mut().set_outline_style(outline_style);
+
+        for (i, (line, offsets, width)) in lines.iter().enumerate() {
+            let x = start.x
+                + match self.alignment {
+                    TextAlignment::Left | TextAlignment::Justify => 0,
+                    TextAlignment::Center => (text_width - width) / 2,
+                    TextAlignment::Right => text_width - width,
+                };
+            let y = start.y + i as i32 * line_height;
+            for (ch, offset) in line.iter().zip(offsets) {
+                if *ch == b' ' && !font.has_char(*ch) {
+                    continue;
+                }
+                editor.set_caret_position(Position::new(x + offset, y));
+                if font.render(editor.buffer_view.lock().get_edit_state_mut(), *ch).is_none() {
+                    editor.type_key(*ch as char);
+                }
+            }
+        }
+        editor.set_caret_position(Position::new(start.x, start.y + lines.len() as i32 * line_height));
+        self.last_char = None;
+        self.sizes.clear();
+    }
+}
+
+/// Width of the rendered glyph, `None` if the font doesn't have it.
+fn get_glyph_width(font: &TheDrawFont, ch: u8) -> Option<i32> {
+    if !font.has_char(ch) {
+        return None;
+    }
+    let mut state = EditState::from_buffer(Buffer::new((100, 30)));
+    font.render(&mut state, ch).map(|size| size.width)
+}
+
+/// Returns the x offset of every character in the line and the width of the line.
+fn layout_line(font: &TheDrawFont, layout: &TdfFontLayout, line: &[u8]) -> (Vec<i32>, i32) {
+    let mut offsets = Vec::new();
+    let mut x = 0;
+    let mut width = 0;
+    let mut last_char = None;
+    for ch in line {
+        if let Some(last_char) = last_char {
+            x = (x + layout.get_kerning(last_char as char, *ch as char)).max(0);
+        }
+        offsets.push(x);
+        let (glyph_width, advance) = match (layout.space_width, get_glyph_width(font, *ch)) {
+            (Some(space_width), _) if *ch == b' ' => (0, space_width),
+            (_, Some(glyph_width)) => (glyph_width, glyph_width + font.spaces + layout.letter_spacing),
+            (_, None) => (1, 1),
+        };
+        width = width.max(x + glyph_width);
+        x += advance;
+        last_char = Some(*ch);
+    }
+    (offsets, width)
 }
 
 fn load_fonts(tdf_dir: &Path) -> Vec<TheDrawFont> {
@@ -149,7 +343,7 @@ impl Tool for FontTool {
         false
     }
 
-    fn show_ui(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui, _editor_opt: Option<&mut AnsiEditor>) -> Option<Message> {
+    fn show_ui(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui, editor_opt: Option<&mut AnsiEditor>) -> Option<Message> {
         let mut select = false;
         let font_count = self.fonts.lock().len();
         let selected_font = *self.selected_font.lock();
@@ -270,6 +464,33 @@ impl Tool for FontTool {
             }
         }
 
+        let font_name = if selected_font >= 0 {
+            self.fonts.lock().get(selected_font as usize).map(|font| font.name.clone())
+        } else {
+            None
+        };
+        if let Some(font_name) = font_name {
+            ui.add_space(16.0);
+            self.show_layout_ui(ui, &font_name);
+
+            ui.add_space(16.0);
+            ui.label(fl!(crate::LANGUAGE_LOADER, "font_tool_text_label"));
+            ui.horizontal(|ui| {
+                for alignment in [TextAlignment::Left, TextAlignment::Center, TextAlignment::Right] {
+                    ui.selectable_value(&mut self.alignment, alignment, alignment.get_label());
+                }
+            });
+            ui.add(egui::TextEdit::multiline(&mut self.text).desired_rows(4).desired_width(f32::INFINITY));
+            if let Some(editor) = editor_opt {
+                if ui
+                    .add_enabled(!self.text.is_empty(), Button::new(fl!(crate::LANGUAGE_LOADER, "font_tool_render_text")))
+                    .clicked()
+                {
+                    self.render_text(editor);
+                }
+            }
+        }
+
         if select {
             Some(Message::SelectFontDialog(self.fonts.clone(), self.selected_font.clone()))
         } else {
@@ -281,6 +502,7 @@ impl Tool for FontTool {
         if button == 1 {
             editor.set_caret_position(pos);
             editor.buffer_view.lock().clear_selection();
+            self.last_char = None;
         }
         None
     }
@@ -296,7 +518,9 @@ impl Tool for FontTool {
             return Event::None;
         }
         let font = &self.fonts.lock()[selected_font as usize];
+        let layout = Settings::get_tdf_font_layout(&font.name);
         let pos = editor.buffer_view.lock().get_caret().get_position();
+        let last_char = self.last_char.take();
 
         match key {
             MKey::Down => {
@@ -340,7 +564,7 @@ impl Tool for FontTool {
             }
 
             MKey::Return => {
-                editor.set_caret(0, pos.y + font.get_font_height());
+                editor.set_caret(0, pos.y + font.get_font_height() + layout.line_spacing);
                 /*
                 if let Some(size) = self.sizes.last() {
                     editor.set_caret(0,pos.y + size.height as i32);
@@ -416,10 +640,24 @@ impl Tool for FontTool {
                 editor.buffer_view.lock().get_edit_state_mut().set_outline_style(outline_style);
 
                 let _ = editor.buffer_view.lock().get_edit_state_mut().undo_caret_position();
+                self.last_char = Some(ch as u8);
+
+                if let (Some(space_width), b' ') = (layout.space_width, ch as u8) {
+                    editor.set_caret(c_pos.x + space_width, c_pos.y);
+                    let new_pos = editor.get_caret_position();
+                    self.sizes.push(Size::new(new_pos.x - c_pos.x, 1));
+                    return Event::None;
+                }
+
+                let kerning = last_char.map_or(0, |last_char| layout.get_kerning(last_char as char, ch as u8 as char));
+                if kerning != 0 {
+                    editor.set_caret(c_pos.x + kerning, c_pos.y);
+                }
+                let c_pos = editor.get_caret_position();
 
                 let opt_size: Option<Size> = font.render(editor.buffer_view.lock().get_edit_state_mut(), ch as u8);
                 if let Some(size) = opt_size {
-                    editor.set_caret(c_pos.x + size.width + font.spaces, c_pos.y);
+                    editor.set_caret(c_pos.x + size.width + font.spaces + layout.letter_spacing, c_pos.y);
                     let new_pos = editor.get_caret_position();
                     self.sizes.push(Size {
                         width: (new_pos.x - c_pos.x),
@@ -461,3 +699,8 @@ fn watch(path: &Path, fonts: &Arc<Mutex<Vec<TheDrawFont>>>) -> notify::Result<()
 
     Ok(())
 }
+
+/// A drag value edit ends when the drag is released or its text field loses the focus.
+fn is_edit_finished(response: &egui::Response) -> bool {
+    response.drag_released() || response.lost_focus()
+}
@@ -0,0 +1,193 @@
+//! Free transform of a layer. The transform works in pixel space where a cell is twice as high
+//! as it is wide, so rotations keep the shape of the art. Every target cell samples the source in
+//! its four quadrants and picks the closest shading or half block character; cells showing text
+//! characters are copied with nearest neighbour sampling instead.
+
+use icy_engine::{AttributedChar, BufferType, Layer, Position, Size, TextPane};
+
+/// Cell height in pixel space, relative to the cell width.
+const CELL_ASPECT: f32 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayerTransform {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    /// Skew angles in degrees.
+    pub skew_x: f32,
+    pub skew_y: f32,
+    /// Rotation angle in degrees, clockwise.
+    pub angle: f32,
+    /// Moves the transformed layer, in cells.
+    pub offset: Position,
+}
+
+impl Default for LayerTransform {
+    fn default() -> Self {
+        Self {
+            scale_x: 1.0,
+            scale_y: 1.0,
+            skew_x: 0.0,
+            skew_y: 0.0,
+            angle: 0.0,
+            offset: Position::default(),
+        }
+    }
+}
+
+pub struct TransformedLayer {
+    pub size: Size,
+    /// Offset of the transformed layer relative to the offset of the source layer.
+    pub offset: Position,
+    /// All cells of the transformed layer, line by line.
+    pub chars: Vec<AttributedChar>,
+}
+
+impl LayerTransform {
+    pub fn is_identity(&self) -> bool {
+        *self == LayerTransform::default()
+    }
+
+    fn get_matrix(&self) -> [f32; 4] {
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        let kx = self.skew_x.to_radians().tan();
+        let ky = self.skew_y.to_radians().tan();
+        // rotation * skew * scale
+        let m = [self.scale_x, kx * self.scale_y, ky * self.scale_x, self.scale_y];
+        [
+            cos * m[0] - sin * m[2],
+            cos * m[1] - sin * m[3],
+            sin * m[0] + cos * m[2],
+            sin * m[1] + cos * m[3],
+        ]
+    }
+
+    fn get_inverse_matrix(&self) -> Option<[f32; 4]> {
+        let m = self.get_matrix();
+        let det = m[0] * m[3] - m[1] * m[2];
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+        Some([m[3] / det, -m[1] / det, -m[2] / det, m[0] / det])
+    }
+
+    pub fn apply(&self, layer: &Layer, buffer_type: BufferType) -> Option<TransformedLayer> {
+        let size = layer.get_size();
+        if size.width <= 0 || size.height <= 0 {
+            return None;
+        }
+        let m = self.get_matrix();
+        let inv = self.get_inverse_matrix()?;
+        let center = (size.width as f32 / 2.0, size.height as f32 * CELL_ASPECT / 2.0);
+
+        let mut min = (f32::MAX, f32::MAX);
+        let mut max = (f32::MIN, f32::MIN);
+        for (x, y) in [
+            (0.0, 0.0),
+            (size.width as f32, 0.0),
+            (0.0, size.height as f32),
+            (size.width as f32, size.height as f32),
+        ] {
+            let (px, py) = (x - center.0, y * CELL_ASPECT - center.1);
+            let tx = m[0] * px + m[1] * py + center.0;
+            let ty = (m[2] * px + m[3] * py + center.1) / CELL_ASPECT;
+            min = (min.0.min(tx), min.1.min(ty));
+            max = (max.0.max(tx), max.1.max(ty));
+        }
+        let offset = Position::new(min.0.round() as i32, min.1.round() as i32);
+        let new_size = Size::new(((max.0 - min.0).round() as i32).max(1), ((max.1 - min.1).round() as i32).max(1));
+
+        let use_blocks = matches!(buffer_type, BufferType::CP437 | BufferType::Unicode);
+        let sample = |x: f32, y: f32| -> Option<(AttributedChar, f32, f32)> {
+            // (x, y) is in target cell coordinates
+            let (px, py) = (x + offset.x as f32 - center.0, (y + offset.y as f32) * CELL_ASPECT - center.1);
+            let sx = inv[0] * px + inv[1] * py + center.0;
+            let sy = (inv[2] * px + inv[3] * py + center.1) / CELL_ASPECT;
+            if sx < 0.0 || sy < 0.0 || sx >= size.width as f32 || sy >= size.height as f32 {
+                return None;
+            }
+            let ch = layer.get_char(Position::new(sx as i32, sy as i32));
+            if ch.is_transparent() {
+                return None;
+            }
+            Some((ch, sx.fract(), sy.fract()))
+        };
+
+        let mut chars = Vec::new();
+        for y in 0..new_size.height {
+            for x in 0..new_size.width {
+                let center_sample = sample(x as f32 + 0.5, y as f32 + 0.5);
+                let quadrants = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)].map(|(qx, qy)| sample(x as f32 + qx, y as f32 + qy));
+                let Some((base, _, _)) = center_sample.or_else(|| quadrants.iter().flatten().next().copied()) else {
+                    chars.push(AttributedChar::invisible());
+                    continue;
+                };
+
+                if !use_blocks || get_coverage(base.ch).is_none() {
+                    chars.push(base);
+                    continue;
+                }
+
+                let mut coverage = [0.0; 4];
+                for (i, (ch, fx, fy)) in quadrants.iter().enumerate().filter_map(|(i, q)| q.map(|q| (i, q))) {
+                    coverage[i] = match get_coverage(ch.ch) {
+                        Some(c) => c[usize::from(fx >= 0.5) + 2 * usize::from(fy >= 0.5)],
+                        None => 0.5,
+                    };
+                    // half block art often swaps foreground and background between cells
+                    if ch.attribute.get_foreground() == base.attribute.get_background() && ch.attribute.get_background() == base.attribute.get_foreground() {
+                        coverage[i] = 1.0 - coverage[i];
+                    }
+                }
+                let mut result = base;
+                result.ch = get_closest_block(coverage, buffer_type);
+                chars.push(result);
+            }
+        }
+
+        Some(TransformedLayer {
+            size: new_size,
+            offset: offset + self.offset,
+            chars,
+        })
+    }
+}
+
+/// Foreground coverage of the quadrants top left, top right, bottom left, bottom right.
+const BLOCKS: [(char, char, [f32; 4]); 9] = [
+    (' ', ' ', [0.0, 0.0, 0.0, 0.0]),
+    ('\u{B0}', '░', [0.25, 0.25, 0.25, 0.25]),
+    ('\u{B1}', '▒', [0.5, 0.5, 0.5, 0.5]),
+    ('\u{B2}', '▓', [0.75, 0.75, 0.75, 0.75]),
+    ('\u{DB}', '█', [1.0, 1.0, 1.0, 1.0]),
+    ('\u{DF}', '▀', [1.0, 1.0, 0.0, 0.0]),
+    ('\u{DC}', '▄', [0.0, 0.0, 1.0, 1.0]),
+    ('\u{DD}', '▌', [1.0, 0.0, 1.0, 0.0]),
+    ('\u{DE}', '▐', [0.0, 1.0, 0.0, 1.0]),
+];
+
+fn get_coverage(ch: char) -> Option<[f32; 4]> {
+    if ch == '\0' || ch == '\u{FF}' {
+        return Some(BLOCKS[0].2);
+    }
+    BLOCKS
+        .iter()
+        .find(|(cp437, unicode, _)| *cp437 == ch || *unicode == ch)
+        .map(|(_, _, coverage)| *coverage)
+}
+
+fn get_closest_block(coverage: [f32; 4], buffer_type: BufferType) -> char {
+    let mut best = &BLOCKS[0];
+    let mut best_error = f32::MAX;
+    for block in &BLOCKS {
+        let error: f32 = block.2.iter().zip(coverage.iter()).map(|(a, b)| (a - b) * (a - b)).sum();
+        if error < best_error {
+            best_error = error;
+            best = block;
+        }
+    }
+    if matches!(buffer_type, BufferType::Unicode) {
+        best.1
+    } else {
+        best.0
+    }
+}
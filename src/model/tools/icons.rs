@@ -17,5 +17,6 @@ lazy_static::lazy_static! {
     pub static ref RECTANGLE_FILLED_SVG: Image<'static> = Image::new(egui::include_image!("../../../data/icons/rectangle_filled.svg"));
     pub static ref RECTANGLE_OUTLINE_SVG: Image<'static> = Image::new(egui::include_image!("../../../data/icons/rectangle_outline.svg"));
     pub static ref SELECT_SVG: Image<'static> = Image::new(egui::include_image!("../../../data/icons/select.svg"));
+    pub static ref TEXT_BOX_SVG: Image<'static> = Image::new(egui::include_image!("../../../data/icons/text_box.svg"));
     pub static ref FLIP_TOOL_SVG: Image<'static> = Image::new(egui::include_image!("../../../data/icons/flip_tool.svg"));
 }
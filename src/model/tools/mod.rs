@@ -8,12 +8,14 @@ pub mod erase_imp;
 pub mod fill_imp;
 pub mod flip_imp;
 pub mod font_imp;
+pub mod free_transform;
 pub mod line_imp;
 pub mod move_layer_imp;
 pub mod paste_tool;
 pub mod pencil_imp;
 pub mod pipette_imp;
 pub mod select_imp;
+pub mod text_box_imp;
 
 mod icons;
 
@@ -123,6 +125,10 @@ pub trait Tool {
         Event::None
     }
 
+    fn handle_paste(&mut self, _editor: &mut AnsiEditor, _text: &str) -> bool {
+        false
+    }
+
     fn handle_click(&mut self, _editor: &mut AnsiEditor, _button: i32, _pos: Position, _pos_abs: Position, _response: &Response) -> Option<Message> {
         None
     }
@@ -1,7 +1,12 @@
-use super::{move_layer_imp::get_layer_offset_text, Event, MKey, Position, Tool};
+use super::{
+    free_transform::{LayerTransform, TransformedLayer},
+    move_layer_imp::get_layer_offset_text,
+    Event, MKey, Position, Tool,
+};
 use crate::{to_message, AnsiEditor, Message};
 use eframe::egui::{self, Key};
 use i18n_embed_fl::fl;
+use icy_engine::{EngineResult, Layer, TextPane};
 use icy_engine_egui::TerminalCalc;
 
 #[derive(Default)]
@@ -11,6 +16,25 @@ pub struct PasteTool {
     drag_offset: Position,
     last_tool: usize,
     closed: bool,
+    start_transform: bool,
+    transform: Option<TransformState>,
+}
+
+struct TransformState {
+    original: Layer,
+    transform: LayerTransform,
+    drag: TransformDrag,
+    drag_start: LayerTransform,
+    drag_start_size: (i32, i32),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TransformDrag {
+    Move,
+    Right,
+    Bottom,
+    BottomRight,
+    Rotate,
 }
 
 impl PasteTool {
@@ -20,6 +44,219 @@ impl PasteTool {
             ..Default::default()
         }
     }
+
+    /// Creates a paste tool that starts in free transform mode.
+    pub(crate) fn new_transform(selected_tool: usize) -> Self {
+        Self {
+            last_tool: selected_tool,
+            start_transform: true,
+            ..Default::default()
+        }
+    }
+
+    fn begin_transform(&mut self, editor: &AnsiEditor) {
+        if let Some(layer) = editor.buffer_view.lock().get_edit_state().get_cur_layer() {
+            self.transform = Some(TransformState {
+                original: layer.clone(),
+                transform: LayerTransform::default(),
+                drag: TransformDrag::Move,
+                drag_start: LayerTransform::default(),
+                drag_start_size: (layer.get_width(), layer.get_height()),
+            });
+        }
+    }
+
+    fn get_transformed_layer(&self, editor: &AnsiEditor) -> Option<TransformedLayer> {
+        let state = self.transform.as_ref()?;
+        let buffer_type = editor.buffer_view.lock().get_buffer().buffer_type;
+        state.transform.apply(&state.original, buffer_type)
+    }
+
+    /// Shows the transformed layer without creating undo operations.
+    fn update_transform_preview(&self, editor: &mut AnsiEditor) {
+        let Some(state) = &self.transform else {
+            return;
+        };
+        let transformed = self.get_transformed_layer(editor);
+        let mut lock = editor.buffer_view.lock();
+        if let Some(layer) = lock.get_edit_state_mut().get_cur_layer_mut() {
+            match transformed {
+                Some(transformed) => {
+                    layer.lines.clear();
+                    layer.set_size(transformed.size);
+                    for (i, ch) in transformed.chars.iter().enumerate() {
+                        let pos = Position::new(i as i32 % transformed.size.width, i as i32 / transformed.size.width);
+                        layer.set_char(pos, *ch);
+                    }
+                    layer.set_offset(state.original.get_offset() + transformed.offset);
+                }
+                None => *layer = state.original.clone(),
+            }
+        }
+        lock.get_edit_state_mut().set_is_buffer_dirty();
+    }
+
+    fn cancel_transform(&mut self, editor: &mut AnsiEditor) {
+        if let Some(state) = self.transform.take() {
+            let mut lock = editor.buffer_view.lock();
+            if let Some(layer) = lock.get_edit_state_mut().get_cur_layer_mut() {
+                *layer = state.original;
+            }
+            lock.get_edit_state_mut().set_is_buffer_dirty();
+        }
+    }
+
+    fn apply_transform(&mut self, editor: &mut AnsiEditor) -> Option<Message> {
+        let transformed = self.get_transformed_layer(editor);
+        let offset = self.transform.as_ref()?.original.get_offset();
+        let is_identity = self.transform.as_ref()?.transform.is_identity();
+        self.cancel_transform(editor);
+        if is_identity {
+            return None;
+        }
+        let transformed = transformed?;
+        to_message(apply_transformed_layer(editor, &transformed, offset))
+    }
+
+    fn show_transform_ui(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, editor: &mut AnsiEditor) -> Option<Message> {
+        let Some(state) = &mut self.transform else {
+            return None;
+        };
+        let mut transform = state.transform;
+        ui.label(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-description"));
+        ui.add_space(8.0);
+        egui::Grid::new("paste_mode_transform_grid").num_columns(2).spacing([4.0, 4.0]).show(ui, |ui| {
+            let mut scale_x = transform.scale_x * 100.0;
+            let mut scale_y = transform.scale_y * 100.0;
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-scale_x"));
+            });
+            if ui.add(egui::DragValue::new(&mut scale_x).clamp_range(1.0..=1000.0).suffix("%")).changed() {
+                transform.scale_x = scale_x / 100.0;
+            }
+            ui.end_row();
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-scale_y"));
+            });
+            if ui.add(egui::DragValue::new(&mut scale_y).clamp_range(1.0..=1000.0).suffix("%")).changed() {
+                transform.scale_y = scale_y / 100.0;
+            }
+            ui.end_row();
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-skew_x"));
+            });
+            ui.add(egui::DragValue::new(&mut transform.skew_x).clamp_range(-80.0..=80.0).suffix("°"));
+            ui.end_row();
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-skew_y"));
+            });
+            ui.add(egui::DragValue::new(&mut transform.skew_y).clamp_range(-80.0..=80.0).suffix("°"));
+            ui.end_row();
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-angle"));
+            });
+            ui.add(egui::DragValue::new(&mut transform.angle).clamp_range(-360.0..=360.0).suffix("°"));
+            ui.end_row();
+        });
+        ui.add_space(8.0);
+        let mut result = None;
+        ui.horizontal(|ui| {
+            if ui.button(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-reset")).clicked() {
+                transform = LayerTransform::default();
+            }
+            if ui.button(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-apply")).clicked() || ctx.input(|i| i.key_pressed(Key::Enter)) {
+                result = Some(true);
+            }
+            if ui.button(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform-cancel")).clicked() || ctx.input(|i| i.key_pressed(Key::Escape)) {
+                result = Some(false);
+            }
+        });
+
+        match result {
+            Some(true) => return self.apply_transform(editor),
+            Some(false) => self.cancel_transform(editor),
+            None => {
+                if transform != state.transform {
+                    state.transform = transform;
+                    self.update_transform_preview(editor);
+                }
+            }
+        }
+        None
+    }
+
+    fn get_transform_drag(editor: &AnsiEditor, pos: Position) -> TransformDrag {
+        let Some(layer) = editor
+            .buffer_view
+            .lock()
+            .get_edit_state()
+            .get_cur_layer()
+            .map(|l| (l.get_offset(), l.get_size()))
+        else {
+            return TransformDrag::Move;
+        };
+        let (offset, size) = layer;
+        let right = offset.x + size.width - 1;
+        let bottom = offset.y + size.height - 1;
+        let inside = |x: i32, y: i32| x >= offset.x - 1 && y >= offset.y - 1 && x <= right + 1 && y <= bottom + 1;
+        if !inside(pos.x, pos.y) {
+            return TransformDrag::Rotate;
+        }
+        let at_right = pos.x >= right;
+        let at_bottom = pos.y >= bottom;
+        match (at_right, at_bottom) {
+            (true, true) => TransformDrag::BottomRight,
+            (true, false) => TransformDrag::Right,
+            (false, true) => TransformDrag::Bottom,
+            (false, false) => TransformDrag::Move,
+        }
+    }
+
+    fn handle_transform_drag(&mut self, editor: &mut AnsiEditor) {
+        let Some(state) = &mut self.transform else {
+            return;
+        };
+        let start = editor.drag_pos.start_abs;
+        let cur = editor.drag_pos.cur_abs;
+        let (width, height) = state.drag_start_size;
+        match state.drag {
+            TransformDrag::Move => {
+                state.transform.offset = state.drag_start.offset + cur - start;
+            }
+            TransformDrag::Right | TransformDrag::Bottom | TransformDrag::BottomRight => {
+                if state.drag != TransformDrag::Bottom {
+                    state.transform.scale_x = state.drag_start.scale_x * ((width + cur.x - start.x).max(1) as f32 / width.max(1) as f32);
+                }
+                if state.drag != TransformDrag::Right {
+                    state.transform.scale_y = state.drag_start.scale_y * ((height + cur.y - start.y).max(1) as f32 / height.max(1) as f32);
+                }
+            }
+            TransformDrag::Rotate => {
+                let center = state.original.get_offset() + state.transform.offset;
+                let cx = center.x as f32 + state.original.get_width() as f32 / 2.0;
+                let cy = center.y as f32 + state.original.get_height() as f32 / 2.0;
+                // cells are twice as high as wide
+                let a1 = (2.0 * (start.y as f32 - cy)).atan2(start.x as f32 - cx);
+                let a2 = (2.0 * (cur.y as f32 - cy)).atan2(cur.x as f32 - cx);
+                state.transform.angle = (state.drag_start.angle + (a2 - a1).to_degrees()).round();
+            }
+        }
+        self.update_transform_preview(editor);
+    }
+}
+
+/// Replaces the floating layer with the transformed layer as one undo operation.
+fn apply_transformed_layer(editor: &mut AnsiEditor, transformed: &TransformedLayer, offset: Position) -> EngineResult<()> {
+    let layer = editor.get_cur_layer_index()?;
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-free-transform"));
+    let mut lock = editor.buffer_view.lock();
+    let edit_state = lock.get_edit_state_mut();
+    edit_state.set_layer_size(layer, (transformed.size.width, transformed.size.height))?;
+    for (i, ch) in transformed.chars.iter().enumerate() {
+        let pos = Position::new(i as i32 % transformed.size.width, i as i32 / transformed.size.width);
+        edit_state.set_char(pos, *ch)?;
+    }
+    edit_state.move_layer(offset + transformed.offset)
 }
 
 impl Tool for PasteTool {
@@ -53,18 +290,26 @@ impl Tool for PasteTool {
 
     fn show_ui(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, editor_opt: Option<&mut AnsiEditor>) -> Option<Message> {
         let mut result = None;
-        if let Some(editor) = editor_opt {
-            if let Some(layer) = editor.buffer_view.lock().get_edit_state().get_cur_layer() {
-                self.closed = !layer.role.is_paste();
-            }
-        } else {
+        let Some(editor) = editor_opt else {
             self.closed = true;
+            return Some(Message::SelectTool(self.last_tool));
+        };
+        if let Some(layer) = editor.buffer_view.lock().get_edit_state().get_cur_layer() {
+            self.closed = !layer.role.is_paste();
         }
 
         if self.closed {
             return Some(Message::SelectTool(self.last_tool));
         }
 
+        if self.start_transform {
+            self.start_transform = false;
+            self.begin_transform(editor);
+        }
+        if self.transform.is_some() {
+            return self.show_transform_ui(ctx, ui, editor);
+        }
+
         ui.label(fl!(crate::LANGUAGE_LOADER, "paste_mode-description"));
         ui.add_space(8.0);
         egui::Grid::new("paste_mode_grid").num_columns(2).spacing([4.0, 4.0]).show(ui, |ui| {
@@ -103,6 +348,13 @@ impl Tool for PasteTool {
                 result = Some(Message::MakeLayerTransparent);
             }
             ui.end_row();
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.strong("F -");
+            });
+            if ui.button(fl!(crate::LANGUAGE_LOADER, "paste_mode-transform")).clicked() || ui.input(|i| i.key_pressed(Key::F)) {
+                self.begin_transform(editor);
+            }
+            ui.end_row();
         });
 
         if ctx.input(|i| i.key_pressed(Key::Escape)) {
@@ -113,6 +365,22 @@ impl Tool for PasteTool {
 
     fn handle_drag_begin(&mut self, editor: &mut AnsiEditor, _response: &egui::Response) -> Event {
         self.drag_started = false;
+        if self.transform.is_some() {
+            let drag = Self::get_transform_drag(editor, editor.drag_pos.start_abs);
+            let size = editor
+                .buffer_view
+                .lock()
+                .get_edit_state()
+                .get_cur_layer()
+                .map(|l| (l.get_width(), l.get_height()))
+                .unwrap_or_default();
+            if let Some(state) = &mut self.transform {
+                state.drag = drag;
+                state.drag_start = state.transform;
+                state.drag_start_size = size;
+            }
+            return Event::None;
+        }
 
         if let Some(layer) = editor.buffer_view.lock().get_edit_state_mut().get_cur_layer_mut() {
             self.start_offset = layer.get_offset();
@@ -123,6 +391,10 @@ impl Tool for PasteTool {
     }
 
     fn handle_drag(&mut self, _ui: &egui::Ui, response: egui::Response, editor: &mut AnsiEditor, _calc: &TerminalCalc) -> egui::Response {
+        if self.transform.is_some() {
+            self.handle_transform_drag(editor);
+            return response;
+        }
         if !self.drag_started {
             return response;
         }
@@ -137,18 +409,31 @@ impl Tool for PasteTool {
         get_layer_offset_text(editor)
     }
 
-    fn handle_hover(&mut self, _ui: &egui::Ui, response: egui::Response, _editor: &mut AnsiEditor, _cur: Position, _cur_abs: Position) -> egui::Response {
+    fn handle_hover(&mut self, _ui: &egui::Ui, response: egui::Response, editor: &mut AnsiEditor, _cur: Position, cur_abs: Position) -> egui::Response {
+        if self.transform.is_some() {
+            let cursor = match Self::get_transform_drag(editor, cur_abs) {
+                TransformDrag::Move => egui::CursorIcon::Move,
+                TransformDrag::Right => egui::CursorIcon::ResizeHorizontal,
+                TransformDrag::Bottom => egui::CursorIcon::ResizeVertical,
+                TransformDrag::BottomRight => egui::CursorIcon::ResizeNwSe,
+                TransformDrag::Rotate => egui::CursorIcon::Crosshair,
+            };
+            return response.on_hover_cursor(cursor);
+        }
         response.on_hover_cursor(egui::CursorIcon::Move)
     }
 
     fn handle_drag_end(&mut self, editor: &mut AnsiEditor) -> Option<Message> {
-        if !self.drag_started {
+        if self.transform.is_some() || !self.drag_started {
             return None;
         }
         to_message(editor.buffer_view.lock().get_edit_state_mut().move_layer(self.drag_offset))
     }
 
     fn handle_key(&mut self, editor: &mut AnsiEditor, key: MKey, modifier: super::MModifiers) -> Event {
+        if self.transform.is_some() {
+            return Event::None;
+        }
         let offset = if let Some(layer) = editor.buffer_view.lock().get_edit_state_mut().get_cur_layer_mut() {
             layer.get_offset()
         } else {
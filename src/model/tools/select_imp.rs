@@ -1,11 +1,13 @@
+use std::collections::HashSet;
+
 use eframe::egui;
 use i18n_embed_fl::fl;
-use icy_engine::{editor::AtomicUndoGuard, AddType, Rectangle};
+use icy_engine::{editor::AtomicUndoGuard, AddType, AttributedChar, Palette, Rectangle, TextPane};
 use icy_engine_egui::TerminalCalc;
 
 use crate::{to_message, AnsiEditor, Message};
 
-use super::{Event, Position, Tool};
+use super::{fill_imp::flood_fill, Event, Position, Tool};
 
 #[derive(Default)]
 enum SelectionDrag {
@@ -31,14 +33,46 @@ enum SelectionMode {
     Attribute,
     Foreground,
     Background,
+    MagicWand,
 }
-enum SelectionModifier {
+
+impl SelectionMode {
+    fn is_match(self, palette: &Palette, tolerance: u8, ch: AttributedChar, cur_ch: AttributedChar) -> bool {
+        match self {
+            SelectionMode::Normal | SelectionMode::MagicWand => false,
+            SelectionMode::Character => ch.ch == cur_ch.ch,
+            SelectionMode::Attribute => {
+                is_color_match(palette, tolerance, ch.attribute.get_foreground(), cur_ch.attribute.get_foreground())
+                    && is_color_match(palette, tolerance, ch.attribute.get_background(), cur_ch.attribute.get_background())
+            }
+            SelectionMode::Foreground => is_color_match(palette, tolerance, ch.attribute.get_foreground(), cur_ch.attribute.get_foreground()),
+            SelectionMode::Background => is_color_match(palette, tolerance, ch.attribute.get_background(), cur_ch.attribute.get_background()),
+        }
+    }
+}
+
+fn is_color_match(palette: &Palette, tolerance: u8, a: u32, b: u32) -> bool {
+    if a == b {
+        return true;
+    }
+    if tolerance == 0 {
+        return false;
+    }
+    let (r1, g1, b1) = palette.get_color(a).get_rgb();
+    let (r2, g2, b2) = palette.get_color(b).get_rgb();
+    r1.abs_diff(r2).max(g1.abs_diff(g2)).max(b1.abs_diff(b2)) <= tolerance
+}
+
+/// How new cells are combined with the current selection.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SelectionModifier {
     Replace,
     Add,
     Remove,
+    Intersect,
 }
 impl SelectionModifier {
-    fn get_response(&self, ch: bool) -> Option<bool> {
+    pub fn get_response(&self, ch: bool) -> Option<bool> {
         match self {
             SelectionModifier::Replace => Some(ch),
             SelectionModifier::Add => {
@@ -55,18 +89,48 @@ impl SelectionModifier {
                     None
                 }
             }
+            SelectionModifier::Intersect => {
+                if ch {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+        }
+    }
+
+    pub fn get_label(self) -> String {
+        match self {
+            SelectionModifier::Replace => fl!(crate::LANGUAGE_LOADER, "menu-selection-operation-replace"),
+            SelectionModifier::Add => fl!(crate::LANGUAGE_LOADER, "menu-selection-operation-add"),
+            SelectionModifier::Remove => fl!(crate::LANGUAGE_LOADER, "menu-selection-operation-subtract"),
+            SelectionModifier::Intersect => fl!(crate::LANGUAGE_LOADER, "menu-selection-operation-intersect"),
         }
     }
 }
 
-#[derive(Default)]
 pub struct SelectTool {
     start_selection: Rectangle,
     selection_drag: SelectionDrag,
     mode: SelectionMode,
+    wand_mode: SelectionMode,
+    wand_tolerance: u8,
     undo_op: Option<AtomicUndoGuard>,
 }
 
+impl Default for SelectTool {
+    fn default() -> Self {
+        Self {
+            start_selection: Rectangle::default(),
+            selection_drag: SelectionDrag::default(),
+            mode: SelectionMode::default(),
+            wand_mode: SelectionMode::Character,
+            wand_tolerance: 0,
+            undo_op: None,
+        }
+    }
+}
+
 impl Tool for SelectTool {
     fn get_icon(&self) -> &egui::Image<'static> {
         &super::icons::SELECT_SVG
@@ -92,6 +156,36 @@ impl Tool for SelectTool {
         ui.radio_value(&mut self.mode, SelectionMode::Foreground, fl!(crate::LANGUAGE_LOADER, "tool-select-foreground"));
 
         ui.radio_value(&mut self.mode, SelectionMode::Background, fl!(crate::LANGUAGE_LOADER, "tool-select-background"));
+        ui.radio_value(&mut self.mode, SelectionMode::MagicWand, fl!(crate::LANGUAGE_LOADER, "tool-select-magic_wand"));
+        if self.mode == SelectionMode::MagicWand {
+            ui.indent("magic_wand_indent", |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "tool-select-magic_wand-match"));
+                ui.radio_value(
+                    &mut self.wand_mode,
+                    SelectionMode::Character,
+                    fl!(crate::LANGUAGE_LOADER, "tool-select-character"),
+                );
+                ui.radio_value(
+                    &mut self.wand_mode,
+                    SelectionMode::Attribute,
+                    fl!(crate::LANGUAGE_LOADER, "tool-select-attribute"),
+                );
+                ui.radio_value(
+                    &mut self.wand_mode,
+                    SelectionMode::Foreground,
+                    fl!(crate::LANGUAGE_LOADER, "tool-select-foreground"),
+                );
+                ui.radio_value(
+                    &mut self.wand_mode,
+                    SelectionMode::Background,
+                    fl!(crate::LANGUAGE_LOADER, "tool-select-background"),
+                );
+                ui.add_enabled(
+                    self.wand_mode != SelectionMode::Character,
+                    egui::Slider::new(&mut self.wand_tolerance, 0..=255).text(fl!(crate::LANGUAGE_LOADER, "tool-select-magic_wand-tolerance")),
+                );
+            });
+        }
         ui.add_space(8.0);
         ui.vertical_centered(|ui| {
             ui.small(fl!(crate::LANGUAGE_LOADER, "tool-select-description"));
@@ -138,6 +232,14 @@ impl Tool for SelectTool {
                 .lock()
                 .get_edit_state_mut()
                 .enumerate_selections(|_, ch, _| selection_mode.get_response(ch.attribute.get_background() == cur_ch.attribute.get_background())),
+            SelectionMode::MagicWand => {
+                let cells = self.get_connected_cells(editor, pos, cur_ch);
+                editor
+                    .buffer_view
+                    .lock()
+                    .get_edit_state_mut()
+                    .enumerate_selections(|pos, _, _| selection_mode.get_response(cells.contains(&pos)));
+            }
         }
         None
     }
@@ -297,6 +399,25 @@ impl Tool for SelectTool {
 }
 
 impl SelectTool {
+    /// Returns the cells connected to `pos` on the current layer that match the wand criteria, in buffer coordinates.
+    fn get_connected_cells(&self, editor: &AnsiEditor, pos: Position, cur_ch: AttributedChar) -> HashSet<Position> {
+        let mut cells = HashSet::new();
+        let lock = editor.buffer_view.lock();
+        let palette = &lock.get_buffer().palette;
+        let Some(layer) = lock.get_edit_state().get_cur_layer() else {
+            return cells;
+        };
+        let offset = layer.get_offset();
+        flood_fill(layer.get_size(), pos, |p| {
+            if !self.wand_mode.is_match(palette, self.wand_tolerance, layer.get_char(p), cur_ch) {
+                return false;
+            }
+            cells.insert(p + offset);
+            true
+        });
+        cells
+    }
+
     fn move_left(&mut self, editor: &AnsiEditor, rect: &mut Rectangle) {
         let delta = editor.drag_pos.start_abs.x - editor.drag_pos.cur_abs.x;
         rect.start.x = self.start_selection.left() - delta;
@@ -0,0 +1,550 @@
+use std::{collections::HashMap, sync::Arc};
+
+use eframe::egui::{self, RichText};
+use egui::mutex::Mutex;
+use i18n_embed_fl::fl;
+use icy_engine::{editor::EditState, AttributedChar, Buffer, BufferType, Position, Rectangle, TextAttribute, TheDrawFont, UnicodeConverter};
+use icy_engine_egui::TerminalCalc;
+
+use crate::{AnsiEditor, Message, Settings};
+
+use super::{Event, MKey, MModifiers, Tool};
+
+/// The text is typed as unicode, cells use the character set of the buffer.
+fn convert_from_unicode(ch: char, buffer_type: BufferType, font_page: usize) -> char {
+    match buffer_type {
+        BufferType::Unicode => ch,
+        BufferType::CP437 => icy_engine::ascii::CP437Converter::default().convert_from_unicode(ch, font_page),
+        BufferType::Petscii => icy_engine::petscii::CharConverter::default().convert_from_unicode(ch, font_page),
+        BufferType::Atascii => icy_engine::atascii::CharConverter::default().convert_from_unicode(ch, font_page),
+        BufferType::Viewdata => icy_engine::viewdata::CharConverter::default().convert_from_unicode(ch, font_page),
+    }
+}
+
+/// TheDraw fonts are indexed by CP437 codes, `None` if the character has none.
+fn get_tdf_char(ch: char) -> Option<u8> {
+    let ch = if ch.is_ascii() {
+        ch
+    } else {
+        icy_engine::ascii::CP437Converter::default().convert_from_unicode(ch, 0)
+    };
+    u8::try_from(ch as u32).ok()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextAlignment {
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+impl TextAlignment {
+    pub fn get_label(self) -> String {
+        match self {
+            TextAlignment::Left => fl!(crate::LANGUAGE_LOADER, "tool-text_box-align-left"),
+            TextAlignment::Center => fl!(crate::LANGUAGE_LOADER, "tool-text_box-align-center"),
+            TextAlignment::Right => fl!(crate::LANGUAGE_LOADER, "tool-text_box-align-right"),
+            TextAlignment::Justify => fl!(crate::LANGUAGE_LOADER, "tool-text_box-align-justify"),
+        }
+    }
+}
+
+/// A wrapped line of text, words are stored together with their rendered width.
+struct TextLine {
+    words: Vec<(Vec<char>, i32)>,
+    end_of_paragraph: bool,
+}
+
+/// Draws a paragraph of text into a rectangle. The text stays editable on the overlay layer
+/// until it gets committed to the current layer.
+pub struct TextBoxTool {
+    pub selected_font: Arc<Mutex<i32>>,
+    pub fonts: Arc<Mutex<Vec<TheDrawFont>>>,
+
+    text: String,
+    text_box: Option<Rectangle>,
+    box_offset: Position,
+    alignment: TextAlignment,
+    use_tdf_font: bool,
+    fill_background: bool,
+    is_dirty: bool,
+
+    char_widths: HashMap<char, i32>,
+    measured_font: i32,
+}
+
+impl TextBoxTool {
+    pub fn new(fonts: Arc<Mutex<Vec<TheDrawFont>>>, selected_font: Arc<Mutex<i32>>) -> Self {
+        Self {
+            selected_font,
+            fonts,
+            text: String::new(),
+            text_box: None,
+            box_offset: Position::default(),
+            alignment: TextAlignment::Left,
+            use_tdf_font: false,
+            fill_background: false,
+            is_dirty: false,
+            char_widths: HashMap::new(),
+            measured_font: -1,
+        }
+    }
+
+    fn get_font(&self) -> Option<TheDrawFont> {
+        if !self.use_tdf_font {
+            return None;
+        }
+        let selected_font = *self.selected_font.lock();
+        if selected_font < 0 {
+            return None;
+        }
+        self.fonts.lock().get(selected_font as usize).cloned()
+    }
+
+    fn get_char_width(&mut self, font: Option<&TheDrawFont>, ch: char) -> i32 {
+        let Some(font) = font else {
+            return 1;
+        };
+        if let Some(width) = self.char_widths.get(&ch) {
+            return *width;
+        }
+        let mut width = 1;
+        match get_tdf_char(ch) {
+            // characters the font can't encode are skipped
+            None => width = 0,
+            Some(tdf_ch) if font.has_char(tdf_ch) => {
+                let mut state = EditState::from_buffer(Buffer::new((100, 30)));
+                if let Some(size) = font.render(&mut state, tdf_ch) {
+                    width = size.width + font.spaces;
+                }
+            }
+            Some(_) => {}
+        }
+        self.char_widths.insert(ch, width);
+        width
+    }
+
+    fn get_word_width(&mut self, font: Option<&TheDrawFont>, word: &[char]) -> i32 {
+        word.iter().map(|ch| self.get_char_width(font, *ch)).sum()
+    }
+
+    fn wrap_text(&mut self, font: Option<&TheDrawFont>, width: i32) -> Vec<TextLine> {
+        let space_width = self.get_char_width(font, ' ');
+        let mut lines = Vec::new();
+        let text = self.text.clone();
+
+        for paragraph in text.split('\n') {
+            let mut line = TextLine {
+                words: Vec::new(),
+                end_of_paragraph: false,
+            };
+            let mut line_width = 0;
+
+            for word in paragraph.split(' ').filter(|w| !w.is_empty()) {
+                let word: Vec<char> = word.chars().collect();
+                let word_width = self.get_word_width(font, &word);
+
+                if word_width > width {
+                    // break words that don't fit into a line on character boundaries
+                    if !line.words.is_empty() {
+                        lines.push(std::mem::replace(
+                            &mut line,
+                            TextLine {
+                                words: Vec::new(),
+                                end_of_paragraph: false,
+                            },
+                        ));
+                    }
+                    let mut part = Vec::new();
+                    let mut part_width = 0;
+                    for ch in word {
+                        let w = self.get_char_width(font, ch);
+                        if !part.is_empty() && part_width + w > width {
+                            lines.push(TextLine {
+                                words: vec![(std::mem::take(&mut part), part_width)],
+                                end_of_paragraph: false,
+                            });
+                            part_width = 0;
+                        }
+                        part.push(ch);
+                        part_width += w;
+                    }
+                    line.words.push((part, part_width));
+                    line_width = part_width;
+                    continue;
+                }
+
+                if line.words.is_empty() {
+                    line_width = word_width;
+                } else if line_width + space_width + word_width <= width {
+                    line_width += space_width + word_width;
+                } else {
+                    lines.push(std::mem::replace(
+                        &mut line,
+                        TextLine {
+                            words: Vec::new(),
+                            end_of_paragraph: false,
+                        },
+                    ));
+                    line_width = word_width;
+                }
+                line.words.push((word, word_width));
+            }
+            line.end_of_paragraph = true;
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Returns the x position of every word in the line.
+    fn layout_line(&self, line: &TextLine, width: i32, space_width: i32) -> Vec<i32> {
+        let word_count = line.words.len() as i32;
+        if word_count == 0 {
+            return Vec::new();
+        }
+        let words_width: i32 = line.words.iter().map(|(_, w)| *w).sum();
+        let natural_width = words_width + space_width * (word_count - 1);
+        let mut result = Vec::new();
+
+        if self.alignment == TextAlignment::Justify && !line.end_of_paragraph && word_count > 1 {
+            let gaps = word_count - 1;
+            let extra = (width - words_width).max(gaps);
+            let mut x = 0;
+            for (i, (_, w)) in line.words.iter().enumerate() {
+                result.push(x);
+                let i = i as i32;
+                x += w + extra / gaps + i32::from(i < extra % gaps);
+            }
+            return result;
+        }
+
+        let mut x = match self.alignment {
+            TextAlignment::Left | TextAlignment::Justify => 0,
+            TextAlignment::Center => (width - natural_width) / 2,
+            TextAlignment::Right => width - natural_width,
+        }
+        .max(0);
+        for (_, w) in &line.words {
+            result.push(x);
+            x += w + space_width;
+        }
+        result
+    }
+
+    fn update_preview(&mut self, editor: &mut AnsiEditor) {
+        self.is_dirty = false;
+        editor.clear_overlay_layer();
+        let Some(text_box) = self.text_box else {
+            return;
+        };
+
+        let font = self.get_font();
+        let selected_font = *self.selected_font.lock();
+        if self.measured_font != selected_font || font.is_none() {
+            self.char_widths.clear();
+            self.measured_font = selected_font;
+        }
+
+        let width = text_box.size.width;
+        let lines = self.wrap_text(font.as_ref(), width);
+        let space_width = self.get_char_width(font.as_ref(), ' ');
+        let line_height = font.as_ref().map_or(1, |f| f.get_font_height().max(1));
+        let attr = editor.buffer_view.lock().get_caret().get_attribute();
+        let convert = {
+            let lock = editor.buffer_view.lock();
+            let buffer_type = lock.get_buffer().buffer_type;
+            let font_page = lock.get_caret().get_font_page();
+            move |ch| convert_from_unicode(ch, buffer_type, font_page)
+        };
+        let height = text_box.size.height.max(lines.len() as i32 * line_height);
+
+        let mut chars: Vec<(Position, AttributedChar)> = Vec::new();
+        if let Some(font) = &font {
+            let mut state = EditState::from_buffer(Buffer::new((width, height)));
+            for y in 0..height {
+                for x in 0..width {
+                    state.get_buffer_mut().layers[0].set_char(Position::new(x, y), AttributedChar::invisible());
+                }
+            }
+            state.set_outline_style(Settings::get_font_outline_style());
+            state.get_caret_mut().set_attr(attr);
+
+            for (i, line) in lines.iter().enumerate() {
+                let y = i as i32 * line_height;
+                for ((word, _), x) in line.words.iter().zip(self.layout_line(line, width, space_width)) {
+                    let mut x = x;
+                    for ch in word {
+                        let pos = Position::new(x, y);
+                        state.get_caret_mut().set_position(pos);
+                        if let Some(tdf_ch) = get_tdf_char(*ch) {
+                            let rendered = font.has_char(tdf_ch) && font.render(&mut state, tdf_ch).is_some();
+                            if !rendered {
+                                state.get_buffer_mut().layers[0].set_char(pos, AttributedChar::new(convert(*ch), attr));
+                            }
+                        }
+                        x += self.get_char_width(Some(font), *ch);
+                    }
+                }
+            }
+
+            for y in 0..height {
+                for x in 0..width {
+                    let pos = Position::new(x, y);
+                    let ch = state.get_buffer().layers[0].get_char(pos);
+                    if ch.is_visible() {
+                        chars.push((pos, ch));
+                    }
+                }
+            }
+        } else {
+            for (i, line) in lines.iter().enumerate() {
+                for ((word, _), x) in line.words.iter().zip(self.layout_line(line, width, space_width)) {
+                    for (j, ch) in word.iter().enumerate() {
+                        chars.push((Position::new(x + j as i32, i as i32), AttributedChar::new(convert(*ch), attr)));
+                    }
+                }
+            }
+        }
+
+        let mut lock = editor.buffer_view.lock();
+        if let Some(layer) = lock.get_edit_state_mut().get_overlay_layer() {
+            if self.fill_background {
+                let mut fill_attr = TextAttribute::default();
+                fill_attr.set_background(attr.get_background());
+                for y in 0..height {
+                    for x in 0..width {
+                        layer.set_char(text_box.start + Position::new(x, y), AttributedChar::new(' ', fill_attr));
+                    }
+                }
+            }
+            for (pos, ch) in chars {
+                if pos.x < width {
+                    layer.set_char(text_box.start + pos, ch);
+                }
+            }
+        }
+        self.draw_outline(&mut lock, Rectangle::from(text_box.start.x, text_box.start.y, width, height));
+        lock.get_edit_state_mut().set_is_buffer_dirty();
+    }
+
+    fn draw_outline(&self, lock: &mut icy_engine_egui::BufferView, rect: Rectangle) {
+        let mask = lock.get_edit_state_mut().get_tool_overlay_mask_mut();
+        mask.clear();
+        let start = rect.start + self.box_offset;
+        let end = start + Position::new(rect.size.width - 1, rect.size.height - 1);
+        for x in start.x..=end.x {
+            mask.set_is_selected(Position::new(x, start.y), true);
+            mask.set_is_selected(Position::new(x, end.y), true);
+        }
+        for y in start.y..=end.y {
+            mask.set_is_selected(Position::new(start.x, y), true);
+            mask.set_is_selected(Position::new(end.x, y), true);
+        }
+    }
+
+    fn commit(&mut self, editor: &mut AnsiEditor) {
+        if self.is_dirty {
+            self.update_preview(editor);
+        }
+        if self.text_box.is_some() && !self.text.is_empty() {
+            editor.join_overlay(fl!(crate::LANGUAGE_LOADER, "undo-text_box"));
+        } else {
+            editor.clear_overlay_layer();
+        }
+        self.reset(editor);
+    }
+
+    fn cancel(&mut self, editor: &mut AnsiEditor) {
+        editor.clear_overlay_layer();
+        self.reset(editor);
+    }
+
+    fn reset(&mut self, editor: &mut AnsiEditor) {
+        self.text.clear();
+        self.text_box = None;
+        self.is_dirty = false;
+        let mut lock = editor.buffer_view.lock();
+        lock.get_edit_state_mut().get_tool_overlay_mask_mut().clear();
+        lock.get_edit_state_mut().set_is_buffer_dirty();
+    }
+}
+
+impl Tool for TextBoxTool {
+    fn get_icon(&self) -> &egui::Image<'static> {
+        &super::icons::TEXT_BOX_SVG
+    }
+
+    fn tool_name(&self) -> String {
+        fl!(crate::LANGUAGE_LOADER, "tool-text_box_name")
+    }
+
+    fn tooltip(&self) -> String {
+        fl!(crate::LANGUAGE_LOADER, "tool-text_box_tooltip")
+    }
+
+    fn use_caret(&self, _editor: &AnsiEditor) -> bool {
+        false
+    }
+
+    fn use_selection(&self) -> bool {
+        false
+    }
+
+    fn show_ui(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui, editor_opt: Option<&mut AnsiEditor>) -> Option<Message> {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            for alignment in [TextAlignment::Left, TextAlignment::Center, TextAlignment::Right, TextAlignment::Justify] {
+                changed |= ui.selectable_value(&mut self.alignment, alignment, alignment.get_label()).changed();
+            }
+        });
+        changed |= ui
+            .checkbox(&mut self.fill_background, fl!(crate::LANGUAGE_LOADER, "tool-text_box-fill_background"))
+            .changed();
+
+        let font_count = self.fonts.lock().len();
+        let mut msg = None;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add_enabled(
+                    font_count > 0,
+                    egui::Checkbox::new(&mut self.use_tdf_font, fl!(crate::LANGUAGE_LOADER, "tool-text_box-use_tdf_font")),
+                )
+                .changed();
+            if self.use_tdf_font {
+                let selected_font = *self.selected_font.lock();
+                let name = if selected_font >= 0 {
+                    self.fonts.lock().get(selected_font as usize).map(|f| f.name.clone())
+                } else {
+                    None
+                }
+                .unwrap_or_else(|| fl!(crate::LANGUAGE_LOADER, "font_tool_no_font"));
+                if ui.button(RichText::new(name)).clicked() {
+                    msg = Some(Message::SelectFontDialog(self.fonts.clone(), self.selected_font.clone()));
+                }
+                if self.measured_font != selected_font {
+                    changed = true;
+                }
+            }
+        });
+
+        ui.add_space(8.0);
+        ui.label(fl!(crate::LANGUAGE_LOADER, "tool-text_box-text_label"));
+        changed |= ui
+            .add(egui::TextEdit::multiline(&mut self.text).desired_rows(6).desired_width(f32::INFINITY))
+            .changed();
+
+        if let Some(editor) = editor_opt {
+            ui.horizontal(|ui| {
+                let has_box = self.text_box.is_some();
+                if ui
+                    .add_enabled(
+                        has_box && !self.text.is_empty(),
+                        egui::Button::new(fl!(crate::LANGUAGE_LOADER, "tool-text_box-commit")),
+                    )
+                    .clicked()
+                {
+                    self.commit(editor);
+                }
+                if ui
+                    .add_enabled(has_box, egui::Button::new(fl!(crate::LANGUAGE_LOADER, "tool-text_box-cancel")))
+                    .clicked()
+                {
+                    self.cancel(editor);
+                }
+            });
+            if changed {
+                self.is_dirty = true;
+            }
+            if self.is_dirty && self.text_box.is_some() {
+                self.update_preview(editor);
+            }
+        } else if changed {
+            self.is_dirty = true;
+        }
+        msg
+    }
+
+    fn handle_key(&mut self, editor: &mut AnsiEditor, key: MKey, modifier: MModifiers) -> Event {
+        if self.text_box.is_none() {
+            return Event::None;
+        }
+        match key {
+            MKey::Character(ch) => {
+                if let Some(ch) = char::from_u32(u32::from(ch)) {
+                    self.text.push(ch);
+                }
+            }
+            MKey::Return => {
+                if modifier.is_control() {
+                    self.commit(editor);
+                    return Event::None;
+                }
+                self.text.push('\n');
+            }
+            MKey::Backspace => {
+                self.text.pop();
+            }
+            MKey::Escape => {
+                self.cancel(editor);
+                return Event::None;
+            }
+            _ => return Event::None,
+        }
+        self.update_preview(editor);
+        Event::None
+    }
+
+    fn handle_paste(&mut self, editor: &mut AnsiEditor, text: &str) -> bool {
+        if self.text_box.is_none() {
+            return false;
+        }
+        self.text.push_str(&text.replace("\r\n", "\n").replace('\r', "\n"));
+        self.update_preview(editor);
+        true
+    }
+
+    fn handle_drag(&mut self, _ui: &egui::Ui, response: egui::Response, editor: &mut AnsiEditor, _calc: &TerminalCalc) -> egui::Response {
+        let p1 = editor.drag_pos.start;
+        let p2 = editor.drag_pos.cur;
+        let start = Position::new(p1.x.min(p2.x), p1.y.min(p2.y));
+        let end = Position::new(p1.x.max(p2.x), p1.y.max(p2.y));
+        let text_box = Rectangle::from(start.x, start.y, end.x - start.x + 1, end.y - start.y + 1);
+        if self.text_box == Some(text_box) {
+            return response;
+        }
+        self.text_box = Some(text_box);
+        self.box_offset = editor.drag_pos.start_abs - editor.drag_pos.start;
+        if self.text.is_empty() {
+            let mut lock = editor.buffer_view.lock();
+            self.draw_outline(&mut lock, text_box);
+            lock.get_edit_state_mut().set_is_buffer_dirty();
+        } else {
+            self.update_preview(editor);
+        }
+        response
+    }
+
+    fn handle_drag_end(&mut self, editor: &mut AnsiEditor) -> Option<Message> {
+        self.update_preview(editor);
+        editor.request_focus = true;
+        None
+    }
+
+    fn handle_hover(&mut self, _ui: &egui::Ui, response: egui::Response, _editor: &mut AnsiEditor, _cur: Position, _cur_abs: Position) -> egui::Response {
+        response.on_hover_cursor(egui::CursorIcon::Text)
+    }
+
+    fn get_toolbar_location_text(&self, editor: &AnsiEditor) -> String {
+        if let Some(text_box) = self.text_box {
+            fl!(
+                crate::LANGUAGE_LOADER,
+                "toolbar-size",
+                colums = text_box.size.width,
+                rows = text_box.size.height
+            )
+        } else {
+            super::toolbar_pos_sel_text(editor, false)
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use eframe::egui::{self, RichText};
+use i18n_embed_fl::fl;
+use icy_engine::{Position, TextPane};
+
+use crate::AnsiEditor;
+
+/// Footprint taken from a selection, offsets are relative to the center of the selection.
+pub static mut CUSTOM_BRUSH_SHAPE: Option<Vec<Position>> = None;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BrushShape {
+    Square,
+    Circle,
+    AspectCircle,
+    Diagonal,
+    Custom,
+}
+
+impl BrushShape {
+    /// Returns the cells covered by the brush, relative to the cursor position.
+    /// `aspect` is the cell height divided by the cell width.
+    pub fn get_footprint(self, size: i32, aspect: f32) -> Vec<Position> {
+        let mut result = Vec::new();
+        match self {
+            BrushShape::Square => {
+                let mid = size / 2;
+                for y in 0..size {
+                    for x in 0..size {
+                        result.push(Position::new(x - mid, y - mid));
+                    }
+                }
+            }
+            BrushShape::Circle => {
+                add_ellipse(&mut result, size, size);
+            }
+            BrushShape::AspectCircle => {
+                let width = ((size as f32 * aspect).round() as i32).max(1);
+                add_ellipse(&mut result, width, size);
+            }
+            BrushShape::Diagonal => {
+                let mid = size / 2;
+                for y in 0..size {
+                    result.push(Position::new(size - 1 - y - mid, y - mid));
+                }
+            }
+            BrushShape::Custom => {
+                if let Some(shape) = unsafe { &CUSTOM_BRUSH_SHAPE } {
+                    result.extend(shape.iter().copied());
+                } else {
+                    result.push(Position::default());
+                }
+            }
+        }
+        result
+    }
+
+    pub fn show_ui(&mut self, ui: &mut egui::Ui, editor_opt: Option<&mut AnsiEditor>) {
+        ui.horizontal(|ui| {
+            ui.label(fl!(crate::LANGUAGE_LOADER, "tool-brush-shape-label"));
+            egui::ComboBox::from_id_source("brush_shape_combo")
+                .width(150.)
+                .selected_text(RichText::new(self.get_label()))
+                .show_ui(ui, |ui| {
+                    for shape in [BrushShape::Square, BrushShape::Circle, BrushShape::AspectCircle, BrushShape::Diagonal] {
+                        ui.selectable_value(self, shape, shape.get_label());
+                    }
+                    if unsafe { CUSTOM_BRUSH_SHAPE.is_some() } {
+                        ui.selectable_value(self, BrushShape::Custom, BrushShape::Custom.get_label());
+                    }
+                });
+        });
+
+        if let Some(editor) = editor_opt {
+            let has_selection = editor.buffer_view.lock().get_edit_state().is_something_selected();
+            if ui
+                .add_enabled(has_selection, egui::Button::new(fl!(crate::LANGUAGE_LOADER, "tool-brush-shape-from-selection")))
+                .clicked()
+            {
+                let shape = get_selection_footprint(editor);
+                if !shape.is_empty() {
+                    unsafe {
+                        CUSTOM_BRUSH_SHAPE = Some(shape);
+                    }
+                    *self = BrushShape::Custom;
+                }
+            }
+        }
+    }
+
+    fn get_label(self) -> String {
+        match self {
+            BrushShape::Square => fl!(crate::LANGUAGE_LOADER, "tool-brush-shape-square"),
+            BrushShape::Circle => fl!(crate::LANGUAGE_LOADER, "tool-brush-shape-circle"),
+            BrushShape::AspectCircle => fl!(crate::LANGUAGE_LOADER, "tool-brush-shape-aspect_circle"),
+            BrushShape::Diagonal => fl!(crate::LANGUAGE_LOADER, "tool-brush-shape-diagonal"),
+            BrushShape::Custom => fl!(crate::LANGUAGE_LOADER, "tool-brush-shape-custom"),
+        }
+    }
+}
+
+fn add_ellipse(result: &mut Vec<Position>, width: i32, height: i32) {
+    let rx = width as f32 / 2.0;
+    let ry = height as f32 / 2.0;
+    let cx = (width - 1) as f32 / 2.0;
+    let cy = (height - 1) as f32 / 2.0;
+    let (mid_x, mid_y) = (width / 2, height / 2);
+    for y in 0..height {
+        for x in 0..width {
+            let dx = (x as f32 - cx) / rx;
+            let dy = (y as f32 - cy) / ry;
+            if dx * dx + dy * dy <= 1.0 {
+                result.push(Position::new(x - mid_x, y - mid_y));
+            }
+        }
+    }
+}
+
+/// Collects all selected cells of the document, centered on the selection bounds.
+fn get_selection_footprint(editor: &AnsiEditor) -> Vec<Position> {
+    let lock = editor.buffer_view.lock();
+    let width = lock.get_buffer().get_width();
+    let height = lock.get_buffer().get_height();
+
+    let mut cells = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let pos = Position::new(x, y);
+            if lock.get_edit_state().get_is_selected(pos) {
+                cells.push(pos);
+            }
+        }
+    }
+    let Some(first) = cells.first() else {
+        return cells;
+    };
+    let mut min = *first;
+    let mut max = *first;
+    for p in &cells {
+        min.x = min.x.min(p.x);
+        min.y = min.y.min(p.y);
+        max.x = max.x.max(p.x);
+        max.y = max.y.max(p.y);
+    }
+    let center = Position::new((min.x + max.x) / 2, (min.y + max.y) / 2);
+    cells.iter().map(|p| *p - center).collect()
+}
+
+/// Height of a character cell divided by its width, used for aspect corrected brushes.
+pub fn get_cell_aspect(editor: &AnsiEditor) -> f32 {
+    let dim = editor.buffer_view.lock().get_buffer().get_font_dimensions();
+    if dim.width <= 0 {
+        return 1.0;
+    }
+    dim.height as f32 / dim.width as f32
+}
+
+/// Returns the cells of the footprint that border on a cell outside of it.
+pub fn get_footprint_outline(footprint: &[Position]) -> Vec<Position> {
+    let cells: HashSet<(i32, i32)> = footprint.iter().map(|p| (p.x, p.y)).collect();
+    footprint
+        .iter()
+        .filter(|p| {
+            !cells.contains(&(p.x - 1, p.y)) || !cells.contains(&(p.x + 1, p.y)) || !cells.contains(&(p.x, p.y - 1)) || !cells.contains(&(p.x, p.y + 1))
+        })
+        .copied()
+        .collect()
+}
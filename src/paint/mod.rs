@@ -12,6 +12,8 @@ mod line;
 pub use line::*;
 mod ellipse;
 pub use ellipse::*;
+mod brush_shape;
+pub use brush_shape::*;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum BrushMode {
@@ -21,6 +23,8 @@ pub enum BrushMode {
     Char(std::rc::Rc<std::cell::RefCell<char>>),
     Shade,
     Colorize,
+    Spray,
+    DodgeBurn,
     Custom,
 }
 
@@ -51,6 +55,14 @@ impl BrushUi {
     fn has_shade(&self) -> bool {
         !matches!(self, BrushUi::Fill)
     }
+
+    fn has_spray(&self) -> bool {
+        matches!(self, BrushUi::Brush)
+    }
+
+    fn has_dodge_burn(&self) -> bool {
+        matches!(self, BrushUi::Brush)
+    }
 }
 
 impl BrushMode {
@@ -83,6 +95,14 @@ impl BrushMode {
 
         ui.radio_value(self, BrushMode::Colorize, fl!(crate::LANGUAGE_LOADER, "tool-colorize"));
 
+        if brush_ui.has_spray() {
+            ui.radio_value(self, BrushMode::Spray, fl!(crate::LANGUAGE_LOADER, "tool-spray"));
+        }
+
+        if brush_ui.has_dodge_burn() {
+            ui.radio_value(self, BrushMode::DodgeBurn, fl!(crate::LANGUAGE_LOADER, "tool-dodge_burn"));
+        }
+
         ui.horizontal(|ui| {
             ui.radio_value(self, BrushMode::Char(char_code.clone()), fl!(crate::LANGUAGE_LOADER, "tool-character"));
             /*  if let Some(editor) = editor_opt {
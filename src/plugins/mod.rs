@@ -120,7 +120,10 @@ impl LuaBufferView {
         let ch = match buffer_type {
             icy_engine::BufferType::Unicode => ch,
             icy_engine::BufferType::CP437 => {
-                icy_engine::ascii::CP437Converter::default().convert_from_unicode(ch, self.buffer_view.lock().get_caret().get_font_page())
+                let lock = self.buffer_view.lock();
+                let font_page = lock.get_caret().get_font_page();
+                crate::font_from_unicode(lock.get_buffer().get_font(font_page), ch)
+                    .unwrap_or_else(|| icy_engine::ascii::CP437Converter::default().convert_from_unicode(ch, font_page))
             }
             icy_engine::BufferType::Petscii => {
                 icy_engine::petscii::CharConverter::default().convert_from_unicode(ch, self.buffer_view.lock().get_caret().get_font_page())
@@ -139,7 +142,8 @@ impl LuaBufferView {
         let buffer_type = self.buffer_view.lock().get_buffer().buffer_type;
         let ch = match buffer_type {
             icy_engine::BufferType::Unicode => ch.ch,
-            icy_engine::BufferType::CP437 => icy_engine::ascii::CP437Converter::default().convert_to_unicode(ch),
+            icy_engine::BufferType::CP437 => crate::font_to_unicode(self.buffer_view.lock().get_buffer().get_font(ch.get_font_page()), ch.ch)
+                .unwrap_or_else(|| icy_engine::ascii::CP437Converter::default().convert_to_unicode(ch)),
             icy_engine::BufferType::Petscii => icy_engine::petscii::CharConverter::default().convert_to_unicode(ch),
             icy_engine::BufferType::Atascii => icy_engine::atascii::CharConverter::default().convert_to_unicode(ch),
             icy_engine::BufferType::Viewdata => icy_engine::viewdata::CharConverter::default().convert_to_unicode(ch),
@@ -470,6 +470,7 @@ keys![
     (copy, "menu-copy", Copy, CanCopyState, C, CTRL),
     (paste, "menu-paste", Paste, CanPasteState, V, CTRL),
     (show_settings, "menu-show_settings", ShowSettings, AlwaysEnabledState),
+    (find_replace, "menu-find_replace", ShowFindReplaceDialog, BufferOpenState, H, CTRL),
     (select_all, "menu-select-all", SelectAll, BufferOpenState, A, CTRL),
     (deselect, "menu-select_nothing", SelectNothing, BufferOpenState),
     (erase_selection, "menu-erase", DeleteSelection, BufferOpenState, Delete, NONE),
@@ -479,6 +480,7 @@ keys![
     (justifyleft, "menu-justifyleft", JustifyLeft, BufferOpenState),
     (justifyright, "menu-justifyright", JustifyRight, BufferOpenState),
     (crop, "menu-crop", Crop, BufferOpenState),
+    (free_transform, "menu-free_transform", FreeTransform, BufferOpenState, T, CTRL),
     (about, "menu-about", ShowAboutDialog, AlwaysEnabledState),
     (justify_line_center, "menu-justify_line_center", CenterLine, BufferOpenState, C, ALT),
     (justify_line_left, "menu-justify_line_left", JustifyLineLeft, BufferOpenState, L, ALT),
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use eframe::egui::{self, Layout, RichText};
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+
+use crate::{BitFontFormat, BitFontSaveOptions, MainWindow, Message, ModalDialog, TerminalResult};
+
+pub struct BitFontSaveDialog {
+    should_commit: bool,
+    path: PathBuf,
+    options: BitFontSaveOptions,
+    font_height: i32,
+}
+
+impl BitFontSaveDialog {
+    pub fn new(path: PathBuf, options: BitFontSaveOptions, font_height: i32) -> Self {
+        Self {
+            should_commit: false,
+            path,
+            options,
+            font_height,
+        }
+    }
+
+    fn get_extension(&self) -> String {
+        self.path.extension().unwrap_or_default().to_str().unwrap_or_default().to_ascii_lowercase()
+    }
+}
+
+impl ModalDialog for BitFontSaveDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut result = false;
+        let modal = Modal::new(ctx, "bitfont_save_dialog");
+        let ext = self.get_extension();
+        let format = BitFontFormat::from_extension(&ext, &self.options).unwrap_or(BitFontFormat::Psf2);
+
+        modal.show(|ui| {
+            modal.title(ui, fl!(crate::LANGUAGE_LOADER, "font-save-dialog-title"));
+
+            modal.frame(ui, |ui| {
+                egui::Grid::new("some_unique_id").num_columns(2).spacing([4.0, 8.0]).show(ui, |ui| {
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(fl!(crate::LANGUAGE_LOADER, "font-save-dialog-format"));
+                    });
+                    if matches!(format, BitFontFormat::Psf1 | BitFontFormat::Psf2) {
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut self.options.psf1, true, BitFontFormat::Psf1.get_label());
+                            ui.radio_value(&mut self.options.psf1, false, BitFontFormat::Psf2.get_label());
+                        });
+                    } else {
+                        ui.label(format.get_label());
+                    }
+                    ui.end_row();
+
+                    match format {
+                        BitFontFormat::Psf1 | BitFontFormat::Psf2 => {
+                            ui.label("");
+                            ui.checkbox(&mut self.options.unicode_table, fl!(crate::LANGUAGE_LOADER, "font-save-dialog-unicode_table"));
+                            ui.end_row();
+                        }
+                        BitFontFormat::Raw => {
+                            if let Ok(height) = ext[1..].parse::<i32>() {
+                                if height != self.font_height {
+                                    ui.label("");
+                                    ui.label(
+                                        RichText::new(fl!(
+                                            crate::LANGUAGE_LOADER,
+                                            "font-save-dialog-height_mismatch",
+                                            ext = ext.clone(),
+                                            height = self.font_height
+                                        ))
+                                        .color(ui.style().visuals.warn_fg_color),
+                                    );
+                                    ui.end_row();
+                                }
+                            }
+                        }
+                        BitFontFormat::Fnt | BitFontFormat::Bdf => {
+                            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.label(fl!(crate::LANGUAGE_LOADER, "font-save-dialog-face_name"));
+                            });
+                            ui.text_edit_singleline(&mut self.options.face_name);
+                            ui.end_row();
+                            if format == BitFontFormat::Bdf {
+                                ui.label("");
+                                ui.checkbox(
+                                    &mut self.options.unicode_encoding,
+                                    fl!(crate::LANGUAGE_LOADER, "font-save-dialog-unicode_encoding"),
+                                );
+                                ui.end_row();
+                            }
+                        }
+                    }
+                });
+                ui.add_space(4.0);
+            });
+
+            modal.buttons(ui, |ui| {
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-ok")).clicked() {
+                    self.should_commit = true;
+                    result = true;
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+
+    fn should_commit(&self) -> bool {
+        self.should_commit
+    }
+
+    fn commit_self(&self, window: &mut MainWindow<'_>) -> TerminalResult<Option<Message>> {
+        let mut msg = None;
+        if let Some(pane) = window.get_active_pane_mut() {
+            if let Some(editor) = pane.doc.lock().get_bitfont_editor_mut() {
+                editor.save_options = self.options.clone();
+            }
+            pane.set_path(self.path.clone());
+            msg = pane.save();
+            window.current_id = None;
+        }
+        Ok(msg)
+    }
+}
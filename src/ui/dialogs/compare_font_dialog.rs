@@ -0,0 +1,35 @@
+use eframe::egui;
+
+use crate::{FontSelector, MainWindow, Message, ModalDialog, TerminalResult};
+
+/// Picks the font the bit font editor compares its glyphs with from the SAUCE, ANSI and library fonts.
+pub struct CompareFontDialog {
+    selector: FontSelector,
+}
+
+impl Default for CompareFontDialog {
+    fn default() -> Self {
+        Self {
+            selector: FontSelector::font_library(),
+        }
+    }
+}
+
+impl ModalDialog for CompareFontDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        self.selector.show(ctx)
+    }
+
+    fn should_commit(&self) -> bool {
+        self.selector.should_commit()
+    }
+
+    fn commit_self(&self, window: &mut MainWindow<'_>) -> TerminalResult<Option<Message>> {
+        if let Some(doc) = window.get_active_document() {
+            if let Some(editor) = doc.lock().get_bitfont_editor_mut() {
+                editor.set_compare_font(Some(self.selector.selected_font().clone()));
+            }
+        }
+        Ok(None)
+    }
+}
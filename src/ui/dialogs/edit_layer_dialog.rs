@@ -1,9 +1,12 @@
 use eframe::egui::{self, color_picker, Layout, RichText};
 use egui_modal::Modal;
 use i18n_embed_fl::fl;
-use icy_engine::{Color, Mode, Properties};
+use icy_engine::{Color, Properties};
 
-use crate::{AnsiEditor, Message, ModalDialog, TerminalResult};
+use crate::{
+    layer_groups::{join_group_path, split_group_path},
+    strip_blend_mode, AnsiEditor, BlendMode, Message, ModalDialog, TerminalResult, BLEND_MODES,
+};
 
 pub struct EditLayerDialog {
     pub should_commit: bool,
@@ -11,15 +14,23 @@ pub struct EditLayerDialog {
     layer: usize,
 
     properties: Properties,
+    blend_mode: BlendMode,
+    /// The group path is kept, only the layer name can be edited.
+    group: String,
+    name: String,
 }
 
 impl EditLayerDialog {
     pub fn new(buf: &icy_engine::Buffer, layer: usize) -> Self {
         let l = &buf.layers[layer];
+        let (group, name) = split_group_path(l.get_title());
         EditLayerDialog {
             should_commit: false,
             layer,
+            group: group.to_string(),
+            name: strip_blend_mode(name).to_string(),
             properties: l.properties.clone(),
+            blend_mode: BlendMode::from_properties(&l.properties),
         }
     }
 }
@@ -37,7 +48,7 @@ impl ModalDialog for EditLayerDialog {
                     ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
                         ui.label(fl!(crate::LANGUAGE_LOADER, "edit-layer-dialog-name-label"));
                     });
-                    ui.add(egui::TextEdit::singleline(&mut self.properties.title));
+                    ui.add(egui::TextEdit::singleline(&mut self.name));
                     ui.end_row();
 
                     if self.properties.color.is_some() {
@@ -103,20 +114,18 @@ impl ModalDialog for EditLayerDialog {
                         ui.end_row();
                     }
 
-                    ui.label("Mode:");
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "edit-layer-dialog-mode-label"));
 
                     egui::ComboBox::from_id_source("combobox1")
                         .width(150.)
-                        .selected_text(RichText::new(match self.properties.mode {
-                            Mode::Normal => "Normal",
-                            Mode::Chars => "Chars only",
-                            Mode::Attributes => "Attribute only",
-                        }))
+                        .selected_text(RichText::new(self.blend_mode.get_label()))
                         .show_ui(ui, |ui| {
-                            ui.selectable_value(&mut self.properties.mode, Mode::Normal, "Normal");
-                            ui.selectable_value(&mut self.properties.mode, Mode::Chars, "Chars only");
-                            ui.selectable_value(&mut self.properties.mode, Mode::Attributes, "Attribute only");
-                        });
+                            for mode in BLEND_MODES {
+                                ui.selectable_value(&mut self.blend_mode, mode, mode.get_label());
+                            }
+                        })
+                        .response
+                        .on_hover_text(fl!(crate::LANGUAGE_LOADER, "edit-layer-dialog-mode-tooltip"));
                     ui.end_row();
 
                     ui.label(fl!(crate::LANGUAGE_LOADER, "edit-layer-dialog-is-x-offset-label"));
@@ -151,9 +160,12 @@ impl ModalDialog for EditLayerDialog {
 
     fn commit(&self, editor: &mut AnsiEditor) -> TerminalResult<Option<Message>> {
         let mut bv = editor.buffer_view.lock();
+        let mut properties = self.properties.clone();
+        properties.title = join_group_path(&self.group, &self.name);
+        self.blend_mode.apply(&mut properties);
 
-        if bv.get_buffer_mut().layers[self.layer].properties != self.properties {
-            bv.get_edit_state_mut().update_layer_properties(self.layer, self.properties.clone())?;
+        if bv.get_buffer_mut().layers[self.layer].properties != properties {
+            bv.get_edit_state_mut().update_layer_properties(self.layer, properties)?;
         }
         Ok(None)
     }
@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use eframe::{
+    egui::{self, color_picker, Layout, TextureHandle},
+    epaint::{ColorImage, TextureOptions},
+};
+use egui_file::FileDialog;
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+use icy_engine::{BitFont, Buffer};
+use image::RgbaImage;
+
+use crate::{FontImageSettings, MainWindow, Message, ModalDialog, TerminalResult};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExportKind {
+    Sheet,
+    Text,
+    Document,
+}
+
+pub struct ExportFontDialog {
+    should_commit: bool,
+    font: BitFont,
+    settings: FontImageSettings,
+    kind: ExportKind,
+    text: String,
+    documents: Vec<(String, Buffer)>,
+    selected_document: usize,
+    preview: Option<TextureHandle>,
+    preview_dirty: bool,
+    file_dialog: Option<FileDialog>,
+    export_path: Option<PathBuf>,
+}
+
+impl ExportFontDialog {
+    pub fn new(font: BitFont, documents: Vec<(String, Buffer)>) -> Self {
+        Self {
+            should_commit: false,
+            font,
+            settings: FontImageSettings::default(),
+            kind: ExportKind::Sheet,
+            text: fl!(crate::LANGUAGE_LOADER, "export-font-dialog-default_text"),
+            documents,
+            selected_document: 0,
+            preview: None,
+            preview_dirty: true,
+            file_dialog: None,
+            export_path: None,
+        }
+    }
+
+    fn render(&self) -> RgbaImage {
+        match self.kind {
+            ExportKind::Sheet => self.settings.render_sheet(&self.font),
+            ExportKind::Text => self.settings.render_text(&self.font, &self.text),
+            ExportKind::Document => match self.documents.get(self.selected_document) {
+                Some((_, buf)) => self.settings.render_document(&self.font, buf.clone()),
+                None => RgbaImage::new(1, 1),
+            },
+        }
+    }
+
+    fn show_settings(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        egui::Grid::new("some_unique_id").num_columns(2).spacing([4.0, 8.0]).show(ui, |ui| {
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "export-font-dialog-kind"));
+            });
+            ui.vertical(|ui| {
+                changed |= ui
+                    .radio_value(&mut self.kind, ExportKind::Sheet, fl!(crate::LANGUAGE_LOADER, "export-font-dialog-kind-sheet"))
+                    .changed();
+                changed |= ui
+                    .radio_value(&mut self.kind, ExportKind::Text, fl!(crate::LANGUAGE_LOADER, "export-font-dialog-kind-text"))
+                    .changed();
+                ui.add_enabled_ui(!self.documents.is_empty(), |ui| {
+                    changed |= ui
+                        .radio_value(
+                            &mut self.kind,
+                            ExportKind::Document,
+                            fl!(crate::LANGUAGE_LOADER, "export-font-dialog-kind-document"),
+                        )
+                        .changed();
+                });
+            });
+            ui.end_row();
+
+            match self.kind {
+                ExportKind::Sheet => {
+                    ui.label("");
+                    changed |= ui
+                        .checkbox(&mut self.settings.grid, fl!(crate::LANGUAGE_LOADER, "export-font-dialog-grid"))
+                        .changed();
+                    ui.end_row();
+
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(fl!(crate::LANGUAGE_LOADER, "export-font-dialog-grid_color"));
+                    });
+                    changed |= color_picker::color_edit_button_srgb(ui, &mut self.settings.grid_color).changed();
+                    ui.end_row();
+                }
+                ExportKind::Text => {
+                    ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                        ui.label(fl!(crate::LANGUAGE_LOADER, "export-font-dialog-text"));
+                    });
+                    changed |= ui.add(egui::TextEdit::multiline(&mut self.text).desired_rows(4)).changed();
+                    ui.end_row();
+                }
+                ExportKind::Document => {
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(fl!(crate::LANGUAGE_LOADER, "export-font-dialog-document"));
+                    });
+                    let selected_text = self.documents.get(self.selected_document).map(|(title, _)| title.clone()).unwrap_or_default();
+                    egui::ComboBox::from_id_source("export_font_document")
+                        .selected_text(selected_text)
+                        .show_ui(ui, |ui| {
+                            for (i, (title, _)) in self.documents.iter().enumerate() {
+                                changed |= ui.selectable_value(&mut self.selected_document, i, title).changed();
+                            }
+                        });
+                    ui.end_row();
+                }
+            }
+
+            if self.kind != ExportKind::Document {
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "export-font-dialog-colors"));
+                });
+                ui.horizontal(|ui| {
+                    changed |= color_picker::color_edit_button_srgb(ui, &mut self.settings.foreground).changed();
+                    changed |= color_picker::color_edit_button_srgb(ui, &mut self.settings.background).changed();
+                });
+                ui.end_row();
+            }
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "export-font-dialog-scale"));
+            });
+            changed |= ui.add(egui::Slider::new(&mut self.settings.scale, 1..=8)).changed();
+            ui.end_row();
+        });
+        self.preview_dirty |= changed;
+    }
+
+    fn show_preview(&mut self, ui: &mut egui::Ui) {
+        if self.preview_dirty || self.preview.is_none() {
+            self.preview_dirty = false;
+            let image = self.render();
+            let color_image = ColorImage::from_rgba_unmultiplied([image.width() as usize, image.height() as usize], image.as_raw());
+            self.preview = Some(ui.ctx().load_texture("export_font_preview", color_image, TextureOptions::NEAREST));
+        }
+        if let Some(preview) = &self.preview {
+            let size = preview.size_vec2();
+            let scale = (400.0 / size.x).min(400.0 / size.y).min(1.0);
+            ui.image((preview.id(), size * scale));
+        }
+    }
+}
+
+impl ModalDialog for ExportFontDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        if let Some(dialog) = &mut self.file_dialog {
+            if dialog.show(ctx).selected() {
+                if let Some(path) = dialog.path() {
+                    self.export_path = Some(path.with_extension("png"));
+                    self.should_commit = true;
+                }
+                return true;
+            }
+            if matches!(dialog.state(), egui_file::State::Cancelled | egui_file::State::Closed) {
+                self.file_dialog = None;
+            }
+            return false;
+        }
+
+        let mut result = false;
+        let modal = Modal::new(ctx, "export_font_dialog");
+
+        modal.show(|ui| {
+            modal.title(ui, fl!(crate::LANGUAGE_LOADER, "export-font-dialog-title"));
+
+            modal.frame(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        self.show_settings(ui);
+                    });
+                    ui.add_space(8.0);
+                    self.show_preview(ui);
+                });
+                ui.add_space(4.0);
+            });
+
+            modal.buttons(ui, |ui| {
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "export-button-title")).clicked() {
+                    let file_name = if self.font.name.is_empty() {
+                        "font.png".to_string()
+                    } else {
+                        format!("{}.png", self.font.name)
+                    };
+                    let mut dialog = FileDialog::save_file(Some(PathBuf::from(file_name)));
+                    dialog.open();
+                    self.file_dialog = Some(dialog);
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+
+    fn should_commit(&self) -> bool {
+        self.should_commit
+    }
+
+    fn commit_self(&self, _window: &mut MainWindow<'_>) -> TerminalResult<Option<Message>> {
+        if let Some(path) = &self.export_path {
+            self.render().save(path)?;
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,383 @@
+use std::sync::Arc;
+
+use eframe::{
+    egui::{self, Layout},
+    epaint::mutex::Mutex,
+};
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+use icy_engine::{attribute, AttributedChar, BufferType, Position, TextPane, UnicodeConverter};
+use icy_engine_egui::BufferView;
+
+use crate::{AnsiEditor, Message, ModalDialog, TerminalResult};
+
+/// The attribute flags a pattern can match or set, visibility is handled separately.
+const ATTRIBUTE_FLAGS: [u16; 10] = [
+    attribute::BOLD,
+    attribute::FAINT,
+    attribute::ITALIC,
+    attribute::BLINK,
+    attribute::UNDERLINE,
+    attribute::DOUBLE_UNDERLINE,
+    attribute::CONCEAL,
+    attribute::CROSSED_OUT,
+    attribute::DOUBLE_HEIGHT,
+    attribute::OVERLINE,
+];
+
+fn get_flag_label(flag: u16) -> String {
+    match flag {
+        attribute::BOLD => fl!(crate::LANGUAGE_LOADER, "find-replace-bold"),
+        attribute::FAINT => fl!(crate::LANGUAGE_LOADER, "find-replace-faint"),
+        attribute::ITALIC => fl!(crate::LANGUAGE_LOADER, "find-replace-italic"),
+        attribute::BLINK => fl!(crate::LANGUAGE_LOADER, "find-replace-blink"),
+        attribute::UNDERLINE => fl!(crate::LANGUAGE_LOADER, "find-replace-underline"),
+        attribute::DOUBLE_UNDERLINE => fl!(crate::LANGUAGE_LOADER, "find-replace-double_underline"),
+        attribute::CONCEAL => fl!(crate::LANGUAGE_LOADER, "find-replace-conceal"),
+        attribute::CROSSED_OUT => fl!(crate::LANGUAGE_LOADER, "find-replace-crossed_out"),
+        attribute::DOUBLE_HEIGHT => fl!(crate::LANGUAGE_LOADER, "find-replace-double_height"),
+        _ => fl!(crate::LANGUAGE_LOADER, "find-replace-overline"),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FindScope {
+    Selection,
+    CurrentLayer,
+    AllLayers,
+}
+
+/// Describes which cells match, `None` fields act as wildcards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharPattern {
+    /// Any of these characters matches, an empty list matches every character.
+    pub chars: Vec<char>,
+    pub foreground: Option<u32>,
+    pub background: Option<u32>,
+    pub font_page: Option<usize>,
+    /// One entry per flag of `ATTRIBUTE_FLAGS`.
+    pub flags: [Option<bool>; ATTRIBUTE_FLAGS.len()],
+}
+
+impl CharPattern {
+    pub fn is_match(&self, ch: AttributedChar) -> bool {
+        if !ch.is_visible() {
+            return false;
+        }
+        (self.chars.is_empty() || self.chars.contains(&ch.ch))
+            && self.foreground.map_or(true, |fg| ch.attribute.get_foreground() == fg)
+            && self.background.map_or(true, |bg| ch.attribute.get_background() == bg)
+            && self.font_page.map_or(true, |page| ch.get_font_page() == page)
+            && self
+                .flags
+                .iter()
+                .zip(ATTRIBUTE_FLAGS)
+                .all(|(value, flag)| value.map_or(true, |is_set| (ch.attribute.attr & flag != 0) == is_set))
+    }
+
+    /// The replacement needs either one character or one character for every searched character.
+    pub fn can_replace_chars(&self, replacement: &CharPattern) -> bool {
+        replacement.chars.len() <= 1 || replacement.chars.len() == self.chars.len()
+    }
+
+    /// Applies the replacement, `None` fields keep the value of the matched cell.
+    /// If search and replacement contain the same number of characters they're mapped one by one.
+    pub fn replace(&self, replacement: &CharPattern, ch: AttributedChar) -> AttributedChar {
+        let mut result = ch;
+        if replacement.chars.len() == 1 {
+            result.ch = replacement.chars[0];
+        } else if !replacement.chars.is_empty() && replacement.chars.len() == self.chars.len() {
+            if let Some(idx) = self.chars.iter().position(|c| *c == ch.ch) {
+                result.ch = replacement.chars[idx];
+            }
+        }
+        if let Some(fg) = replacement.foreground {
+            result.attribute.set_foreground(fg);
+        }
+        if let Some(bg) = replacement.background {
+            result.attribute.set_background(bg);
+        }
+        if let Some(page) = replacement.font_page {
+            result.set_font_page(page);
+        }
+        for (value, flag) in replacement.flags.iter().zip(ATTRIBUTE_FLAGS) {
+            match value {
+                Some(true) => result.attribute.attr |= flag,
+                Some(false) => result.attribute.attr &= !flag,
+                None => {}
+            }
+        }
+        result
+    }
+}
+
+pub struct FindReplaceDialog {
+    should_commit: bool,
+    buffer_view: Arc<Mutex<BufferView>>,
+
+    find_text: String,
+    replace_text: String,
+    find: CharPattern,
+    replace: CharPattern,
+    scope: FindScope,
+
+    counted_pattern: Option<(CharPattern, FindScope)>,
+    match_count: usize,
+}
+
+impl FindReplaceDialog {
+    pub fn new(buffer_view: Arc<Mutex<BufferView>>) -> Self {
+        let scope = if buffer_view.lock().get_edit_state().is_something_selected() {
+            FindScope::Selection
+        } else {
+            FindScope::CurrentLayer
+        };
+        let empty = CharPattern {
+            chars: Vec::new(),
+            foreground: None,
+            background: None,
+            font_page: None,
+            flags: [None; ATTRIBUTE_FLAGS.len()],
+        };
+        FindReplaceDialog {
+            should_commit: false,
+            buffer_view,
+            find_text: String::new(),
+            replace_text: String::new(),
+            find: empty.clone(),
+            replace: empty,
+            scope,
+            counted_pattern: None,
+            match_count: 0,
+        }
+    }
+
+    fn convert_text(&self, text: &str) -> Vec<char> {
+        let lock = self.buffer_view.lock();
+        let buffer_type = lock.get_buffer().buffer_type;
+        let font_page = lock.get_caret().get_font_page();
+        text.chars()
+            .map(|ch| match buffer_type {
+                BufferType::Unicode => ch,
+                BufferType::CP437 => icy_engine::ascii::CP437Converter::default().convert_from_unicode(ch, font_page),
+                BufferType::Petscii => icy_engine::petscii::CharConverter::default().convert_from_unicode(ch, font_page),
+                BufferType::Atascii => icy_engine::atascii::CharConverter::default().convert_from_unicode(ch, font_page),
+                BufferType::Viewdata => icy_engine::viewdata::CharConverter::default().convert_from_unicode(ch, font_page),
+            })
+            .collect()
+    }
+
+    /// Locked layers are never changed. All layers means the visible ones, replacing cells the user
+    /// can't see would be a surprise.
+    fn get_layers(&self, view: &BufferView) -> Vec<usize> {
+        let layers = &view.get_buffer().layers;
+        let is_editable = |layer: &usize| layers.get(*layer).is_some_and(|l| !l.properties.is_locked);
+        match self.scope {
+            FindScope::Selection | FindScope::CurrentLayer => view.get_edit_state().get_current_layer().into_iter().filter(is_editable).collect(),
+            FindScope::AllLayers => (0..layers.len()).filter(is_editable).filter(|layer| layers[*layer].get_is_visible()).collect(),
+        }
+    }
+
+    /// Collects the matching cells of a layer together with their replacement.
+    fn find_matches(&self, view: &BufferView, layer: usize) -> Vec<(Position, AttributedChar)> {
+        let mut result = Vec::new();
+        let Some(cur_layer) = view.get_buffer().layers.get(layer) else {
+            return result;
+        };
+        let use_selection = self.scope == FindScope::Selection && view.get_edit_state().is_something_selected();
+        let offset = cur_layer.get_offset();
+        for y in 0..cur_layer.get_height() {
+            for x in 0..cur_layer.get_width() {
+                let pos = Position::new(x, y);
+                if use_selection && !view.get_edit_state().get_is_selected(pos + offset) {
+                    continue;
+                }
+                let ch = cur_layer.get_char(pos);
+                if self.find.is_match(ch) {
+                    result.push((pos, self.find.replace(&self.replace, ch)));
+                }
+            }
+        }
+        result
+    }
+
+    fn update_count(&mut self) {
+        let key = (self.find.clone(), self.scope);
+        if self.counted_pattern.as_ref() == Some(&key) {
+            return;
+        }
+        let view = self.buffer_view.lock();
+        self.match_count = self.get_layers(&view).into_iter().map(|layer| self.find_matches(&view, layer).len()).sum();
+        drop(view);
+        self.counted_pattern = Some(key);
+    }
+}
+
+fn color_row(ui: &mut egui::Ui, label: String, any_label: &str, value: &mut Option<u32>, palette_len: u32) {
+    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+        ui.label(label);
+    });
+    ui.horizontal(|ui| {
+        let mut is_set = value.is_some();
+        if ui.checkbox(&mut is_set, "").changed() {
+            *value = if is_set { Some(0) } else { None };
+        }
+        if let Some(color) = value {
+            ui.add(egui::DragValue::new(color).clamp_range(0..=palette_len.saturating_sub(1)));
+        } else {
+            ui.label(any_label);
+        }
+    });
+    ui.end_row();
+}
+
+fn flag_row(ui: &mut egui::Ui, label: String, any_label: &str, value: &mut Option<bool>) {
+    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+        ui.label(label);
+    });
+    ui.horizontal(|ui| {
+        ui.selectable_value(value, None, any_label);
+        ui.selectable_value(value, Some(true), fl!(crate::LANGUAGE_LOADER, "find-replace-flag-on"));
+        ui.selectable_value(value, Some(false), fl!(crate::LANGUAGE_LOADER, "find-replace-flag-off"));
+    });
+    ui.end_row();
+}
+
+fn pattern_grid(ui: &mut egui::Ui, id: &str, text: &mut String, pattern: &mut CharPattern, any_label: &str, palette_len: u32) {
+    egui::Grid::new(id).num_columns(2).spacing([4.0, 8.0]).show(ui, |ui| {
+        ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.label(fl!(crate::LANGUAGE_LOADER, "find-replace-chars"));
+        });
+        ui.add(egui::TextEdit::singleline(text).hint_text(any_label.to_string()).char_limit(64));
+        ui.end_row();
+
+        color_row(
+            ui,
+            fl!(crate::LANGUAGE_LOADER, "find-replace-foreground"),
+            any_label,
+            &mut pattern.foreground,
+            palette_len,
+        );
+        color_row(
+            ui,
+            fl!(crate::LANGUAGE_LOADER, "find-replace-background"),
+            any_label,
+            &mut pattern.background,
+            palette_len,
+        );
+
+        ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.label(fl!(crate::LANGUAGE_LOADER, "find-replace-font_page"));
+        });
+        ui.horizontal(|ui| {
+            let mut is_set = pattern.font_page.is_some();
+            if ui.checkbox(&mut is_set, "").changed() {
+                pattern.font_page = if is_set { Some(0) } else { None };
+            }
+            if let Some(page) = &mut pattern.font_page {
+                ui.add(egui::DragValue::new(page).clamp_range(0..=255));
+            } else {
+                ui.label(any_label);
+            }
+        });
+        ui.end_row();
+
+        for (value, flag) in pattern.flags.iter_mut().zip(ATTRIBUTE_FLAGS) {
+            flag_row(ui, get_flag_label(flag), any_label, value);
+        }
+    });
+}
+
+impl ModalDialog for FindReplaceDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut result = false;
+        let modal = Modal::new(ctx, "find_replace_dialog");
+        let palette_len = self.buffer_view.lock().get_buffer().palette.len() as u32;
+        let has_selection = self.buffer_view.lock().get_edit_state().is_something_selected();
+        let any_label = fl!(crate::LANGUAGE_LOADER, "find-replace-any");
+        let keep_label = fl!(crate::LANGUAGE_LOADER, "find-replace-keep");
+
+        modal.show(|ui| {
+            ui.set_width(340.);
+            modal.title(ui, fl!(crate::LANGUAGE_LOADER, "find-replace-title"));
+
+            modal.frame(ui, |ui| {
+                ui.heading(fl!(crate::LANGUAGE_LOADER, "find-replace-find"));
+                pattern_grid(ui, "find_grid", &mut self.find_text, &mut self.find, &any_label, palette_len);
+                ui.add_space(8.0);
+                ui.heading(fl!(crate::LANGUAGE_LOADER, "find-replace-replace"));
+                pattern_grid(ui, "replace_grid", &mut self.replace_text, &mut self.replace, &keep_label, palette_len);
+                ui.add_space(8.0);
+
+                ui.horizontal(|ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "find-replace-scope"));
+                    ui.add_enabled_ui(has_selection, |ui| {
+                        ui.selectable_value(
+                            &mut self.scope,
+                            FindScope::Selection,
+                            fl!(crate::LANGUAGE_LOADER, "find-replace-scope-selection"),
+                        );
+                    });
+                    ui.selectable_value(
+                        &mut self.scope,
+                        FindScope::CurrentLayer,
+                        fl!(crate::LANGUAGE_LOADER, "find-replace-scope-layer"),
+                    );
+                    ui.selectable_value(
+                        &mut self.scope,
+                        FindScope::AllLayers,
+                        fl!(crate::LANGUAGE_LOADER, "find-replace-scope-all_layers"),
+                    )
+                    .on_hover_text(fl!(crate::LANGUAGE_LOADER, "find-replace-scope-all_layers-tooltip"));
+                });
+
+                self.find.chars = self.convert_text(&self.find_text);
+                self.replace.chars = self.convert_text(&self.replace_text);
+                self.update_count();
+                ui.add_space(4.0);
+                ui.label(fl!(crate::LANGUAGE_LOADER, "find-replace-match_count", count = self.match_count));
+                if !self.find.can_replace_chars(&self.replace) {
+                    ui.colored_label(ui.style().visuals.error_fg_color, fl!(crate::LANGUAGE_LOADER, "find-replace-error-length"));
+                }
+            });
+
+            let can_replace = self.match_count > 0 && self.find.can_replace_chars(&self.replace);
+            modal.buttons(ui, |ui| {
+                if ui
+                    .add_enabled(can_replace, egui::Button::new(fl!(crate::LANGUAGE_LOADER, "find-replace-replace_all")))
+                    .clicked()
+                {
+                    self.should_commit = true;
+                    result = true;
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+
+    fn should_commit(&self) -> bool {
+        self.should_commit
+    }
+
+    fn commit(&self, editor: &mut AnsiEditor) -> TerminalResult<Option<Message>> {
+        let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-find_replace"));
+        let cur_layer = editor.get_cur_layer_index()?;
+        let layers = self.get_layers(&editor.buffer_view.lock());
+        for layer in layers {
+            let matches = self.find_matches(&editor.buffer_view.lock(), layer);
+            if matches.is_empty() {
+                continue;
+            }
+            editor.set_cur_layer_index(layer);
+            for (pos, ch) in matches {
+                editor.set_char(pos, ch);
+            }
+        }
+        editor.set_cur_layer_index(cur_layer);
+        Ok(None)
+    }
+}
@@ -251,6 +251,11 @@ impl FontSelector {
 
             if is_font_extensions(&ext) {
                 if let Ok(font) = BitFont::load(path) {
+                    if ext == "psf" {
+                        if let Ok(data) = fs::read(path) {
+                            crate::register_psf_unicode_table(&font, &data);
+                        }
+                    }
                     fonts.push(font);
                 }
             }
@@ -286,6 +291,7 @@ impl FontSelector {
                                     let mut data = Vec::new();
                                     file.read_to_end(&mut data).unwrap_or_default();
                                     if let Ok(font) = BitFont::from_bytes(file_name, &data) {
+                                        crate::register_psf_unicode_table(&font, &data);
                                         fonts.push(font)
                                     }
                                 } else if ext == "zip" {
@@ -0,0 +1,371 @@
+use std::path::PathBuf;
+
+use eframe::egui::{self, Layout, TextureHandle};
+use egui_file::FileDialog;
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+use icy_engine::{editor::EditState, BitFont, Buffer, FontType, Size, TextPane, TheDrawFont, SAUCE_FONT_NAMES};
+
+use crate::{
+    create_image, get_tdf_font_name, GlyphImporter, GlyphSource, MainWindow, Message, ModalDialog, TdfRecipe, TerminalResult, FULL_BLOCK, LIGHT_SHADE,
+    TDF_NAME_LENGTH,
+};
+
+/// Block characters a pixel or shadow can be drawn with.
+const BLOCK_CHARS: [u8; 4] = [FULL_BLOCK, 0xB2, 0xB1, LIGHT_SHADE];
+
+pub struct GenerateTdfFontDialog {
+    should_commit: bool,
+    recipe: TdfRecipe,
+    name: String,
+
+    sauce_font: usize,
+    use_file: bool,
+    file_name: String,
+    file_font: Option<BitFont>,
+    importer: Option<GlyphImporter>,
+    file_dialog: Option<FileDialog>,
+    load_error: Option<String>,
+
+    preview: Option<TextureHandle>,
+    preview_dirty: bool,
+}
+
+impl Default for GenerateTdfFontDialog {
+    fn default() -> Self {
+        Self {
+            should_commit: false,
+            recipe: TdfRecipe::default(),
+            name: get_tdf_font_name(SAUCE_FONT_NAMES[0]),
+            sauce_font: 0,
+            use_file: false,
+            file_name: String::new(),
+            file_font: None,
+            importer: None,
+            file_dialog: None,
+            load_error: None,
+            preview: None,
+            preview_dirty: true,
+        }
+    }
+}
+
+fn get_block_label(ch: u8) -> String {
+    let unicode = match ch {
+        0xDB => '█',
+        0xB2 => '▓',
+        0xB1 => '▒',
+        _ => '░',
+    };
+    format!("{unicode} 0x{ch:02X}")
+}
+
+fn get_gradient_presets() -> [(String, &'static [u8]); 4] {
+    [
+        (fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-gradient-ice"), &[15, 11, 3, 9, 1]),
+        (fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-gradient-fire"), &[15, 14, 12, 4]),
+        (fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-gradient-steel"), &[15, 7, 8]),
+        (fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-gradient-plain"), &[7]),
+    ]
+}
+
+impl GenerateTdfFontDialog {
+    fn load(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        let ext = path.extension().unwrap_or_default().to_str().unwrap_or_default().to_ascii_lowercase();
+        let data = std::fs::read(&path)?;
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if ext == "ttf" || ext == "otf" {
+            let source = GlyphSource::from_bytes(&ext, data)?;
+            self.importer = Some(GlyphImporter::new(source, Size::new(16, 16)));
+            self.file_font = None;
+        } else {
+            self.file_font = Some(BitFont::from_bytes(file_name.clone(), &data)?);
+            self.importer = None;
+        }
+        self.name = get_tdf_font_name(path.file_stem().unwrap_or_default().to_str().unwrap_or_default());
+        self.file_name = file_name;
+        self.use_file = true;
+        self.preview_dirty = true;
+        Ok(())
+    }
+
+    /// TrueType fonts are rasterized into a bit font first.
+    fn get_source_font(&self) -> Option<BitFont> {
+        if self.use_file {
+            if let Some(importer) = &self.importer {
+                let mut template = BitFont::from_sauce_name("IBM VGA").ok()?;
+                for glyph in template.glyphs.values_mut() {
+                    glyph.data.fill(0);
+                }
+                return Some(importer.import_into(&template));
+            }
+            return self.file_font.clone();
+        }
+        BitFont::from_sauce_name(SAUCE_FONT_NAMES[self.sauce_font]).ok()
+    }
+
+    fn generate(&self) -> Option<TheDrawFont> {
+        self.get_source_font().map(|font| self.recipe.generate(&font, &self.name))
+    }
+
+    fn show_settings(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        egui::Grid::new("some_unique_id").num_columns(2).spacing([4.0, 8.0]).show(ui, |ui| {
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-source"));
+            });
+            ui.horizontal(|ui| {
+                changed |= ui.radio_value(&mut self.use_file, false, "").changed();
+                let old_font = self.sauce_font;
+                egui::ComboBox::from_id_source("generate_tdf_sauce_font")
+                    .selected_text(SAUCE_FONT_NAMES[self.sauce_font])
+                    .show_ui(ui, |ui| {
+                        for (i, name) in SAUCE_FONT_NAMES.iter().enumerate() {
+                            ui.selectable_value(&mut self.sauce_font, i, *name);
+                        }
+                    });
+                if old_font != self.sauce_font {
+                    self.use_file = false;
+                    self.name = get_tdf_font_name(SAUCE_FONT_NAMES[self.sauce_font]);
+                    changed = true;
+                }
+            });
+            ui.end_row();
+
+            ui.label("");
+            ui.horizontal(|ui| {
+                let has_file = self.file_font.is_some() || self.importer.is_some();
+                if has_file {
+                    changed |= ui.radio_value(&mut self.use_file, true, &self.file_name).changed();
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-open_file")).clicked() {
+                    let mut dialog = FileDialog::open_file(None::<PathBuf>);
+                    dialog.open();
+                    self.file_dialog = Some(dialog);
+                }
+            });
+            ui.end_row();
+
+            if self.use_file {
+                if let Some(importer) = &mut self.importer {
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-glyph_height"));
+                    });
+                    let mut height = importer.size.height;
+                    if ui.add(egui::Slider::new(&mut height, 4..=32)).changed() {
+                        importer.size = Size::new(height.min(16), height);
+                        importer.fit_font_size();
+                        changed = true;
+                    }
+                    ui.end_row();
+                }
+            }
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_name_label"));
+            });
+            changed |= ui.add(egui::TextEdit::singleline(&mut self.name).char_limit(TDF_NAME_LENGTH)).changed();
+            ui.end_row();
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_label"));
+            });
+            ui.horizontal(|ui| {
+                let is_color = matches!(self.recipe.font_type, FontType::Color);
+                if ui.radio(!is_color, fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_block")).clicked() {
+                    self.recipe.font_type = FontType::Block;
+                    changed = true;
+                }
+                if ui.radio(is_color, fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_color")).clicked() {
+                    self.recipe.font_type = FontType::Color;
+                    changed = true;
+                }
+            });
+            ui.end_row();
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-pixel_char"));
+            });
+            ui.horizontal(|ui| {
+                for ch in BLOCK_CHARS {
+                    changed |= ui.selectable_value(&mut self.recipe.pixel_char, ch, get_block_label(ch)).changed();
+                }
+            });
+            ui.end_row();
+
+            ui.label("");
+            changed |= ui
+                .checkbox(&mut self.recipe.half_blocks, fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-half_blocks"))
+                .changed();
+            ui.end_row();
+
+            ui.label("");
+            changed |= ui
+                .checkbox(&mut self.recipe.proportional, fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-proportional"))
+                .changed();
+            ui.end_row();
+
+            if matches!(self.recipe.font_type, FontType::Color) {
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-gradient"));
+                });
+                ui.horizontal(|ui| {
+                    for (name, colors) in get_gradient_presets() {
+                        if ui.button(name).clicked() {
+                            self.recipe.gradient = colors.to_vec();
+                            changed = true;
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("");
+                ui.horizontal(|ui| {
+                    for color in &mut self.recipe.gradient {
+                        changed |= ui.add(egui::DragValue::new(color).clamp_range(0..=15)).changed();
+                    }
+                    if ui.button("+").clicked() {
+                        let last = self.recipe.gradient.last().copied().unwrap_or(7);
+                        self.recipe.gradient.push(last);
+                        changed = true;
+                    }
+                    if self.recipe.gradient.len() > 1 && ui.button("-").clicked() {
+                        self.recipe.gradient.pop();
+                        changed = true;
+                    }
+                });
+                ui.end_row();
+
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-background"));
+                });
+                changed |= ui.add(egui::DragValue::new(&mut self.recipe.background).clamp_range(0..=15)).changed();
+                ui.end_row();
+            }
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-shadow"));
+            });
+            ui.horizontal(|ui| {
+                changed |= ui.add(egui::DragValue::new(&mut self.recipe.shadow_offset.0).clamp_range(-3..=3)).changed();
+                changed |= ui.add(egui::DragValue::new(&mut self.recipe.shadow_offset.1).clamp_range(-3..=3)).changed();
+            });
+            ui.end_row();
+
+            if self.recipe.shadow_offset != (0, 0) {
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-shadow_char"));
+                });
+                ui.horizontal(|ui| {
+                    for ch in BLOCK_CHARS {
+                        changed |= ui.selectable_value(&mut self.recipe.shadow_char, ch, get_block_label(ch)).changed();
+                    }
+                });
+                ui.end_row();
+
+                if matches!(self.recipe.font_type, FontType::Color) {
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-shadow_color"));
+                    });
+                    changed |= ui.add(egui::DragValue::new(&mut self.recipe.shadow_color).clamp_range(0..=15)).changed();
+                    ui.end_row();
+                }
+            }
+        });
+        self.preview_dirty |= changed;
+    }
+
+    fn show_preview(&mut self, ui: &mut egui::Ui) {
+        if self.preview_dirty || self.preview.is_none() {
+            self.preview_dirty = false;
+            self.preview = None;
+            if let Some(font) = self.generate() {
+                let mut state = EditState::from_buffer(Buffer::new((160, 24)));
+                for ch in fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-preview_text").bytes() {
+                    if let Some(size) = font.render(&mut state, ch) {
+                        let mut pos = state.get_caret().get_position();
+                        pos.x += size.width + font.spaces;
+                        state.get_caret_mut().set_position(pos);
+                    } else if ch == b' ' {
+                        let mut pos = state.get_caret().get_position();
+                        pos.x += 3;
+                        state.get_caret_mut().set_position(pos);
+                    }
+                }
+                // only show the used part of the buffer
+                let used_width = state.get_caret().get_position().x.max(1);
+                let buf = state.get_buffer_mut();
+                let size = Size::new(buf.get_width().min(used_width), buf.get_height());
+                buf.set_size(size);
+                buf.layers[0].set_size(size);
+                self.preview = Some(create_image(ui.ctx(), state.get_buffer()));
+            }
+        }
+        if let Some(preview) = &self.preview {
+            let size = preview.size_vec2();
+            let scale = (600.0 / size.x).min(1.0);
+            ui.image((preview.id(), size * scale));
+        }
+    }
+}
+
+impl ModalDialog for GenerateTdfFontDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        if let Some(dialog) = &mut self.file_dialog {
+            if dialog.show(ctx).selected() {
+                let path = dialog.path().map(|p| p.to_path_buf());
+                self.file_dialog = None;
+                if let Some(path) = path {
+                    self.load_error = self.load(path).err().map(|err| format!("{err}"));
+                }
+            } else if matches!(dialog.state(), egui_file::State::Cancelled | egui_file::State::Closed) {
+                self.file_dialog = None;
+            }
+            return false;
+        }
+
+        let mut result = false;
+        let modal = Modal::new(ctx, "generate_tdf_font_dialog");
+
+        modal.show(|ui| {
+            modal.title(ui, fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-title"));
+
+            modal.frame(ui, |ui| {
+                self.show_settings(ui);
+                if let Some(err) = &self.load_error {
+                    ui.colored_label(ui.style().visuals.error_fg_color, err);
+                }
+                ui.add_space(8.0);
+                self.show_preview(ui);
+            });
+
+            modal.buttons(ui, |ui| {
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-generate")).clicked() {
+                    self.should_commit = true;
+                    result = true;
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+
+    fn should_commit(&self) -> bool {
+        self.should_commit
+    }
+
+    fn commit_self(&self, window: &mut MainWindow<'_>) -> TerminalResult<Option<Message>> {
+        let Some(font) = self.generate() else {
+            return Err(anyhow::anyhow!(fl!(crate::LANGUAGE_LOADER, "generate-tdf-dialog-no_source")));
+        };
+        if let Some(doc) = window.get_active_document() {
+            if let Some(editor) = doc.lock().get_char_font_editor_mut() {
+                editor.add_generated_font(font);
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use eframe::egui;
+use egui_file::FileDialog;
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+
+use crate::{get_font_files, GlyphPatch, MainWindow, Message, ModalDialog, TerminalResult};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlyphPatchMode {
+    /// Save the marked glyphs of the edited font as patch file.
+    Save,
+    /// Apply a patch file to the edited font.
+    Apply,
+    /// Apply the marked glyphs of the edited font to all fonts in a directory.
+    PatchDirectory,
+}
+
+pub struct GlyphPatchDialog {
+    mode: GlyphPatchMode,
+    patch: GlyphPatch,
+    dialog: FileDialog,
+    path: Option<PathBuf>,
+    /// Font files of the selected directory, they are only patched after the user confirmed the list.
+    files: Option<Vec<PathBuf>>,
+    confirmed: bool,
+}
+
+impl GlyphPatchDialog {
+    pub fn new(mode: GlyphPatchMode, patch: GlyphPatch) -> Self {
+        let mut dialog = match mode {
+            GlyphPatchMode::Save => FileDialog::save_file(Some(PathBuf::from("glyphs.gpatch"))),
+            GlyphPatchMode::Apply => FileDialog::open_file(None::<PathBuf>),
+            GlyphPatchMode::PatchDirectory => FileDialog::select_folder(None::<PathBuf>),
+        };
+        dialog.open();
+        Self {
+            mode,
+            patch,
+            dialog,
+            path: None,
+            files: None,
+            confirmed: false,
+        }
+    }
+
+    fn show_confirmation(&mut self, ctx: &egui::Context) -> bool {
+        let Some(files) = &self.files else {
+            return true;
+        };
+        let mut result = false;
+        let modal = Modal::new(ctx, "glyph_patch_confirm_dialog");
+        modal.show(|ui| {
+            modal.title(ui, fl!(crate::LANGUAGE_LOADER, "glyph-patch-confirm-title"));
+            modal.frame(ui, |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "glyph-patch-confirm-text", count = files.len()));
+                ui.add_space(4.0);
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    for file in files {
+                        ui.label(file.file_name().unwrap_or_default().to_string_lossy());
+                    }
+                });
+            });
+            modal.buttons(ui, |ui| {
+                if ui
+                    .add_enabled(!files.is_empty(), egui::Button::new(fl!(crate::LANGUAGE_LOADER, "glyph-patch-confirm-patch")))
+                    .clicked()
+                {
+                    self.confirmed = true;
+                    result = true;
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+}
+
+impl ModalDialog for GlyphPatchDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        if self.files.is_some() {
+            return self.show_confirmation(ctx);
+        }
+        if self.dialog.show(ctx).selected() {
+            self.path = self.dialog.path().map(|path| path.to_path_buf());
+            if self.mode == GlyphPatchMode::PatchDirectory {
+                if let Some(path) = &self.path {
+                    match get_font_files(path) {
+                        Ok(files) => {
+                            self.files = Some(files);
+                            return false;
+                        }
+                        Err(err) => log::error!("Error reading {}: {err}", path.display()),
+                    }
+                }
+            }
+            return true;
+        }
+        matches!(self.dialog.state(), egui_file::State::Cancelled | egui_file::State::Closed)
+    }
+
+    fn should_commit(&self) -> bool {
+        match self.mode {
+            GlyphPatchMode::PatchDirectory => self.confirmed,
+            GlyphPatchMode::Save | GlyphPatchMode::Apply => self.path.is_some(),
+        }
+    }
+
+    fn commit_self(&self, window: &mut MainWindow<'_>) -> TerminalResult<Option<Message>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+        match self.mode {
+            GlyphPatchMode::Save => {
+                std::fs::write(path.with_extension("gpatch"), self.patch.to_bytes())?;
+            }
+            GlyphPatchMode::Apply => {
+                let patch = GlyphPatch::from_bytes(&std::fs::read(path)?)?;
+                if let Some(doc) = window.get_active_document() {
+                    if let Some(editor) = doc.lock().get_bitfont_editor_mut() {
+                        editor.apply_patch(&patch)?;
+                    }
+                }
+            }
+            GlyphPatchMode::PatchDirectory => {
+                let (patched, errors) = self.patch.patch_files(self.files.as_deref().unwrap_or_default());
+                log::info!("Patched {patched} font files in {}", path.display());
+                if !errors.is_empty() {
+                    return Ok(Some(Message::ShowError(fl!(
+                        crate::LANGUAGE_LOADER,
+                        "glyph-patch-error-files",
+                        patched = patched,
+                        errors = errors.join("\n")
+                    ))));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        mpsc::{Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+use eframe::egui::{self, Layout, TextureHandle};
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+use icy_engine::{Buffer, PaletteMode, TextPane};
+use image::RgbaImage;
+
+use crate::{
+    add_child, create_image,
+    util::image_converter::{ConversionMode, Dithering, ImageConverter},
+    AnsiEditor, MainWindow, Message, ModalDialog, TerminalResult,
+};
+
+enum ImageTarget {
+    /// Paste the converted image as floating layer into the active editor.
+    Paste,
+    /// Open the converted image from the file as new document.
+    NewDocument(PathBuf),
+}
+
+pub struct ImageConvertDialog {
+    should_commit: bool,
+    target: ImageTarget,
+    image: Arc<RgbaImage>,
+    converter: ImageConverter,
+    keep_aspect_ratio: bool,
+    preview: Option<TextureHandle>,
+    preview_dirty: bool,
+    /// The preview is converted on a worker thread, only one conversion runs at a time.
+    preview_job: Option<Receiver<Buffer>>,
+}
+
+impl ImageConvertDialog {
+    pub fn new(image: RgbaImage, buf: &Buffer) -> Self {
+        let mut converter = ImageConverter::new(buf);
+        converter.width = buf.get_width().clamp(1, 160);
+        converter.height = converter.get_height_for_width(&image);
+        Self {
+            should_commit: false,
+            target: ImageTarget::Paste,
+            image: Arc::new(image),
+            converter,
+            keep_aspect_ratio: true,
+            preview: None,
+            preview_dirty: true,
+            preview_job: None,
+        }
+    }
+
+    pub fn new_document(image: RgbaImage, path: PathBuf) -> Self {
+        let mut buf = Buffer::create((80, 25));
+        buf.ice_mode = icy_engine::IceMode::Ice;
+        buf.palette_mode = PaletteMode::Fixed16;
+        let mut dialog = Self::new(image, &buf);
+        dialog.target = ImageTarget::NewDocument(path);
+        dialog
+    }
+
+    fn show_settings(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        egui::Grid::new("some_unique_id").num_columns(2).spacing([4.0, 8.0]).show(ui, |ui| {
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-mode"));
+            });
+            egui::ComboBox::from_id_source("image_convert_mode")
+                .selected_text(get_mode_label(self.converter.mode))
+                .show_ui(ui, |ui| {
+                    for mode in [
+                        ConversionMode::FullBlock,
+                        ConversionMode::HalfBlock,
+                        ConversionMode::QuarterBlock,
+                        ConversionMode::Shade,
+                        ConversionMode::Ascii,
+                    ] {
+                        changed |= ui.selectable_value(&mut self.converter.mode, mode, get_mode_label(mode)).changed();
+                    }
+                });
+            ui.end_row();
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-dithering"));
+            });
+            egui::ComboBox::from_id_source("image_convert_dithering")
+                .selected_text(get_dithering_label(self.converter.dithering))
+                .show_ui(ui, |ui| {
+                    for dithering in [Dithering::None, Dithering::FloydSteinberg, Dithering::Ordered] {
+                        changed |= ui
+                            .selectable_value(&mut self.converter.dithering, dithering, get_dithering_label(dithering))
+                            .changed();
+                    }
+                });
+            ui.end_row();
+
+            if matches!(self.target, ImageTarget::NewDocument(_)) {
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-palette_mode"));
+                });
+                egui::ComboBox::from_id_source("image_convert_palette_mode")
+                    .selected_text(get_palette_mode_label(self.converter.palette_mode))
+                    .show_ui(ui, |ui| {
+                        for mode in [PaletteMode::Fixed16, PaletteMode::Free8, PaletteMode::Free16, PaletteMode::RGB] {
+                            if ui.selectable_label(self.converter.palette_mode == mode, get_palette_mode_label(mode)).clicked() {
+                                self.converter.palette_mode = mode;
+                                self.converter.fit_palette(&self.image);
+                                changed = true;
+                            }
+                        }
+                    });
+                ui.end_row();
+            }
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "new-file-width"));
+            });
+            if ui.add(egui::DragValue::new(&mut self.converter.width).clamp_range(1..=1000)).changed() {
+                if self.keep_aspect_ratio {
+                    self.converter.height = self.converter.get_height_for_width(&self.image);
+                }
+                changed = true;
+            }
+            ui.end_row();
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "new-file-height"));
+            });
+            changed |= ui
+                .add_enabled(!self.keep_aspect_ratio, egui::DragValue::new(&mut self.converter.height).clamp_range(1..=1000))
+                .changed();
+            ui.end_row();
+
+            ui.label("");
+            if ui
+                .checkbox(
+                    &mut self.keep_aspect_ratio,
+                    fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-keep_aspect_ratio"),
+                )
+                .changed()
+                && self.keep_aspect_ratio
+            {
+                self.converter.height = self.converter.get_height_for_width(&self.image);
+                changed = true;
+            }
+            ui.end_row();
+        });
+        self.preview_dirty |= changed;
+    }
+
+    fn show_preview(&mut self, ui: &mut egui::Ui) {
+        if let Some(job) = &self.preview_job {
+            match job.try_recv() {
+                Ok(buf) => {
+                    self.preview = Some(create_image(ui.ctx(), &buf));
+                    self.preview_job = None;
+                }
+                Err(TryRecvError::Empty) => ui.ctx().request_repaint(),
+                Err(TryRecvError::Disconnected) => self.preview_job = None,
+            }
+        }
+        // settings changed while converting get picked up once the running conversion is done
+        if self.preview_dirty && self.preview_job.is_none() {
+            self.preview_dirty = false;
+            let (tx, rx) = std::sync::mpsc::channel();
+            let converter = self.converter.clone();
+            let image = self.image.clone();
+            let ctx = ui.ctx().clone();
+            thread::spawn(move || {
+                if tx.send(converter.convert(&image)).is_ok() {
+                    ctx.request_repaint();
+                }
+            });
+            self.preview_job = Some(rx);
+        }
+        ui.vertical(|ui| {
+            if let Some(preview) = &self.preview {
+                let size = preview.size_vec2();
+                let scale = (400.0 / size.x).min(300.0 / size.y).min(1.0);
+                ui.image((preview.id(), size * scale));
+            }
+            if self.preview_job.is_some() {
+                ui.spinner();
+            }
+        });
+    }
+}
+
+fn get_mode_label(mode: ConversionMode) -> String {
+    match mode {
+        ConversionMode::FullBlock => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-mode-full_block"),
+        ConversionMode::HalfBlock => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-mode-half_block"),
+        ConversionMode::QuarterBlock => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-mode-quarter_block"),
+        ConversionMode::Shade => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-mode-shade"),
+        ConversionMode::Ascii => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-mode-ascii"),
+    }
+}
+
+fn get_palette_mode_label(mode: PaletteMode) -> String {
+    match mode {
+        PaletteMode::Fixed16 => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-palette_mode-fixed16"),
+        PaletteMode::Free8 => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-palette_mode-free8"),
+        PaletteMode::Free16 => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-palette_mode-free16"),
+        PaletteMode::RGB => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-palette_mode-rgb"),
+    }
+}
+
+fn get_dithering_label(dithering: Dithering) -> String {
+    match dithering {
+        Dithering::None => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-dithering-none"),
+        Dithering::FloydSteinberg => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-dithering-floyd_steinberg"),
+        Dithering::Ordered => fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-dithering-ordered"),
+    }
+}
+
+impl ModalDialog for ImageConvertDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut result = false;
+        let modal = Modal::new(ctx, "image_convert_dialog");
+
+        modal.show(|ui| {
+            modal.title(ui, fl!(crate::LANGUAGE_LOADER, "image-convert-dialog-title"));
+
+            modal.frame(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        self.show_settings(ui);
+                    });
+                    ui.add_space(8.0);
+                    self.show_preview(ui);
+                });
+                ui.add_space(4.0);
+            });
+
+            modal.buttons(ui, |ui| {
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-ok")).clicked() {
+                    self.should_commit = true;
+                    result = true;
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+
+    fn should_commit(&self) -> bool {
+        self.should_commit
+    }
+
+    fn commit(&self, editor: &mut AnsiEditor) -> TerminalResult<Option<Message>> {
+        if matches!(self.target, ImageTarget::Paste) {
+            let buf = self.converter.convert(&self.image);
+            editor.paste_buffer(buf)?;
+        }
+        Ok(None)
+    }
+
+    fn commit_self(&self, window: &mut MainWindow<'_>) -> TerminalResult<Option<Message>> {
+        if let ImageTarget::NewDocument(path) = &self.target {
+            let mut buf = self.converter.convert(&self.image);
+            buf.is_terminal_buffer = false;
+            buf.font_mode = icy_engine::FontMode::Sauce;
+            if let Some(title) = path.file_stem() {
+                buf.layers[0].set_title(title.to_string_lossy().to_string());
+            }
+            let id = window.create_id();
+            let editor = AnsiEditor::new(&window.gl, id, buf);
+            add_child(&mut window.document_tree, None, Box::new(editor));
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,300 @@
+use std::path::PathBuf;
+
+use eframe::{
+    egui::{self, Layout, RichText, Sense, TextureHandle},
+    epaint::{Color32, ColorImage, TextureOptions, Vec2},
+};
+use egui_file::FileDialog;
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+use icy_engine::Size;
+
+use crate::{get_glyph_pixel, GlyphImporter, GlyphSource, MainWindow, Message, ModalDialog, TerminalResult};
+
+const PREVIEW_SCALE: usize = 2;
+
+pub struct ImportGlyphsDialog {
+    should_commit: bool,
+    size: Size,
+    glyph_count: usize,
+    file_dialog: Option<FileDialog>,
+    importer: Option<GlyphImporter>,
+    selected_glyph: usize,
+    preview: Option<TextureHandle>,
+    preview_dirty: bool,
+    load_error: Option<String>,
+}
+
+impl ImportGlyphsDialog {
+    pub fn new(size: Size, glyph_count: usize) -> Self {
+        let mut dialog = FileDialog::open_file(None::<PathBuf>);
+        dialog.open();
+        Self {
+            should_commit: false,
+            size,
+            glyph_count,
+            file_dialog: Some(dialog),
+            importer: None,
+            selected_glyph: 65,
+            preview: None,
+            preview_dirty: true,
+            load_error: None,
+        }
+    }
+
+    fn load(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        let ext = path.extension().unwrap_or_default().to_str().unwrap_or_default().to_ascii_lowercase();
+        let data = std::fs::read(&path)?;
+        let source = GlyphSource::from_bytes(&ext, data)?;
+        self.importer = Some(GlyphImporter::new(source, self.size));
+        self.preview_dirty = true;
+        Ok(())
+    }
+
+    fn create_preview(&self, ctx: &egui::Context, importer: &GlyphImporter) -> TextureHandle {
+        let scale = self.get_preview_scale();
+        let (w, h) = (importer.size.width as usize + 1, importer.size.height as usize + 1);
+        let rows = self.get_preview_rows();
+        let (width, height) = (16 * w * scale, rows * h * scale);
+        let mut image = ColorImage::new([width, height], Color32::from_gray(40));
+        for i in 0..rows * 16 {
+            let (cx, cy) = ((i % 16) * w, (i / 16) * h);
+            let (fg, bg) = if i == self.selected_glyph {
+                (Color32::BLACK, Color32::YELLOW)
+            } else {
+                (Color32::WHITE, Color32::BLACK)
+            };
+            let data = importer.get_glyph(i);
+            for y in 0..importer.size.height as usize {
+                for x in 0..importer.size.width as usize {
+                    let col = match &data {
+                        Some(data) if get_glyph_pixel(data, importer.size.width, x as i32, y as i32) => fg,
+                        Some(_) => bg,
+                        None => Color32::DARK_RED,
+                    };
+                    for sy in 0..scale {
+                        for sx in 0..scale {
+                            image[((cx + x) * scale + sx, (cy + y) * scale + sy)] = col;
+                        }
+                    }
+                }
+            }
+        }
+        ctx.load_texture("import_glyphs_preview", image, TextureOptions::NEAREST)
+    }
+
+    /// 512 glyph previews are shown unscaled so they fit on screen.
+    fn get_preview_scale(&self) -> usize {
+        if self.glyph_count > 256 {
+            1
+        } else {
+            PREVIEW_SCALE
+        }
+    }
+
+    fn get_preview_rows(&self) -> usize {
+        ((self.glyph_count + 15) / 16).max(1)
+    }
+
+    fn show_settings(&mut self, ui: &mut egui::Ui) {
+        let Some(importer) = &mut self.importer else {
+            return;
+        };
+        let mut changed = false;
+        egui::Grid::new("some_unique_id").num_columns(2).spacing([4.0, 8.0]).show(ui, |ui| {
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-cell_size"));
+            });
+            ui.horizontal(|ui| {
+                changed |= ui.add(egui::DragValue::new(&mut importer.size.width).clamp_range(2..=16)).changed();
+                ui.label("×");
+                changed |= ui.add(egui::DragValue::new(&mut importer.size.height).clamp_range(2..=19)).changed();
+            });
+            ui.end_row();
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-threshold"));
+            });
+            changed |= ui.add(egui::Slider::new(&mut importer.threshold, 1..=255)).changed();
+            ui.end_row();
+
+            ui.label("");
+            changed |= ui
+                .checkbox(&mut importer.invert, fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-invert"))
+                .changed();
+            ui.end_row();
+
+            if importer.is_true_type() {
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-font_size"));
+                });
+                changed |= ui
+                    .add(egui::DragValue::new(&mut importer.font_size).clamp_range(1.0..=100.0).speed(0.1))
+                    .changed();
+                ui.end_row();
+
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-baseline"));
+                });
+                changed |= ui.add(egui::DragValue::new(&mut importer.baseline).clamp_range(-100..=100)).changed();
+                ui.end_row();
+
+                ui.label("");
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-fit_size")).clicked() {
+                    importer.fit_font_size();
+                    changed = true;
+                }
+                ui.end_row();
+            } else {
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-origin"));
+                });
+                ui.horizontal(|ui| {
+                    changed |= ui.add(egui::DragValue::new(&mut importer.origin.0)).changed();
+                    changed |= ui.add(egui::DragValue::new(&mut importer.origin.1)).changed();
+                });
+                ui.end_row();
+
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-grid_cell"));
+                });
+                ui.horizontal(|ui| {
+                    changed |= ui.add(egui::DragValue::new(&mut importer.cell_size.0).clamp_range(1..=256)).changed();
+                    ui.label("×");
+                    changed |= ui.add(egui::DragValue::new(&mut importer.cell_size.1).clamp_range(1..=256)).changed();
+                });
+                ui.end_row();
+
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-spacing"));
+                });
+                ui.horizontal(|ui| {
+                    changed |= ui.add(egui::DragValue::new(&mut importer.spacing.0).clamp_range(0..=256)).changed();
+                    changed |= ui.add(egui::DragValue::new(&mut importer.spacing.1).clamp_range(0..=256)).changed();
+                });
+                ui.end_row();
+
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-columns"));
+                });
+                changed |= ui.add(egui::DragValue::new(&mut importer.columns).clamp_range(1..=256)).changed();
+                ui.end_row();
+            }
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-nudge", glyph = self.selected_glyph));
+            });
+            ui.horizontal(|ui| {
+                let nudge = &mut importer.nudge[self.selected_glyph];
+                for (label, dx, dy) in [("⬅", -1, 0), ("➡", 1, 0), ("⬆", 0, -1), ("⬇", 0, 1)] {
+                    if ui.button(label).clicked() {
+                        nudge.0 += dx;
+                        nudge.1 += dy;
+                        changed = true;
+                    }
+                }
+                ui.label(RichText::new(format!("{}, {}", nudge.0, nudge.1)).small());
+            });
+            ui.end_row();
+        });
+        self.preview_dirty |= changed;
+    }
+
+    fn show_preview(&mut self, ui: &mut egui::Ui) {
+        let Some(importer) = &self.importer else {
+            return;
+        };
+        if self.preview_dirty || self.preview.is_none() {
+            self.preview_dirty = false;
+            self.preview = Some(self.create_preview(ui.ctx(), importer));
+        }
+        let Some(preview) = &self.preview else {
+            return;
+        };
+        let response = ui.add(egui::Image::new((preview.id(), preview.size_vec2())).sense(Sense::click()));
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let scale = self.get_preview_scale();
+                let cell = Vec2::new(
+                    ((importer.size.width + 1) as usize * scale) as f32,
+                    ((importer.size.height + 1) as usize * scale) as f32,
+                );
+                let p = (pos - response.rect.min) / cell;
+                let (x, y) = ((p.x as usize).min(15), (p.y as usize).min(self.get_preview_rows() - 1));
+                self.selected_glyph = x + y * 16;
+                self.preview_dirty = true;
+            }
+        }
+    }
+}
+
+impl ModalDialog for ImportGlyphsDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        if let Some(dialog) = &mut self.file_dialog {
+            if dialog.show(ctx).selected() {
+                let path = dialog.path().map(|p| p.to_path_buf());
+                self.file_dialog = None;
+                if let Some(path) = path {
+                    if let Err(err) = self.load(path) {
+                        // reported by commit_self
+                        self.load_error = Some(format!("{err}"));
+                        self.should_commit = true;
+                        return true;
+                    }
+                }
+            } else if matches!(dialog.state(), egui_file::State::Cancelled | egui_file::State::Closed) {
+                return true;
+            }
+            return false;
+        }
+        if self.importer.is_none() {
+            return true;
+        }
+
+        let mut result = false;
+        let modal = Modal::new(ctx, "import_glyphs_dialog");
+
+        modal.show(|ui| {
+            modal.title(ui, fl!(crate::LANGUAGE_LOADER, "import-glyphs-dialog-title"));
+
+            modal.frame(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        self.show_settings(ui);
+                    });
+                    ui.add_space(8.0);
+                    self.show_preview(ui);
+                });
+                ui.add_space(4.0);
+            });
+
+            modal.buttons(ui, |ui| {
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-ok")).clicked() {
+                    self.should_commit = true;
+                    result = true;
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+
+    fn should_commit(&self) -> bool {
+        self.should_commit
+    }
+
+    fn commit_self(&self, window: &mut MainWindow<'_>) -> TerminalResult<Option<Message>> {
+        if let Some(err) = &self.load_error {
+            return Err(anyhow::anyhow!("{err}"));
+        }
+        if let (Some(importer), Some(doc)) = (&self.importer, window.get_active_document()) {
+            if let Some(editor) = doc.lock().get_bitfont_editor_mut() {
+                editor.import_glyphs(importer)?;
+            }
+        }
+        Ok(None)
+    }
+}
@@ -25,6 +25,24 @@ pub use edit_layer_dialog::*;
 mod save_file_dialog;
 pub use save_file_dialog::*;
 
+mod bitfont_save_dialog;
+pub use bitfont_save_dialog::*;
+
+mod import_glyphs_dialog;
+pub use import_glyphs_dialog::*;
+
+mod export_font_dialog;
+pub use export_font_dialog::*;
+
+mod compare_font_dialog;
+pub use compare_font_dialog::*;
+
+mod glyph_patch_dialog;
+pub use glyph_patch_dialog::*;
+
+mod generate_tdf_font_dialog;
+pub use generate_tdf_font_dialog::*;
+
 mod select_tdf_font_dialog;
 pub use select_tdf_font_dialog::*;
 
@@ -51,3 +69,18 @@ pub use select_palette_dialog::*;
 
 mod settings_dialog;
 pub use settings_dialog::*;
+
+mod find_replace_dialog;
+pub use find_replace_dialog::*;
+
+mod rename_layer_group_dialog;
+pub use rename_layer_group_dialog::*;
+
+mod save_selection_dialog;
+pub use save_selection_dialog::*;
+
+mod modify_selection_dialog;
+pub use modify_selection_dialog::*;
+
+mod image_convert_dialog;
+pub use image_convert_dialog::*;
@@ -0,0 +1,65 @@
+use eframe::egui::{self, Layout};
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+
+use crate::{AnsiEditor, Message, ModalDialog, ModifySelection, TerminalResult};
+
+pub struct ModifySelectionDialog {
+    should_commit: bool,
+    modify: ModifySelection,
+    amount: i32,
+}
+
+impl ModifySelectionDialog {
+    pub fn new(modify: ModifySelection) -> Self {
+        ModifySelectionDialog {
+            should_commit: false,
+            modify,
+            amount: 1,
+        }
+    }
+}
+
+impl ModalDialog for ModifySelectionDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut result = false;
+        let modal = Modal::new(ctx, "modify_selection_dialog");
+
+        modal.show(|ui| {
+            ui.set_width(250.);
+            modal.title(ui, self.modify.get_label());
+
+            modal.frame(ui, |ui| {
+                egui::Grid::new("some_unique_id").num_columns(2).spacing([4.0, 8.0]).show(ui, |ui| {
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(fl!(crate::LANGUAGE_LOADER, "modify-selection-dialog-amount-label"));
+                    });
+                    ui.add(egui::DragValue::new(&mut self.amount).clamp_range(1..=100));
+                    ui.end_row();
+                });
+                ui.add_space(4.0);
+            });
+
+            modal.buttons(ui, |ui| {
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-ok")).clicked() {
+                    self.should_commit = true;
+                    result = true;
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+
+    fn should_commit(&self) -> bool {
+        self.should_commit
+    }
+
+    fn commit(&self, editor: &mut AnsiEditor) -> TerminalResult<Option<Message>> {
+        editor.modify_selection(self.modify, self.amount);
+        Ok(None)
+    }
+}
@@ -347,6 +347,7 @@ end"#;
 struct BitFontTemplate {
     width: i32,
     height: i32,
+    large_font: bool,
 }
 
 impl Template for BitFontTemplate {
@@ -373,11 +374,16 @@ impl Template for BitFontTemplate {
         } else {
             BitFont::create_8(
                 format!("Empty {}x{}", self.width, self.height),
-                self.width as u8,
+                self.width.min(8) as u8,
                 self.height as u8,
                 &vec![0; 256 * self.height as usize],
             )
         };
+        let font = if self.width > 8 || self.large_font {
+            crate::resize_bit_font(&font, icy_engine::Size::new(self.width, self.height), self.large_font)
+        } else {
+            font
+        };
         let editor = crate::BitFontEditor::new(&window.gl, id, font);
         add_child(&mut window.document_tree, None, Box::new(editor));
         Ok(None)
@@ -389,7 +395,7 @@ impl Template for BitFontTemplate {
             ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
                 ui.label(fl!(crate::LANGUAGE_LOADER, "new-file-width"));
             });
-            ui.add(egui::Slider::new(&mut self.width, 2..=8));
+            ui.add(egui::Slider::new(&mut self.width, 2..=16));
             ui.end_row();
 
             ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
@@ -397,6 +403,15 @@ impl Template for BitFontTemplate {
             });
             ui.add(egui::Slider::new(&mut self.height, 2..=19));
             ui.end_row();
+
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(fl!(crate::LANGUAGE_LOADER, "font-editor-glyph_count"));
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.large_font, false, "256");
+                ui.radio_value(&mut self.large_font, true, "512");
+            });
+            ui.end_row();
         });
     }
 }
@@ -432,7 +447,8 @@ impl Template for TdfFontTemplate {
 
     fn create_file(&self, window: &mut MainWindow<'_>) -> crate::TerminalResult<Option<Message>> {
         let id = window.create_id();
-        let fonts = vec![TheDrawFont::new(self.title(), self.font_type, 1)];
+        // the template title is too long for the TDF name field
+        let fonts = vec![TheDrawFont::new(fl!(crate::LANGUAGE_LOADER, "tdf-editor-new_font_name"), self.font_type, 1)];
         let editor = crate::CharFontEditor::new(&window.gl, id, fonts);
         add_child(&mut window.document_tree, None, Box::new(editor));
         Ok(None)
@@ -455,7 +471,11 @@ impl Default for NewFileDialog {
             Box::new(FileIdTemplate { width: 44, height: 25 }),
             Box::new(AnsiMationTemplate {}),
             Box::new(AtasciiTemplate { width: 40, height: 24 }),
-            Box::new(BitFontTemplate { width: 8, height: 16 }),
+            Box::new(BitFontTemplate {
+                width: 8,
+                height: 16,
+                large_font: false,
+            }),
             Box::new(TdfFontTemplate { font_type: FontType::Color }),
             Box::new(TdfFontTemplate { font_type: FontType::Block }),
             Box::new(TdfFontTemplate { font_type: FontType::Outline }),
@@ -0,0 +1,71 @@
+use eframe::egui::{self, Layout};
+use egui_modal::Modal;
+use i18n_embed_fl::fl;
+
+use crate::{layer_groups, AnsiEditor, Message, ModalDialog, TerminalResult};
+
+pub struct RenameLayerGroupDialog {
+    should_commit: bool,
+    group: String,
+    layer: usize,
+    name: String,
+}
+
+impl RenameLayerGroupDialog {
+    pub fn new(group: String, layer: usize) -> Self {
+        let name = layer_groups::get_group_name(&group).to_string();
+        RenameLayerGroupDialog {
+            should_commit: false,
+            group,
+            layer,
+            name,
+        }
+    }
+}
+
+impl ModalDialog for RenameLayerGroupDialog {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut result = false;
+        let modal = Modal::new(ctx, "rename_layer_group_dialog");
+
+        modal.show(|ui| {
+            ui.set_width(250.);
+            modal.title(ui, fl!(crate::LANGUAGE_LOADER, "rename-layer-group-dialog-title"));
+
+            modal.frame(ui, |ui| {
+                egui::Grid::new("some_unique_id").num_columns(2).spacing([4.0, 8.0]).show(ui, |ui| {
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(fl!(crate::LANGUAGE_LOADER, "edit-layer-dialog-name-label"));
+                    });
+                    ui.add(egui::TextEdit::singleline(&mut self.name));
+                    ui.end_row();
+                });
+                ui.add_space(4.0);
+            });
+
+            modal.buttons(ui, |ui| {
+                if ui
+                    .add_enabled(!self.name.trim().is_empty(), egui::Button::new(fl!(crate::LANGUAGE_LOADER, "new-file-ok")))
+                    .clicked()
+                {
+                    self.should_commit = true;
+                    result = true;
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "new-file-cancel")).clicked() {
+                    result = true;
+                }
+            });
+        });
+        modal.open();
+        result
+    }
+
+    fn should_commit(&self) -> bool {
+        self.should_commit
+    }
+
+    fn commit(&self, editor: &mut AnsiEditor) -> TerminalResult<Option<Message>> {
+        layer_groups::rename_group(editor, &self.group, self.layer, self.name.trim())?;
+        Ok(None)
+    }
+}
@@ -44,10 +44,24 @@ impl crate::ModalDialog for SaveFileDialog {
 
     fn commit_self(&self, window: &mut MainWindow<'_>) -> crate::TerminalResult<Option<Message>> {
         if let Some(file) = &self.opened_file.clone() {
+            let mut font_options = None;
             if let Some(pane) = window.get_active_pane_mut() {
-                pane.set_path(file.clone());
-                pane.save();
-                window.current_id = None;
+                let path = pane.get_save_path(file.clone());
+                font_options = pane
+                    .doc
+                    .lock()
+                    .get_bitfont_editor_mut()
+                    .map(|editor| (path.clone(), editor.save_options.clone(), editor.get_font_height()));
+                if font_options.is_none() {
+                    pane.set_path(path);
+                    pane.save();
+                    window.current_id = None;
+                }
+            }
+            // font formats have options that need to be set before saving,
+            // the path is only changed once the options dialog commits
+            if let Some((path, options, height)) = font_options {
+                window.open_dialog(crate::BitFontSaveDialog::new(path, options, height));
             }
         }
         Ok(None)
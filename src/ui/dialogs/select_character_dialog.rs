@@ -8,7 +8,7 @@ use egui_modal::Modal;
 use i18n_embed_fl::fl;
 use icy_engine_egui::BufferView;
 
-use crate::{AnsiEditor, Message, ModalDialog, TerminalResult};
+use crate::{get_glyph_pixel, AnsiEditor, Message, ModalDialog, TerminalResult};
 
 pub struct SelectCharacterDialog {
     should_commit: bool,
@@ -51,7 +51,7 @@ impl ModalDialog for SelectCharacterDialog {
                     if let Some(glyph) = font.get_glyph(ch) {
                         for y in 0..s.height {
                             for x in 0..s.width {
-                                if glyph.data[y as usize] & (128 >> x) != 0 {
+                                if get_glyph_pixel(&glyph.data, s.width, x, y) {
                                     painter.rect_filled(
                                         Rect::from_min_size(
                                             egui::Pos2::new(stroke_rect.left() + x as f32 * scale, stroke_rect.top() + y as f32 * scale),
@@ -115,7 +115,7 @@ impl ModalDialog for SelectCharacterDialog {
                         if let Some(glyph) = font.get_glyph(ch) {
                             for y in 0..s.height {
                                 for x in 0..s.width {
-                                    if glyph.data[y as usize] & (128 >> x) != 0 {
+                                    if get_glyph_pixel(&glyph.data, s.width, x, y) {
                                         painter.rect_filled(
                                             Rect::from_min_size(
                                                 egui::Pos2::new(xs + stroke_rect.left() + x as f32 * scale, ys + stroke_rect.top() + y as f32 * scale),
@@ -6,7 +6,7 @@ use egui_modal::Modal;
 use i18n_embed_fl::fl;
 use icy_engine::{BitFont, TheDrawFont};
 
-use crate::{AnsiEditor, Message, ModalDialog, Settings, TerminalResult};
+use crate::{get_glyph_pixel, AnsiEditor, Message, ModalDialog, Settings, TerminalResult};
 
 pub struct SelectOutlineDialog {
     should_commit: bool,
@@ -75,7 +75,7 @@ impl SelectOutlineDialog {
                 if let Some(glyph) = self.font.get_glyph(ch) {
                     for y in 0..s.height {
                         for x in 0..s.width {
-                            if glyph.data[y as usize] & (128 >> x) != 0 {
+                            if get_glyph_pixel(&glyph.data, s.width, x, y) {
                                 painter.rect_filled(
                                     Rect::from_min_size(
                                         egui::Pos2::new(
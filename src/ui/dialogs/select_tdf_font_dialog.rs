@@ -8,7 +8,7 @@ use egui::{load::SizedTexture, mutex::Mutex, Image, TextureHandle, TextureOption
 use egui_file::FileDialog;
 use egui_modal::Modal;
 use i18n_embed_fl::fl;
-use icy_engine::{editor::EditState, Buffer, Rectangle, Size, TextPane, TheDrawFont};
+use icy_engine::{editor::EditState, Buffer, Position, Rectangle, Size, TextPane, TheDrawFont};
 
 use crate::{MainWindow, Message};
 
@@ -26,6 +26,8 @@ pub struct SelectFontDialog {
     export_dialog: Option<FileDialog>,
 
     image_cache: HashMap<usize, TextureHandle>,
+    show_glyph_sheet: bool,
+    glyph_sheet: Option<(usize, TextureHandle)>,
 }
 
 impl SelectFontDialog {
@@ -42,6 +44,8 @@ impl SelectFontDialog {
             show_color: true,
             show_block: true,
             image_cache: HashMap::default(),
+            show_glyph_sheet: false,
+            glyph_sheet: None,
             export_dialog: None,
             export_data: None,
         }
@@ -177,6 +181,58 @@ impl SelectFontDialog {
 
         response
     }
+
+    fn draw_glyph_sheet(&mut self, ui: &mut egui::Ui) {
+        if self.selected_font < 0 || self.selected_font as usize >= self.fonts.lock().len() {
+            return;
+        }
+        let cur_font = self.selected_font as usize;
+        if !matches!(&self.glyph_sheet, Some((font, _)) if *font == cur_font) {
+            let img = create_glyph_sheet(ui.ctx(), &self.fonts.lock()[cur_font]);
+            self.glyph_sheet = Some((cur_font, img));
+        }
+        if let Some((_, image)) = &self.glyph_sheet {
+            let sized_texture: SizedTexture = image.into();
+            let scale = (ui.available_width() / sized_texture.size.x).min(1.0);
+            egui::ScrollArea::vertical().id_source("glyph_sheet_scroll").max_height(240.).show(ui, |ui| {
+                ui.add(Image::from_texture(sized_texture).fit_to_exact_size(sized_texture.size * scale));
+            });
+        }
+    }
+}
+
+/// Renders all glyphs of the font wrapped into rows.
+fn create_glyph_sheet(ctx: &egui::Context, font: &TheDrawFont) -> TextureHandle {
+    const SHEET_WIDTH: i32 = 80;
+    let mut sizes = Vec::new();
+    for ch in b'!'..=b'~' {
+        let mut state = EditState::from_buffer(Buffer::new((100, 30)));
+        if font.has_char(ch) {
+            if let Some(size) = font.render(&mut state, ch) {
+                sizes.push((ch, size));
+            }
+        }
+    }
+
+    let mut positions = Vec::new();
+    let (mut x, mut y, mut row_height) = (0, 0, 0);
+    for (ch, size) in sizes {
+        if x > 0 && x + size.width > SHEET_WIDTH {
+            x = 0;
+            y += row_height + 1;
+            row_height = 0;
+        }
+        positions.push((ch, Position::new(x, y)));
+        x += size.width + 1;
+        row_height = row_height.max(size.height);
+    }
+
+    let mut state = EditState::from_buffer(Buffer::new((SHEET_WIDTH, (y + row_height).max(1))));
+    for (ch, pos) in positions {
+        state.get_caret_mut().set_position(pos);
+        font.render(&mut state, ch);
+    }
+    create_image(ctx, state.get_buffer())
 }
 
 impl crate::ModalDialog for SelectFontDialog {
@@ -232,6 +288,11 @@ impl crate::ModalDialog for SelectFontDialog {
                     if response.clicked() {
                         self.show_outline = !self.show_outline;
                     }
+
+                    let response = ui.selectable_label(self.show_glyph_sheet, fl!(crate::LANGUAGE_LOADER, "select-font-dialog-glyph-sheet"));
+                    if response.clicked() {
+                        self.show_glyph_sheet = !self.show_glyph_sheet;
+                    }
                 });
                 ui.add_space(4.0);
 
@@ -271,6 +332,10 @@ impl crate::ModalDialog for SelectFontDialog {
                                 }
                             }
                         });
+                    if self.show_glyph_sheet {
+                        ui.separator();
+                        self.draw_glyph_sheet(ui);
+                    }
                 }
             });
 
@@ -3,7 +3,7 @@ use std::path::Path;
 use eframe::egui;
 use icy_engine::EngineResult;
 
-use crate::{model::Tool, AnsiEditor, Commands, Message, TerminalResult};
+use crate::{model::Tool, AnsiEditor, BitFontEditor, CharFontEditor, Commands, Message, TerminalResult};
 
 pub trait ClipboardHandler {
     fn can_cut(&self) -> bool {
@@ -53,6 +53,11 @@ pub trait Document: UndoHandler + ClipboardHandler {
 
     fn default_extension(&self) -> &'static str;
 
+    /// Returns true if the document can be saved with that extension, otherwise the default extension is used.
+    fn supports_extension(&self, ext: &str) -> bool {
+        ext == self.default_extension()
+    }
+
     fn get_bytes(&mut self, path: &Path) -> TerminalResult<Vec<u8>>;
 
     fn show_ui(&mut self, ui: &mut egui::Ui, cur_tool: &mut Box<dyn Tool>, selected_tool: usize, options: &DocumentOptions) -> Option<Message>;
@@ -62,6 +67,14 @@ pub trait Document: UndoHandler + ClipboardHandler {
     fn get_ansi_editor_mut(&mut self) -> Option<&mut AnsiEditor>;
     fn get_ansi_editor(&self) -> Option<&AnsiEditor>;
 
+    fn get_bitfont_editor_mut(&mut self) -> Option<&mut BitFontEditor> {
+        None
+    }
+
+    fn get_char_font_editor_mut(&mut self) -> Option<&mut CharFontEditor> {
+        None
+    }
+
     fn can_paste_char(&self) -> bool {
         false
     }
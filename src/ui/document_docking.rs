@@ -80,9 +80,18 @@ impl DocumentTab {
         self.full_path.clone()
     }
 
-    pub fn set_path(&mut self, mut path: PathBuf) {
-        let doc = &mut self.doc.lock();
-        path.set_extension(doc.default_extension());
+    /// Returns the path the document would be saved to, unsupported extensions are replaced by the default one.
+    pub fn get_save_path(&self, mut path: PathBuf) -> PathBuf {
+        let doc = self.doc.lock();
+        let ext = path.extension().unwrap_or_default().to_str().unwrap_or_default().to_ascii_lowercase();
+        if !doc.supports_extension(&ext) {
+            path.set_extension(doc.default_extension());
+        }
+        path
+    }
+
+    pub fn set_path(&mut self, path: PathBuf) {
+        let path = self.get_save_path(path);
         if let Some(old_path) = &self.full_path {
             remove_autosave(old_path);
         }
@@ -0,0 +1,269 @@
+//! Layer blend modes beyond the engine's Normal, Chars and Attributes modes.
+//!
+//! The engine doesn't know the extended modes. They are stored at the end of the layer title behind
+//! a record separator and the engine sees the layer as `Mode::Normal`. That keeps them in the undo
+//! stack and in .icy files. The editor paints the composited cells over the engine rendering and
+//! exports to formats without layers get a flattened copy of the buffer.
+
+use eframe::{
+    egui::{self, TextureHandle, TextureOptions},
+    epaint::{Color32, ColorImage, Rect},
+};
+use i18n_embed_fl::fl;
+use icy_engine::{AttributedChar, Buffer, Mode, Position, Properties, Rectangle, TextPane};
+use icy_engine_egui::TerminalCalc;
+
+use crate::Document;
+
+use super::AnsiEditor;
+
+pub const BLEND_MODE_SEPARATOR: char = '\u{1E}';
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    Normal,
+    Chars,
+    Attributes,
+    Foreground,
+    Background,
+    ColorsOverChars,
+    Lighten,
+    Darken,
+    Mask,
+}
+
+pub const BLEND_MODES: [BlendMode; 9] = [
+    BlendMode::Normal,
+    BlendMode::Chars,
+    BlendMode::Attributes,
+    BlendMode::Foreground,
+    BlendMode::Background,
+    BlendMode::ColorsOverChars,
+    BlendMode::Lighten,
+    BlendMode::Darken,
+    BlendMode::Mask,
+];
+
+impl BlendMode {
+    fn get_key(self) -> Option<&'static str> {
+        match self {
+            BlendMode::Normal | BlendMode::Chars | BlendMode::Attributes => None,
+            BlendMode::Foreground => Some("foreground"),
+            BlendMode::Background => Some("background"),
+            BlendMode::ColorsOverChars => Some("colors"),
+            BlendMode::Lighten => Some("lighten"),
+            BlendMode::Darken => Some("darken"),
+            BlendMode::Mask => Some("mask"),
+        }
+    }
+
+    /// Extended modes are composited by the editor, the engine draws the layer as a normal layer.
+    pub fn is_extended(self) -> bool {
+        self.get_key().is_some()
+    }
+
+    pub fn get_label(self) -> String {
+        match self {
+            BlendMode::Normal => fl!(crate::LANGUAGE_LOADER, "blend-mode-normal"),
+            BlendMode::Chars => fl!(crate::LANGUAGE_LOADER, "blend-mode-chars"),
+            BlendMode::Attributes => fl!(crate::LANGUAGE_LOADER, "blend-mode-attributes"),
+            BlendMode::Foreground => fl!(crate::LANGUAGE_LOADER, "blend-mode-foreground"),
+            BlendMode::Background => fl!(crate::LANGUAGE_LOADER, "blend-mode-background"),
+            BlendMode::ColorsOverChars => fl!(crate::LANGUAGE_LOADER, "blend-mode-colors"),
+            BlendMode::Lighten => fl!(crate::LANGUAGE_LOADER, "blend-mode-lighten"),
+            BlendMode::Darken => fl!(crate::LANGUAGE_LOADER, "blend-mode-darken"),
+            BlendMode::Mask => fl!(crate::LANGUAGE_LOADER, "blend-mode-mask"),
+        }
+    }
+
+    pub fn from_properties(properties: &Properties) -> Self {
+        if let Some((_, key)) = properties.title.split_once(BLEND_MODE_SEPARATOR) {
+            if let Some(mode) = BLEND_MODES.iter().find(|mode| mode.get_key() == Some(key)) {
+                return *mode;
+            }
+        }
+        match properties.mode {
+            Mode::Normal => BlendMode::Normal,
+            Mode::Chars => BlendMode::Chars,
+            Mode::Attributes => BlendMode::Attributes,
+        }
+    }
+
+    pub fn apply(self, properties: &mut Properties) {
+        properties.title = strip_blend_mode(&properties.title).to_string();
+        properties.mode = match self {
+            BlendMode::Chars => Mode::Chars,
+            BlendMode::Attributes => Mode::Attributes,
+            _ => Mode::Normal,
+        };
+        if let Some(key) = self.get_key() {
+            properties.title.push(BLEND_MODE_SEPARATOR);
+            properties.title.push_str(key);
+        }
+    }
+}
+
+/// Returns the layer title without the stored blend mode.
+pub fn strip_blend_mode(title: &str) -> &str {
+    title.split_once(BLEND_MODE_SEPARATOR).map_or(title, |(title, _)| title)
+}
+
+fn has_extended_blend_modes(buf: &Buffer) -> bool {
+    buf.layers
+        .iter()
+        .any(|layer| layer.get_is_visible() && BlendMode::from_properties(&layer.properties).is_extended())
+}
+
+fn get_brightness(buf: &Buffer, color: u32) -> u32 {
+    let (r, g, b) = buf.palette.get_color(color).get_rgb();
+    299 * r as u32 + 587 * g as u32 + 114 * b as u32
+}
+
+/// Composites all visible layers at `pos`.
+/// Returns `None` if no layer with an extended blend mode covers the position,
+/// the engine rendering is correct for these cells.
+fn get_blended_char(buf: &Buffer, pos: Position) -> Option<AttributedChar> {
+    let mut is_blended = false;
+    let mut result = AttributedChar::default();
+    for layer in &buf.layers {
+        if !layer.get_is_visible() {
+            continue;
+        }
+        let local = pos - layer.get_offset();
+        if local.x < 0 || local.y < 0 || local.x >= layer.get_width() || local.y >= layer.get_height() {
+            continue;
+        }
+        let mode = BlendMode::from_properties(&layer.properties);
+        is_blended |= mode.is_extended();
+        let ch = layer.get_char(local);
+        if ch.is_transparent() {
+            if mode == BlendMode::Mask {
+                result = AttributedChar::default();
+            }
+            continue;
+        }
+        match mode {
+            BlendMode::Normal => result = ch,
+            BlendMode::Chars => result.ch = ch.ch,
+            BlendMode::Attributes => result.attribute = ch.attribute,
+            BlendMode::Foreground => result.attribute.set_foreground(ch.attribute.get_foreground()),
+            BlendMode::Background => result.attribute.set_background(ch.attribute.get_background()),
+            BlendMode::ColorsOverChars => {
+                result.attribute.set_foreground(ch.attribute.get_foreground());
+                result.attribute.set_background(ch.attribute.get_background());
+            }
+            BlendMode::Lighten | BlendMode::Darken => {
+                let pick = |a: u32, b: u32| {
+                    let is_brighter = get_brightness(buf, b) > get_brightness(buf, a);
+                    if is_brighter == (mode == BlendMode::Lighten) {
+                        b
+                    } else {
+                        a
+                    }
+                };
+                let fg = pick(result.attribute.get_foreground(), ch.attribute.get_foreground());
+                let bg = pick(result.attribute.get_background(), ch.attribute.get_background());
+                result.attribute.set_foreground(fg);
+                result.attribute.set_background(bg);
+            }
+            // the mask only hides, its own cells aren't drawn
+            BlendMode::Mask => {}
+        }
+    }
+    if is_blended {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Returns a single layer copy of the buffer with the blend modes applied.
+/// `None` if no visible layer uses an extended blend mode.
+pub fn flatten_blend_modes(buf: &Buffer) -> Option<Buffer> {
+    if !has_extended_blend_modes(buf) {
+        return None;
+    }
+    let mut result = buf.clone();
+    result.layers.truncate(1);
+    let layer = &mut result.layers[0];
+    layer.role = icy_engine::Role::Normal;
+    layer.properties.title = strip_blend_mode(&layer.properties.title).to_string();
+    layer.properties.mode = Mode::Normal;
+    layer.set_is_visible(true);
+    layer.set_offset((0, 0));
+    layer.set_size(buf.get_size());
+    for y in 0..buf.get_height() {
+        for x in 0..buf.get_width() {
+            let pos = Position::new(x, y);
+            let ch = get_blended_char(buf, pos).unwrap_or_else(|| buf.get_char(pos));
+            layer.set_char(pos, ch);
+        }
+    }
+    Some(result)
+}
+
+/// Renders the blended cells, all other pixels are transparent.
+fn create_blend_image(buf: &Buffer) -> Option<ColorImage> {
+    if !has_extended_blend_modes(buf) {
+        return None;
+    }
+    let (width, height) = (buf.get_width(), buf.get_height());
+    let mut blend_buf = Buffer::new(buf.get_size());
+    blend_buf.buffer_type = buf.buffer_type;
+    blend_buf.ice_mode = buf.ice_mode;
+    blend_buf.palette = buf.palette.clone();
+    blend_buf.set_font_table(buf.get_font_table());
+
+    let mut is_blended = vec![false; (width * height) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let pos = Position::new(x, y);
+            if let Some(ch) = get_blended_char(buf, pos) {
+                blend_buf.layers[0].set_char(pos, ch);
+                is_blended[(y * width + x) as usize] = true;
+            }
+        }
+    }
+
+    Some(render_cells(&blend_buf, &is_blended))
+}
+
+/// Renders the buffer, the pixels of cells that aren't drawn (row by row in `is_drawn`) stay transparent.
+pub(super) fn render_cells(buf: &Buffer, is_drawn: &[bool]) -> ColorImage {
+    let width = buf.get_width().max(1);
+    let height = buf.get_height().max(1);
+    let (size, mut pixels) = buf.render_to_rgba(Rectangle::from(0, 0, width, height));
+    let char_width = (size.width / width).max(1) as usize;
+    let char_height = (size.height / height).max(1) as usize;
+    for (i, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+        let x = i % size.width as usize / char_width;
+        let y = i / size.width as usize / char_height;
+        if !is_drawn.get(y * width as usize + x).copied().unwrap_or(false) {
+            pixel.fill(0);
+        }
+    }
+    ColorImage::from_rgba_premultiplied([size.width as usize, size.height as usize], &pixels)
+}
+
+impl AnsiEditor {
+    /// Paints the cells of layers with extended blend modes over the engine rendering.
+    pub(super) fn paint_blend_modes(&mut self, ui: &egui::Ui, calc: &TerminalCalc) {
+        let undo_stack_len = self.undo_stack_len();
+        if !matches!(&self.blend_texture, Some((len, _)) if *len == undo_stack_len) {
+            let image = create_blend_image(self.buffer_view.lock().get_buffer());
+            let texture = image.map(|image| ui.ctx().load_texture("blend_modes", image, TextureOptions::NEAREST));
+            self.blend_texture = Some((undo_stack_len, texture));
+        }
+        let Some((_, Some(texture))) = &self.blend_texture else {
+            return;
+        };
+        let min = calc.buffer_rect.min - calc.char_scroll_position * calc.scale;
+        let rect = Rect::from_min_size(min, texture.size_vec2() * calc.scale);
+        ui.painter_at(calc.terminal_rect).image(
+            texture.id(),
+            rect,
+            Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            Color32::WHITE,
+        );
+    }
+}
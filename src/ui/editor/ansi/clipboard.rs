@@ -0,0 +1,277 @@
+use std::{collections::HashMap, fmt::Write, io::Cursor, path::Path};
+
+use clipboard_rs::{common::RustImage, Clipboard, ClipboardContent, ClipboardContext, RustImageData};
+use icy_engine::{
+    editor::EditState, util::BUFFER_DATA, AttributedChar, Buffer, BufferType, EngineResult, Layer, Palette, PaletteMode, Position, Rectangle, Role,
+    SaveOptions, TextPane, UnicodeConverter,
+};
+
+use image::RgbaImage;
+
+use crate::{apply_unicode_tables, font_from_unicode, font_to_unicode};
+
+use super::AnsiEditor;
+
+/// Clipboard format used for the ANSI escape text of a copied block.
+pub const ANSI_DATA: &str = "text/x-ansi";
+
+impl AnsiEditor {
+    /// Puts the copied block on the system clipboard. Other icy draw instances read the internal
+    /// data, other applications get UTF-8 text, ANSI escape text, HTML with inline colors and a PNG.
+    pub fn push_clipboard_data(&self, data: Vec<u8>) -> EngineResult<()> {
+        let mut contents = Vec::new();
+        if let Some(buf) = self.create_clipboard_buffer(&data) {
+            contents.push(ClipboardContent::Text(get_utf8_text(&buf)));
+            contents.push(ClipboardContent::Html(get_html(&buf)));
+
+            let mut options = SaveOptions::new();
+            options.modern_terminal_output = true;
+            let unicode_buf = apply_unicode_tables(&buf);
+            if let Ok(ansi) = unicode_buf.as_ref().unwrap_or(&buf).to_bytes("ans", &options) {
+                contents.push(ClipboardContent::Other(ANSI_DATA.to_string(), ansi));
+            }
+
+            let (size, pixels) = buf.render_to_rgba(Rectangle::from(0, 0, buf.get_width(), buf.get_height()));
+            if let Some(img) = image::RgbaImage::from_raw(size.width as u32, size.height as u32, pixels) {
+                let mut png = Vec::new();
+                if img.write_to(&mut Cursor::new(&mut png), image::ImageOutputFormat::Png).is_ok() {
+                    if let Ok(image) = RustImageData::from_bytes(&png) {
+                        contents.push(ClipboardContent::Image(image));
+                    }
+                }
+            }
+        }
+        contents.push(ClipboardContent::Other(BUFFER_DATA.to_string(), data));
+
+        let ctx = ClipboardContext::new().map_err(|err| anyhow::anyhow!("{err}"))?;
+        ctx.set(contents).map_err(|err| anyhow::anyhow!("{err}"))?;
+        Ok(())
+    }
+
+    /// Creates a buffer containing only the copied block, using the palette, fonts and buffer type of the document.
+    fn create_clipboard_buffer(&self, data: &[u8]) -> Option<Buffer> {
+        let mut layer = Layer::from_clipboard_data(data)?;
+        let lock = self.buffer_view.lock();
+        let source = lock.get_buffer();
+        layer.set_offset((0, 0));
+        layer.role = Role::Normal;
+        let mut buf = Buffer::new(layer.get_size());
+        buf.buffer_type = source.buffer_type;
+        buf.palette = source.palette.clone();
+        for (page, font) in source.font_iter() {
+            buf.set_font(*page, font.clone());
+        }
+        buf.layers.clear();
+        buf.layers.push(layer);
+        Some(buf)
+    }
+
+    /// Pastes text from other applications. Text containing line breaks or ANSI escape sequences
+    /// becomes a floating layer, single lines are typed at the caret. Returns false if the text
+    /// should be typed.
+    pub fn paste_text(&mut self, text: &str) -> EngineResult<bool> {
+        if !text.contains('\x1B') && !text.trim_end().contains('\n') {
+            return Ok(false);
+        }
+        let buffer_type = self.buffer_view.lock().get_buffer().buffer_type;
+        // glyphs of fonts with a unicode table are looked up in the table of the current font page
+        let font = if buffer_type == BufferType::CP437 {
+            let lock = self.buffer_view.lock();
+            lock.get_buffer().get_font(lock.get_caret().get_font_page()).cloned()
+        } else {
+            None
+        };
+
+        // The ANSI parser works on CP437 bytes, the result is converted to the buffer type of the document through unicode.
+        let converter = icy_engine::ascii::CP437Converter::default();
+        let bytes: Vec<u8> = text
+            .chars()
+            .filter(|ch| *ch != '\r')
+            .map(|ch| {
+                if let Some(glyph) = font_from_unicode(font.as_ref(), ch).filter(|glyph| (*glyph as u32) < 256) {
+                    glyph as u8
+                } else if ch.is_ascii() {
+                    ch as u8
+                } else {
+                    converter.convert_from_unicode(ch, 0) as u8
+                }
+            })
+            .collect();
+        let mut buf = Buffer::from_bytes(Path::new("clipboard.ans"), true, &bytes)?;
+        let width = get_used_width(&buf);
+        let height = buf.get_line_count().max(1);
+        if buffer_type != BufferType::CP437 {
+            let font_page = self.buffer_view.lock().get_caret().get_font_page();
+            for y in 0..height {
+                for x in 0..width {
+                    let pos = Position::new(x, y);
+                    let mut ch = buf.get_char(pos);
+                    let unicode = converter.convert_to_unicode(ch);
+                    ch.ch = match buffer_type {
+                        BufferType::Unicode | BufferType::CP437 => unicode,
+                        BufferType::Petscii => icy_engine::petscii::CharConverter::default().convert_from_unicode(unicode, font_page),
+                        BufferType::Atascii => icy_engine::atascii::CharConverter::default().convert_from_unicode(unicode, font_page),
+                        BufferType::Viewdata => icy_engine::viewdata::CharConverter::default().convert_from_unicode(unicode, font_page),
+                    };
+                    buf.layers[0].set_char(pos, ch);
+                }
+            }
+        }
+
+        buf.set_size((width, height));
+        self.paste_buffer(buf)?;
+        Ok(true)
+    }
+
+    /// Pastes the whole buffer as floating layer, a floating layer that is still open gets anchored first.
+    pub fn paste_buffer(&mut self, mut buf: Buffer) -> EngineResult<()> {
+        if self.buffer_view.lock().get_edit_state_mut().has_floating_layer() {
+            self.buffer_view.lock().get_edit_state_mut().anchor_layer()?;
+        }
+        self.map_to_document_palette(&mut buf);
+        let (width, height) = (buf.get_width(), buf.get_height());
+        let mut state = EditState::from_buffer(buf);
+        state.set_selection(Rectangle::from(0, 0, width, height));
+        if let Some(data) = state.get_clipboard_data() {
+            self.buffer_view.lock().get_edit_state_mut().paste_clipboard_data(&data)?;
+        }
+        Ok(())
+    }
+
+    /// The clipboard data only has palette indices, the colors of the buffer are mapped to the
+    /// document palette first. RGB documents get missing colors added, the other palette modes
+    /// use the nearest color they can show.
+    fn map_to_document_palette(&self, buf: &mut Buffer) {
+        let mut lock = self.buffer_view.lock();
+        let doc = lock.get_buffer_mut();
+        let colors = match doc.palette_mode {
+            PaletteMode::RGB => None,
+            PaletteMode::Free8 => Some(8),
+            PaletteMode::Fixed16 | PaletteMode::Free16 => Some(16),
+        };
+        let mut mapped = HashMap::new();
+        let mut map_color = |color: u32| {
+            *mapped.entry(color).or_insert_with(|| {
+                let (r, g, b) = buf.palette.get_color(color).get_rgb();
+                match colors {
+                    None => doc.palette.insert_color_rgb(r, g, b),
+                    Some(colors) => get_nearest_color(&doc.palette, colors, (r, g, b)),
+                }
+            })
+        };
+        for y in 0..buf.get_height() {
+            for x in 0..buf.get_width() {
+                let pos = Position::new(x, y);
+                let mut ch = buf.layers[0].get_char(pos);
+                if ch.is_transparent() {
+                    continue;
+                }
+                let fg = map_color(ch.attribute.get_foreground());
+                let bg = map_color(ch.attribute.get_background());
+                ch.attribute.set_foreground(fg);
+                ch.attribute.set_background(bg);
+                buf.layers[0].set_char(pos, ch);
+            }
+        }
+        buf.palette = doc.palette.clone();
+    }
+}
+
+fn get_nearest_color(palette: &Palette, colors: usize, (r, g, b): (u8, u8, u8)) -> u32 {
+    let mut best = (u32::MAX, 0);
+    for i in 0..colors.min(palette.len()) {
+        let (pr, pg, pb) = palette.get_color(i as u32).get_rgb();
+        let dist = (pr.abs_diff(r) as u32).pow(2) + (pg.abs_diff(g) as u32).pow(2) + (pb.abs_diff(b) as u32).pow(2);
+        if dist < best.0 {
+            best = (dist, i as u32);
+        }
+    }
+    best.1
+}
+
+/// Returns the raster image on the system clipboard, if there is one.
+pub fn pop_clipboard_image() -> Option<RgbaImage> {
+    let ctx = ClipboardContext::new().ok()?;
+    let png = ctx.get_image().ok()?.to_png().ok()?;
+    image::load_from_memory(png.get_bytes()).ok().map(|img| img.to_rgba8())
+}
+
+fn get_used_width(buf: &Buffer) -> i32 {
+    let mut width = 1;
+    for y in 0..buf.get_line_count() {
+        for x in (0..buf.get_width()).rev() {
+            let ch = buf.get_char(Position::new(x, y));
+            if ch.ch != ' ' && ch.ch != '\0' || ch.attribute.get_background() != 0 {
+                width = width.max(x + 1);
+                break;
+            }
+        }
+    }
+    width
+}
+
+fn to_unicode(buf: &Buffer, ch: AttributedChar) -> char {
+    match buf.buffer_type {
+        BufferType::Unicode => ch.ch,
+        BufferType::CP437 => {
+            font_to_unicode(buf.get_font(ch.get_font_page()), ch.ch).unwrap_or_else(|| icy_engine::ascii::CP437Converter::default().convert_to_unicode(ch))
+        }
+        BufferType::Petscii => icy_engine::petscii::CharConverter::default().convert_to_unicode(ch),
+        BufferType::Atascii => icy_engine::atascii::CharConverter::default().convert_to_unicode(ch),
+        BufferType::Viewdata => icy_engine::viewdata::CharConverter::default().convert_to_unicode(ch),
+    }
+}
+
+fn get_utf8_text(buf: &Buffer) -> String {
+    let mut result = String::new();
+    for y in 0..buf.get_height() {
+        let mut line = String::new();
+        for x in 0..buf.get_width() {
+            let ch = buf.get_char(Position::new(x, y));
+            if ch.is_transparent() || ch.ch == '\0' {
+                line.push(' ');
+            } else {
+                line.push(to_unicode(buf, ch));
+            }
+        }
+        result.push_str(line.trim_end());
+        result.push('\n');
+    }
+    result
+}
+
+fn get_html(buf: &Buffer) -> String {
+    let mut result = String::from("<pre style=\"font-family: monospace; line-height: 1.0;\">");
+    for y in 0..buf.get_height() {
+        let mut last_attr = None;
+        for x in 0..buf.get_width() {
+            let ch = buf.get_char(Position::new(x, y));
+            let attr = (ch.attribute.get_foreground(), ch.attribute.get_background());
+            if last_attr != Some(attr) {
+                if last_attr.is_some() {
+                    result.push_str("</span>");
+                }
+                let _ = write!(
+                    result,
+                    "<span style=\"color:{};background-color:{};\">",
+                    buf.palette.get_color(attr.0).to_hex(),
+                    buf.palette.get_color(attr.1).to_hex()
+                );
+                last_attr = Some(attr);
+            }
+            let ch = if ch.is_transparent() || ch.ch == '\0' { ' ' } else { to_unicode(buf, ch) };
+            match ch {
+                '<' => result.push_str("&lt;"),
+                '>' => result.push_str("&gt;"),
+                '&' => result.push_str("&amp;"),
+                ch => result.push(ch),
+            }
+        }
+        if last_attr.is_some() {
+            result.push_str("</span>");
+        }
+        result.push('\n');
+    }
+    result.push_str("</pre>");
+    result
+}
@@ -0,0 +1,94 @@
+use eframe::{
+    egui::{self, TextureHandle, TextureOptions},
+    epaint::{Color32, Rect, Vec2},
+};
+use icy_engine::{Buffer, Position, Role, TextPane};
+use icy_engine_egui::TerminalCalc;
+
+use crate::Document;
+
+use super::{render_cells, AnsiEditor, LayerRef};
+
+/// A layer painted translucent over the document, used to align overlays against a reference.
+/// The ghost is only a view setting, it doesn't change the document. It is painted while the layer
+/// is hidden, a visible layer is drawn by the document itself.
+pub struct GhostLayer {
+    pub layer: LayerRef,
+    pub opacity: f32,
+    /// Rendered layer together with the undo stack length and layer index it was rendered at.
+    texture: Option<(usize, usize, TextureHandle)>,
+}
+
+impl AnsiEditor {
+    pub fn get_ghost_layer(&self) -> Option<usize> {
+        let ghost = self.ghost_layer.as_ref()?;
+        ghost.layer.find(&self.buffer_view.lock().get_buffer().layers)
+    }
+
+    pub fn toggle_ghost_layer(&mut self, layer: usize) {
+        if self.get_ghost_layer() == Some(layer) {
+            self.ghost_layer = None;
+        } else if let Some(layer_ref) = LayerRef::new(&self.buffer_view.lock().get_buffer().layers, layer) {
+            self.ghost_layer = Some(GhostLayer {
+                layer: layer_ref,
+                opacity: self.ghost_layer.as_ref().map_or(0.5, |ghost| ghost.opacity),
+                texture: None,
+            });
+        }
+    }
+
+    pub fn set_ghost_opacity(&mut self, opacity: f32) {
+        if let Some(ghost) = &mut self.ghost_layer {
+            ghost.opacity = opacity.clamp(0.0, 1.0);
+        }
+    }
+
+    pub(super) fn paint_ghost_layer(&mut self, ui: &egui::Ui, calc: &TerminalCalc) {
+        let stack_len = self.undo_stack_len();
+        let Some(ghost) = &mut self.ghost_layer else {
+            return;
+        };
+        let lock = self.buffer_view.lock();
+        let buf = lock.get_buffer();
+        let Some(index) = ghost.layer.update(&buf.layers) else {
+            return;
+        };
+        let layer = &buf.layers[index];
+        if layer.get_is_visible() {
+            return;
+        }
+
+        if !matches!(&ghost.texture, Some((len, i, _)) if *len == stack_len && *i == index) {
+            let mut is_drawn = Vec::new();
+            for y in 0..layer.get_height() {
+                for x in 0..layer.get_width() {
+                    is_drawn.push(!layer.get_char(Position::new(x, y)).is_transparent());
+                }
+            }
+            let mut ghost_buf = Buffer::new(layer.get_size());
+            ghost_buf.buffer_type = buf.buffer_type;
+            ghost_buf.palette = buf.palette.clone();
+            ghost_buf.set_font_table(buf.get_font_table());
+            let mut layer = layer.clone();
+            layer.set_offset((0, 0));
+            layer.set_is_visible(true);
+            layer.role = Role::Normal;
+            ghost_buf.layers.clear();
+            ghost_buf.layers.push(layer);
+            let image = render_cells(&ghost_buf, &is_drawn);
+            ghost.texture = Some((stack_len, index, ui.ctx().load_texture("ghost_layer", image, TextureOptions::NEAREST)));
+        }
+
+        let Some((_, _, texture)) = &ghost.texture else {
+            return;
+        };
+        let dims = buf.get_font_dimensions();
+        let offset = layer.get_offset();
+        let pos = Vec2::new(offset.x as f32 * dims.width as f32, offset.y as f32 * dims.height as f32);
+        let min = calc.buffer_rect.min + (pos - calc.char_scroll_position) * calc.scale;
+        let rect = Rect::from_min_size(min, texture.size_vec2() * calc.scale);
+        let tint = Color32::from_white_alpha((ghost.opacity * 255.0) as u8);
+        ui.painter_at(calc.terminal_rect)
+            .image(texture.id(), rect, Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)), tint);
+    }
+}
@@ -0,0 +1,40 @@
+use icy_engine::Layer;
+
+/// Refers to a layer across edits. The engine has no layer ids and indices shift when layers
+/// get added, removed or moved, so the layer is found by its title and the index only tells
+/// layers with the same title apart.
+#[derive(Clone, Debug)]
+pub struct LayerRef {
+    title: String,
+    index: usize,
+}
+
+impl LayerRef {
+    pub fn new(layers: &[Layer], index: usize) -> Option<Self> {
+        let layer = layers.get(index)?;
+        Some(Self {
+            title: layer.get_title().to_string(),
+            index,
+        })
+    }
+
+    /// Returns the current index of the layer, the layer closest to the last known index wins.
+    pub fn find(&self, layers: &[Layer]) -> Option<usize> {
+        if layers.get(self.index).is_some_and(|layer| layer.get_title() == self.title) {
+            return Some(self.index);
+        }
+        layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.get_title() == self.title)
+            .min_by_key(|(i, _)| i.abs_diff(self.index))
+            .map(|(i, _)| i)
+    }
+
+    /// Looks the layer up and remembers the new index.
+    pub fn update(&mut self, layers: &[Layer]) -> Option<usize> {
+        let index = self.find(layers)?;
+        self.index = index;
+        Some(index)
+    }
+}
@@ -15,18 +15,32 @@ use i18n_embed_fl::fl;
 use icy_engine::{
     attribute,
     editor::{AtomicUndoGuard, UndoState},
-    util::{pop_data, pop_sixel_image, push_data, BUFFER_DATA},
+    util::{pop_data, pop_sixel_image, BUFFER_DATA},
     AttributedChar, Buffer, EngineResult, Line, Position, Rectangle, SaveOptions, TextAttribute, TextPane,
 };
 
 use icy_engine_egui::{show_terminal_area, BufferView, CaretShape, TerminalCalc};
 
 use crate::{
+    apply_unicode_tables,
     model::{DragPos, MKey, MModifiers, Tool},
     paint::ColorMode,
     ClipboardHandler, Commands, Document, DocumentOptions, Message, SavingError, TerminalResult, UndoHandler, SETTINGS,
 };
 
+mod blend_modes;
+pub use blend_modes::*;
+mod clipboard;
+pub use clipboard::*;
+mod ghost;
+pub use ghost::*;
+mod layer_ref;
+pub use layer_ref::*;
+mod selections;
+pub use selections::*;
+mod solo;
+pub use solo::*;
+
 pub enum Event {
     None,
     CursorPositionChange(Position, Position),
@@ -54,6 +68,10 @@ pub struct AnsiEditor {
     //pub attr_changed: std::boxed::Box<dyn Fn(TextAttribute)>
     pub request_focus: bool,
     pub color_mode: ColorMode,
+    blend_texture: Option<(usize, Option<egui::TextureHandle>)>,
+    pub saved_selections: Vec<SavedSelection>,
+    pub ghost_layer: Option<GhostLayer>,
+    pub solo_layer: Option<SoloLayer>,
 }
 
 impl UndoHandler for AnsiEditor {
@@ -100,8 +118,9 @@ impl ClipboardHandler for AnsiEditor {
     }
 
     fn copy(&mut self) -> EngineResult<()> {
-        if let Some(data) = self.buffer_view.lock().get_edit_state_mut().get_clipboard_data() {
-            push_data(BUFFER_DATA, &data)?;
+        let data = self.buffer_view.lock().get_edit_state_mut().get_clipboard_data();
+        if let Some(data) = data {
+            self.push_clipboard_data(data)?;
         } else {
             log::error!("can't get clipboard data!");
         }
@@ -152,7 +171,15 @@ impl Document for AnsiEditor {
         let mut options = SaveOptions::new();
         options.compress = false;
         options.lossles_output = true;
-        let bytes = self.buffer_view.lock().get_buffer().to_bytes(&ext, &options)?;
+        let lock = self.buffer_view.lock();
+        let solo_buf = self.get_buffer_without_solo(lock.get_buffer());
+        let buf = solo_buf.as_ref().unwrap_or(lock.get_buffer());
+        let flat_buf = if ext == ICED_EXT { None } else { flatten_blend_modes(buf) };
+        let mut bytes = flat_buf.as_ref().unwrap_or(buf).to_bytes(&ext, &options)?;
+        drop(lock);
+        if ext == ICED_EXT {
+            write_saved_selections(&mut bytes, &self.saved_selections);
+        }
         Ok(bytes)
     }
 
@@ -194,10 +221,12 @@ impl Document for AnsiEditor {
             ..Default::default()
         };
         let (mut response, calc) = show_terminal_area(ui, self.buffer_view.clone(), opt);
+        self.paint_blend_modes(ui, &calc);
 
         if calc.has_focus {
             self.request_focus = false;
         }
+        self.paint_ghost_layer(ui, &calc);
         let response_opt = response.context_menu(|ui| {
             message = terminal_context_menu(self, &options.commands, ui);
         });
@@ -244,6 +273,10 @@ impl AnsiEditor {
             last_selected_tool: 0,
             request_focus: false,
             color_mode: ColorMode::Both,
+            blend_texture: None,
+            saved_selections: Vec::new(),
+            ghost_layer: None,
+            solo_layer: None,
         }
     }
 
@@ -385,12 +418,22 @@ impl AnsiEditor {
     pub fn save_content(&self, file_name: &Path, options: &SaveOptions) -> EngineResult<bool> {
         match File::create(file_name) {
             Ok(mut f) => {
-                let content = if let Some(ext) = file_name.extension() {
-                    let ext = OsStr::to_string_lossy(ext).to_lowercase();
-                    self.buffer_view.lock().get_buffer().to_bytes(ext.as_str(), options)?
-                } else {
-                    self.buffer_view.lock().get_buffer().to_bytes(ICED_EXT, options)?
-                };
+                let ext = file_name
+                    .extension()
+                    .map_or(ICED_EXT.to_string(), |ext| OsStr::to_string_lossy(ext).to_lowercase());
+                let lock = self.buffer_view.lock();
+                let solo_buf = self.get_buffer_without_solo(lock.get_buffer());
+                let buf = solo_buf.as_ref().unwrap_or(lock.get_buffer());
+                // formats without layers get the blend modes flattened
+                let flat_buf = if ext == ICED_EXT { None } else { flatten_blend_modes(buf) };
+                let buf = flat_buf.as_ref().unwrap_or(buf);
+                // UTF-8 output uses the unicode tables of custom fonts
+                let unicode_buf = if options.modern_terminal_output { apply_unicode_tables(buf) } else { None };
+                let buf = unicode_buf.as_ref().unwrap_or(buf);
+                let mut content = buf.to_bytes(ext.as_str(), options)?;
+                if ext == ICED_EXT {
+                    write_saved_selections(&mut content, &self.saved_selections);
+                }
                 if let Err(err) = f.write_all(&content) {
                     return Err(SavingError::ErrorWritingFile(format!("{err}")).into());
                 }
@@ -561,7 +604,14 @@ impl AnsiEditor {
                     egui::Event::Copy => {}
                     egui::Event::Cut => {}
                     egui::Event::Paste(text) => {
-                        self.output_string(text);
+                        // internal data is pasted by the paste command
+                        if pop_data(BUFFER_DATA).is_none() && !cur_tool.handle_paste(self, text) {
+                            match self.paste_text(text) {
+                                Ok(true) => {}
+                                Ok(false) => self.output_string(text),
+                                Err(err) => log::error!("Error pasting text: {err}"),
+                            }
+                        }
                     }
 
                     egui::Event::CompositionEnd(text) | egui::Event::Text(text) => {
@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+
+use i18n_embed_fl::fl;
+use icy_engine::{AttributedChar, EngineResult, Position, TextPane};
+
+use super::AnsiEditor;
+use crate::model::select_imp::SelectionModifier;
+
+/// Private ancillary PNG chunk holding the saved selections of an .icy file, other readers skip it.
+const SELECTIONS_CHUNK: &[u8; 4] = b"icSl";
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModifySelection {
+    Grow,
+    Shrink,
+    Border,
+    Smooth,
+}
+
+impl ModifySelection {
+    pub fn get_label(self) -> String {
+        match self {
+            ModifySelection::Grow => fl!(crate::LANGUAGE_LOADER, "menu-modify-selection-grow"),
+            ModifySelection::Shrink => fl!(crate::LANGUAGE_LOADER, "menu-modify-selection-shrink"),
+            ModifySelection::Border => fl!(crate::LANGUAGE_LOADER, "menu-modify-selection-border"),
+            ModifySelection::Smooth => fl!(crate::LANGUAGE_LOADER, "menu-modify-selection-smooth"),
+        }
+    }
+}
+
+/// A selection mask stored under a name in the document, like an alpha channel.
+#[derive(Clone, Debug)]
+pub struct SavedSelection {
+    pub name: String,
+    pub cells: HashSet<Position>,
+}
+
+impl AnsiEditor {
+    /// Returns all selected cells of the document in buffer coordinates.
+    pub fn get_selected_cells(&self) -> HashSet<Position> {
+        let lock = self.buffer_view.lock();
+        let state = lock.get_edit_state();
+        let mut cells = HashSet::new();
+        if !state.is_something_selected() {
+            return cells;
+        }
+        for y in 0..lock.get_buffer().get_height() {
+            for x in 0..lock.get_buffer().get_width() {
+                let pos = Position::new(x, y);
+                if state.get_is_selected(pos) {
+                    cells.insert(pos);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Combines the cells with the current selection.
+    pub fn apply_selection_cells(&mut self, cells: &HashSet<Position>, operation: SelectionModifier) {
+        let _undo = self.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-select"));
+        let mut lock = self.buffer_view.lock();
+        let _ = lock.get_edit_state_mut().add_selection_to_mask();
+        let _ = lock.get_edit_state_mut().deselect();
+        lock.get_edit_state_mut()
+            .enumerate_selections(|pos, _, _| operation.get_response(cells.contains(&pos)));
+    }
+
+    pub fn save_selection(&mut self, name: String) {
+        let cells = self.get_selected_cells();
+        if let Some(saved) = self.saved_selections.iter_mut().find(|s| s.name == name) {
+            saved.cells = cells;
+        } else {
+            self.saved_selections.push(SavedSelection { name, cells });
+        }
+    }
+
+    pub fn apply_saved_selection(&mut self, index: usize, operation: SelectionModifier) {
+        if let Some(saved) = self.saved_selections.get(index) {
+            let cells = saved.cells.clone();
+            self.apply_selection_cells(&cells, operation);
+        }
+    }
+
+    pub fn delete_saved_selection(&mut self, index: usize) {
+        if index < self.saved_selections.len() {
+            self.saved_selections.remove(index);
+        }
+    }
+
+    /// Selects all non transparent cells of a layer.
+    pub fn selection_from_layer(&mut self, layer: usize, operation: SelectionModifier) {
+        let cells = {
+            let lock = self.buffer_view.lock();
+            let Some(layer) = lock.get_buffer().layers.get(layer) else {
+                return;
+            };
+            let offset = layer.get_offset();
+            let mut cells = HashSet::new();
+            for y in 0..layer.get_height() {
+                for x in 0..layer.get_width() {
+                    let pos = Position::new(x, y);
+                    if !layer.get_char(pos).is_transparent() {
+                        cells.insert(pos + offset);
+                    }
+                }
+            }
+            cells
+        };
+        self.apply_selection_cells(&cells, operation);
+    }
+
+    /// Moves the selected cells into a floating layer at the same position.
+    pub fn float_selection(&mut self) -> EngineResult<()> {
+        let cells = self.get_selected_cells();
+        let (Some(x), Some(y)) = (cells.iter().map(|p| p.x).min(), cells.iter().map(|p| p.y).min()) else {
+            return Ok(());
+        };
+        let _undo = self.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-float-selection"));
+        let mut lock = self.buffer_view.lock();
+        let edit_state = lock.get_edit_state_mut();
+        let Some(data) = edit_state.get_clipboard_data() else {
+            return Ok(());
+        };
+        edit_state.erase_selection()?;
+        edit_state.paste_clipboard_data(&data)?;
+        edit_state.move_layer(Position::new(x, y))
+    }
+
+    /// Changes the selection mask by `amount` cells. Grow and shrink use the 8 cell neighbourhood,
+    /// border keeps the ring of the selection that lies within `amount` cells of its edge and
+    /// smooth removes single cells and fills single holes `amount` times.
+    pub fn modify_selection(&mut self, modify: ModifySelection, amount: i32) {
+        let cells = self.get_selected_cells();
+        if cells.is_empty() || amount <= 0 {
+            return;
+        }
+        let size = self.buffer_view.lock().get_buffer().get_size();
+        let cells = match modify {
+            ModifySelection::Grow => grow_cells(&cells, amount, size.width, size.height),
+            ModifySelection::Shrink => shrink_cells(&cells, amount, size.width, size.height),
+            ModifySelection::Border => {
+                let inner = shrink_cells(&cells, amount, size.width, size.height);
+                cells.difference(&inner).copied().collect()
+            }
+            ModifySelection::Smooth => {
+                let mut cells = cells;
+                for _ in 0..amount {
+                    cells = smooth_cells(&cells, size.width, size.height);
+                }
+                cells
+            }
+        };
+        self.apply_selection_cells(&cells, SelectionModifier::Replace);
+    }
+
+    /// Adds all cells of the current layer to the selection that look like one of the selected cells.
+    pub fn select_similar(&mut self) {
+        let cells = self.get_selected_cells();
+        let Ok(cur_layer) = self.get_cur_layer_index() else {
+            return;
+        };
+        let mut similar = Vec::new();
+        {
+            let lock = self.buffer_view.lock();
+            let Some(layer) = lock.get_buffer().layers.get(cur_layer) else {
+                return;
+            };
+            let offset = layer.get_offset();
+            for pos in cells {
+                let ch = layer.get_char(pos - offset);
+                if !similar.iter().any(|s: &AttributedChar| s.ch == ch.ch && s.attribute == ch.attribute) {
+                    similar.push(ch);
+                }
+            }
+        }
+        if similar.is_empty() {
+            return;
+        }
+        let _undo = self.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-select"));
+        let mut lock = self.buffer_view.lock();
+        let _ = lock.get_edit_state_mut().add_selection_to_mask();
+        let _ = lock.get_edit_state_mut().deselect();
+        lock.get_edit_state_mut()
+            .enumerate_selections(|_, ch, _| SelectionModifier::Add.get_response(similar.iter().any(|s| s.ch == ch.ch && s.attribute == ch.attribute)));
+    }
+}
+
+/// Adds the saved selections as extra chunk in front of the IEND chunk of an .icy file.
+pub fn write_saved_selections(icy_bytes: &mut Vec<u8>, selections: &[SavedSelection]) {
+    if selections.is_empty() || !icy_bytes.starts_with(PNG_SIGNATURE) || !icy_bytes.ends_with(&[b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]) {
+        return;
+    }
+    let mut data = Vec::new();
+    data.extend((selections.len() as u32).to_be_bytes());
+    for selection in selections {
+        data.extend((selection.name.len() as u32).to_be_bytes());
+        data.extend(selection.name.as_bytes());
+        let mut cells: Vec<&Position> = selection.cells.iter().collect();
+        cells.sort_by_key(|pos| (pos.y, pos.x));
+        data.extend((cells.len() as u32).to_be_bytes());
+        for pos in cells {
+            data.extend(pos.x.to_be_bytes());
+            data.extend(pos.y.to_be_bytes());
+        }
+    }
+
+    let mut chunk = Vec::new();
+    chunk.extend((data.len() as u32).to_be_bytes());
+    chunk.extend(SELECTIONS_CHUNK);
+    chunk.extend(&data);
+    chunk.extend(crc32(&chunk[4..]).to_be_bytes());
+    let iend = icy_bytes.len() - 12;
+    icy_bytes.splice(iend..iend, chunk);
+}
+
+/// Reads the saved selections of an .icy file, files without them return an empty list.
+pub fn read_saved_selections(icy_bytes: &[u8]) -> Vec<SavedSelection> {
+    let mut result = Vec::new();
+    if !icy_bytes.starts_with(PNG_SIGNATURE) {
+        return result;
+    }
+    let mut i = PNG_SIGNATURE.len();
+    while i + 8 <= icy_bytes.len() {
+        let len = u32::from_be_bytes([icy_bytes[i], icy_bytes[i + 1], icy_bytes[i + 2], icy_bytes[i + 3]]) as usize;
+        let Some(data) = icy_bytes.get(i + 8..i + 8 + len) else {
+            break;
+        };
+        if &icy_bytes[i + 4..i + 8] == SELECTIONS_CHUNK {
+            if let Some(selections) = parse_saved_selections(data) {
+                result = selections;
+            } else {
+                log::error!("Invalid saved selections in file.");
+            }
+            break;
+        }
+        i += len + 12;
+    }
+    result
+}
+
+fn take<'a>(data: &'a [u8], i: &mut usize, len: usize) -> Option<&'a [u8]> {
+    let bytes = data.get(*i..*i + len)?;
+    *i += len;
+    Some(bytes)
+}
+
+fn read_u32(data: &[u8], i: &mut usize) -> Option<u32> {
+    let b = take(data, i, 4)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn parse_saved_selections(data: &[u8]) -> Option<Vec<SavedSelection>> {
+    let mut i = 0;
+    let count = read_u32(data, &mut i)?;
+    let mut result = Vec::new();
+    for _ in 0..count {
+        let name_len = read_u32(data, &mut i)? as usize;
+        let name = String::from_utf8_lossy(take(data, &mut i, name_len)?).to_string();
+        let cell_count = read_u32(data, &mut i)?;
+        let mut cells = HashSet::new();
+        for _ in 0..cell_count {
+            let x = read_u32(data, &mut i)? as i32;
+            let y = read_u32(data, &mut i)? as i32;
+            cells.insert(Position::new(x, y));
+        }
+        result.push(SavedSelection { name, cells });
+    }
+    Some(result)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for b in data {
+        crc ^= u32::from(*b);
+        for _ in 0..8 {
+            crc = if crc & 1 == 0 { crc >> 1 } else { (crc >> 1) ^ 0xEDB8_8320 };
+        }
+    }
+    !crc
+}
+
+fn grow_cells(cells: &HashSet<Position>, amount: i32, width: i32, height: i32) -> HashSet<Position> {
+    let mut result = HashSet::new();
+    for pos in cells {
+        for y in (pos.y - amount).max(0)..=(pos.y + amount).min(height - 1) {
+            for x in (pos.x - amount).max(0)..=(pos.x + amount).min(width - 1) {
+                result.insert(Position::new(x, y));
+            }
+        }
+    }
+    result
+}
+
+/// Cells outside of the buffer count as selected, otherwise a selection touching the border would shrink away from it.
+fn shrink_cells(cells: &HashSet<Position>, amount: i32, width: i32, height: i32) -> HashSet<Position> {
+    let unselected: Vec<Position> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| Position::new(x, y)))
+        .filter(|pos| !cells.contains(pos))
+        .collect();
+    let removed = grow_cells(&unselected.into_iter().collect(), amount, width, height);
+    cells.difference(&removed).copied().collect()
+}
+
+/// Removes selected cells without selected neighbours and fills unselected cells surrounded by 8 selected ones.
+fn smooth_cells(cells: &HashSet<Position>, width: i32, height: i32) -> HashSet<Position> {
+    let mut result = HashSet::new();
+    for y in 0..height {
+        for x in 0..width {
+            let mut neighbours = 0;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if (dx != 0 || dy != 0) && cells.contains(&Position::new(x + dx, y + dy)) {
+                        neighbours += 1;
+                    }
+                }
+            }
+            let pos = Position::new(x, y);
+            let is_selected = cells.contains(&pos);
+            if is_selected && neighbours > 0 || !is_selected && neighbours == 8 {
+                result.insert(pos);
+            }
+        }
+    }
+    result
+}
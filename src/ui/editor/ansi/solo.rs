@@ -0,0 +1,69 @@
+use icy_engine::Buffer;
+
+use super::{AnsiEditor, LayerRef};
+
+/// Shows a single layer, the other layers are hidden until the solo view ends.
+/// Solo is only a view setting: the visibility is changed without undo and the original
+/// visibility is restored before the document gets saved.
+pub struct SoloLayer {
+    pub layer: LayerRef,
+    /// Visibility of all layers before the solo view started.
+    visibility: Vec<(LayerRef, bool)>,
+}
+
+impl SoloLayer {
+    fn restore_visibility(&self, buf: &mut Buffer) {
+        for (layer, is_visible) in &self.visibility {
+            if let Some(i) = layer.find(&buf.layers) {
+                buf.layers[i].properties.is_visible = *is_visible;
+            }
+        }
+    }
+}
+
+impl AnsiEditor {
+    pub fn get_solo_layer(&self) -> Option<usize> {
+        let solo = self.solo_layer.as_ref()?;
+        solo.layer.find(&self.buffer_view.lock().get_buffer().layers)
+    }
+
+    /// Shows only the given layer, toggling the same layer again ends the solo view.
+    pub fn toggle_solo_layer(&mut self, index: usize) {
+        let was_solo = self.get_solo_layer() == Some(index);
+        self.end_solo_layer();
+        if was_solo {
+            return;
+        }
+        let mut lock = self.buffer_view.lock();
+        let layers = &mut lock.get_buffer_mut().layers;
+        let Some(layer) = LayerRef::new(layers, index) else {
+            return;
+        };
+        let visibility = (0..layers.len())
+            .filter_map(|i| LayerRef::new(layers, i).map(|layer| (layer, layers[i].properties.is_visible)))
+            .collect();
+        for (i, layer) in layers.iter_mut().enumerate() {
+            layer.properties.is_visible = i == index;
+        }
+        lock.get_edit_state_mut().set_is_buffer_dirty();
+        drop(lock);
+        self.solo_layer = Some(SoloLayer { layer, visibility });
+    }
+
+    pub fn end_solo_layer(&mut self) {
+        if let Some(solo) = self.solo_layer.take() {
+            let mut lock = self.buffer_view.lock();
+            solo.restore_visibility(lock.get_buffer_mut());
+            lock.get_edit_state_mut().set_is_buffer_dirty();
+        }
+    }
+
+    /// Returns a copy of the buffer with the visibility the layers had before the solo view,
+    /// `None` if there is no solo view.
+    pub(super) fn get_buffer_without_solo(&self, buf: &Buffer) -> Option<Buffer> {
+        let solo = self.solo_layer.as_ref()?;
+        let mut buf = buf.clone();
+        solo.restore_visibility(&mut buf);
+        Some(buf)
+    }
+}
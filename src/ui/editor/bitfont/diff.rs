@@ -0,0 +1,200 @@
+//! Glyph comparison between two bit fonts and glyph patches. A patch is a set of glyphs that can be
+//! saved to a file and applied to the edited font or to many font files at once.
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use i18n_embed_fl::fl;
+use icy_engine::{BitFont, Size};
+
+use crate::{bytes_per_row, get_glyph_pixel, is_font_extensions, resize_glyph_data, BitFontFormat, BitFontSaveOptions, TerminalResult, UnicodeTable};
+
+const PATCH_HEADER: &str = "ICYGLYPHPATCH 1";
+
+fn get_glyph_data(font: &BitFont, ch: char) -> &[u8] {
+    font.get_glyph(ch).map(|glyph| glyph.data.as_slice()).unwrap_or_default()
+}
+
+/// Returns the glyphs that differ between the fonts, glyphs of different sizes are compared pixel by pixel.
+pub fn diff_glyphs(font: &BitFont, other: &BitFont) -> Vec<char> {
+    let width = font.size.width.max(other.size.width);
+    let height = font.size.height.max(other.size.height);
+    let length = (font.length as usize).max(other.length as usize);
+    let mut result = Vec::new();
+    for i in 0..length {
+        let Some(ch) = char::from_u32(i as u32) else {
+            continue;
+        };
+        let (a, b) = (get_glyph_data(font, ch), get_glyph_data(other, ch));
+        let differs = (0..height).any(|y| {
+            (0..width).any(|x| {
+                let pixel_a = y < font.size.height && get_glyph_pixel(a, font.size.width, x, y);
+                let pixel_b = y < other.size.height && get_glyph_pixel(b, other.size.width, x, y);
+                pixel_a != pixel_b
+            })
+        });
+        if differs {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GlyphPatch {
+    pub size: Size,
+    pub glyphs: Vec<(char, Vec<u8>)>,
+}
+
+impl GlyphPatch {
+    /// Takes the given glyphs from the font.
+    pub fn from_font(font: &BitFont, chars: &[char]) -> Self {
+        let len = bytes_per_row(font.size.width) * font.size.height as usize;
+        let glyphs = chars
+            .iter()
+            .filter(|ch| (**ch as usize) < font.length as usize)
+            .map(|ch| {
+                let mut data = get_glyph_data(font, *ch).to_vec();
+                data.resize(len, 0);
+                (*ch, data)
+            })
+            .collect();
+        Self { size: font.size, glyphs }
+    }
+
+    /// The glyph data converted to the given glyph size.
+    pub fn get_glyphs(&self, size: Size) -> Vec<(char, Vec<u8>)> {
+        self.glyphs.iter().map(|(ch, data)| (*ch, resize_glyph_data(data, self.size, size))).collect()
+    }
+
+    /// Returns a copy of the font with the glyphs of the patch, glyphs outside of the font are skipped.
+    pub fn apply(&self, font: &BitFont) -> BitFont {
+        let mut result = font.clone();
+        for (ch, data) in self.get_glyphs(font.size) {
+            if let Some(glyph) = result.get_glyph_mut(ch) {
+                glyph.data = data;
+            }
+        }
+        result
+    }
+
+    /// Text format: a header line, the glyph size and for every glyph its number followed by one hex line per row.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = String::new();
+        let _ = writeln!(result, "{PATCH_HEADER}");
+        let _ = writeln!(result, "SIZE {} {}", self.size.width, self.size.height);
+        for (ch, data) in &self.glyphs {
+            let _ = writeln!(result, "GLYPH {}", *ch as u32);
+            for row in data.chunks(bytes_per_row(self.size.width)) {
+                for b in row {
+                    let _ = write!(result, "{b:02X}");
+                }
+                let _ = writeln!(result);
+            }
+        }
+        result.into_bytes()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> TerminalResult<Self> {
+        let invalid = || anyhow::anyhow!(fl!(crate::LANGUAGE_LOADER, "glyph-patch-error-invalid"));
+        let text = String::from_utf8_lossy(data);
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next() != Some(PATCH_HEADER) {
+            return Err(invalid());
+        }
+        let size = lines
+            .next()
+            .and_then(|line| line.strip_prefix("SIZE "))
+            .and_then(|size| size.split_once(' '))
+            .and_then(|(w, h)| Some(Size::new(w.trim().parse().ok()?, h.trim().parse().ok()?)))
+            .filter(|size| (1..=32).contains(&size.width) && (1..=32).contains(&size.height))
+            .ok_or_else(invalid)?;
+
+        let mut glyphs = Vec::new();
+        let bpr = bytes_per_row(size.width);
+        while let Some(line) = lines.next() {
+            let ch = line
+                .strip_prefix("GLYPH ")
+                .and_then(|num| num.trim().parse::<u32>().ok())
+                .and_then(char::from_u32)
+                .ok_or_else(invalid)?;
+            let mut data = Vec::with_capacity(bpr * size.height as usize);
+            for _ in 0..size.height {
+                let row = lines.next().ok_or_else(invalid)?;
+                if row.len() != bpr * 2 {
+                    return Err(invalid());
+                }
+                for i in 0..bpr {
+                    data.push(u8::from_str_radix(&row[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?);
+                }
+            }
+            glyphs.push((ch, data));
+        }
+        Ok(Self { size, glyphs })
+    }
+
+    /// Applies the patch to a font file and writes it back in its own format, PSF unicode tables are kept.
+    /// The original file is kept as backup with a `.bak` extension appended.
+    pub fn patch_file(&self, path: &Path) -> TerminalResult<()> {
+        let data = fs::read(path)?;
+        // the unicode table is written back without the sequences, rather leave those fonts alone
+        if has_psf_sequences(&data) {
+            return Err(anyhow::anyhow!(fl!(crate::LANGUAGE_LOADER, "glyph-patch-error-sequences")));
+        }
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let font = BitFont::from_bytes(file_name, &data)?;
+        let table = UnicodeTable::from_psf_bytes(&data);
+        let options = BitFontSaveOptions {
+            psf1: data.starts_with(&[0x36, 0x04]),
+            unicode_table: table.is_some(),
+            unicode_encoding: true,
+            face_name: String::new(),
+        };
+        let ext = path.extension().unwrap_or_default().to_str().unwrap_or_default();
+        let Some(format) = BitFontFormat::from_extension(ext, &options) else {
+            return Err(anyhow::anyhow!(fl!(crate::LANGUAGE_LOADER, "glyph-patch-error-format", ext = ext)));
+        };
+        let table = table.unwrap_or_else(|| UnicodeTable::from_cp437(font.length as usize));
+        let bytes = format.to_bytes(&self.apply(&font), &table, &options)?;
+        fs::write(get_backup_path(path), &data)?;
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Patches the font files, returns the number of patched files and the errors.
+    pub fn patch_files(&self, files: &[PathBuf]) -> (usize, Vec<String>) {
+        let mut patched = 0;
+        let mut errors = Vec::new();
+        for path in files {
+            match self.patch_file(path) {
+                Ok(()) => patched += 1,
+                Err(err) => errors.push(format!("{}: {err}", path.display())),
+            }
+        }
+        (patched, errors)
+    }
+}
+
+/// Returns the font files of the directory, sorted by name.
+pub fn get_font_files(dir: &Path) -> TerminalResult<Vec<PathBuf>> {
+    let mut result = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let ext = path.extension().unwrap_or_default().to_string_lossy().to_ascii_lowercase();
+        if path.is_file() && is_font_extensions(&ext) {
+            result.push(path);
+        }
+    }
+    result.sort();
+    Ok(result)
+}
+
+/// `font.psf` is backed up as `font.psf.bak`.
+pub fn get_backup_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".bak");
+    path.with_file_name(file_name)
+}
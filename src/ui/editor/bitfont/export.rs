@@ -0,0 +1,156 @@
+//! Renders a bit font to images for publishing: a glyph sheet with all glyphs or a specimen showing
+//! text or a whole document set in the font.
+
+use icy_engine::{AttributedChar, BitFont, Buffer, Position, Rectangle, TextAttribute, TextPane};
+use image::{Rgba, RgbaImage};
+
+use crate::{create_font_buffer, get_glyph_pixel, is_wide_font};
+
+/// Glyphs per line of the glyph sheet.
+pub const SHEET_WIDTH: usize = 16;
+
+#[derive(Clone, Debug)]
+pub struct FontImageSettings {
+    pub foreground: [u8; 3],
+    pub background: [u8; 3],
+    pub scale: u32,
+    pub grid: bool,
+    pub grid_color: [u8; 3],
+}
+
+impl Default for FontImageSettings {
+    fn default() -> Self {
+        Self {
+            foreground: [0xAA, 0xAA, 0xAA],
+            background: [0x00, 0x00, 0x00],
+            scale: 2,
+            grid: true,
+            grid_color: [0x55, 0x55, 0x55],
+        }
+    }
+}
+
+impl FontImageSettings {
+    fn get_attribute(&self, buffer: &mut Buffer) -> TextAttribute {
+        let [r, g, b] = self.foreground;
+        let fg = buffer.palette.insert_color_rgb(r, g, b);
+        let [r, g, b] = self.background;
+        let bg = buffer.palette.insert_color_rgb(r, g, b);
+        let mut attr = TextAttribute::default();
+        attr.set_foreground(fg);
+        attr.set_background(bg);
+        attr
+    }
+
+    /// Scales the rendered buffer and draws grid lines between the cells.
+    fn finish(&self, buffer: &Buffer) -> RgbaImage {
+        let image = if buffer.get_font(0).is_some_and(is_wide_font) {
+            render_wide_font(buffer)
+        } else {
+            let (size, pixels) = buffer.render_to_rgba(Rectangle::from(0, 0, buffer.get_width(), buffer.get_height()));
+            RgbaImage::from_raw(size.width as u32, size.height as u32, pixels).unwrap_or_default()
+        };
+        let font_size = buffer.get_font_dimensions();
+        let (cell_width, cell_height) = (font_size.width as u32, font_size.height as u32);
+        let scale = self.scale.max(1);
+        let grid = u32::from(self.grid);
+        let scaled_cell = (cell_width * scale + grid, cell_height * scale + grid);
+        let (width, height) = (buffer.get_width() as u32, buffer.get_height() as u32);
+        let [r, g, b] = self.grid_color;
+        let mut result = RgbaImage::from_pixel(width * scaled_cell.0 + grid, height * scaled_cell.1 + grid, Rgba([r, g, b, 255]));
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                let pixel = *image.get_pixel(x, y);
+                let (cx, cy) = (x / cell_width.max(1), y / cell_height.max(1));
+                let left = grid + cx * scaled_cell.0 + (x % cell_width.max(1)) * scale;
+                let top = grid + cy * scaled_cell.1 + (y % cell_height.max(1)) * scale;
+                for sy in 0..scale {
+                    for sx in 0..scale {
+                        result.put_pixel(left + sx, top + sy, pixel);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Renders all glyphs of the font, 16 glyphs per line.
+    pub fn render_sheet(&self, font: &BitFont) -> RgbaImage {
+        let mut buffer = create_font_buffer(font, SHEET_WIDTH, TextAttribute::default(), TextAttribute::default(), usize::MAX);
+        let attr = self.get_attribute(&mut buffer);
+        for y in 0..buffer.get_height() {
+            for x in 0..buffer.get_width() {
+                let mut ch = buffer.get_char(Position::new(x, y));
+                ch.attribute = attr;
+                buffer.layers[0].set_char(Position::new(x, y), ch);
+            }
+        }
+        self.finish(&buffer)
+    }
+
+    /// Renders the text line by line, characters are mapped 1:1 to glyphs.
+    pub fn render_text(&self, font: &BitFont, text: &str) -> RgbaImage {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(1).max(1);
+        let mut buffer = Buffer::new((width, lines.len().max(1)));
+        buffer.set_font(0, font.clone());
+        let attr = self.get_attribute(&mut buffer);
+        for y in 0..buffer.get_height() {
+            let mut chars = lines.get(y as usize).map(|line| line.chars()).into_iter().flatten();
+            for x in 0..buffer.get_width() {
+                let ch = chars.next().unwrap_or(' ');
+                let ch = get_glyph_char(font, ch);
+                buffer.layers[0].set_char(Position::new(x, y), AttributedChar::new(ch, attr));
+            }
+        }
+        // a grid between text cells would only make the specimen harder to read
+        Self { grid: false, ..self.clone() }.finish(&buffer)
+    }
+
+    /// Renders the document with its colors, using the font for font page 0.
+    pub fn render_document(&self, font: &BitFont, mut buffer: Buffer) -> RgbaImage {
+        buffer.set_font(0, font.clone());
+        Self { grid: false, ..self.clone() }.finish(&buffer)
+    }
+}
+
+/// The engine renders glyphs from one byte per row, buffers with wider fonts are rasterized here.
+fn render_wide_font(buffer: &Buffer) -> RgbaImage {
+    let font_size = buffer.get_font_dimensions();
+    let (cell_width, cell_height) = (font_size.width, font_size.height);
+    let mut image = RgbaImage::new((buffer.get_width() * cell_width) as u32, (buffer.get_height() * cell_height) as u32);
+    let get_color = |color: u32| {
+        let (r, g, b) = buffer.palette.get_color(color).get_rgb();
+        Rgba([r, g, b, 255])
+    };
+    for y in 0..buffer.get_height() {
+        for x in 0..buffer.get_width() {
+            let ch = buffer.get_char(Position::new(x, y));
+            let fg = get_color(ch.attribute.get_foreground());
+            let bg = get_color(ch.attribute.get_background());
+            let font = buffer.get_font(ch.get_font_page()).or_else(|| buffer.get_font(0));
+            let glyph = font.and_then(|font| font.get_glyph(ch.ch).map(|glyph| (font.size.width, glyph)));
+            for gy in 0..cell_height {
+                for gx in 0..cell_width {
+                    let is_set = glyph.is_some_and(|(width, glyph)| get_glyph_pixel(&glyph.data, width, gx, gy));
+                    image.put_pixel((x * cell_width + gx) as u32, (y * cell_height + gy) as u32, if is_set { fg } else { bg });
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Maps unicode text to the glyph slots of a CP437 font, glyphs missing in the font show as '?'.
+fn get_glyph_char(font: &BitFont, ch: char) -> char {
+    let ch = if ch.is_ascii() {
+        ch
+    } else {
+        icy_engine::ascii::CP437Converter::default().convert_from_unicode(ch, 0)
+    };
+    if font.get_glyph(ch).is_some() {
+        ch
+    } else {
+        '?'
+    }
+}
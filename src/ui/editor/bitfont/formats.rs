@@ -0,0 +1,314 @@
+//! Writers for the bit font formats the font editor can save. The format follows the extension
+//! of the save path, options that can't be derived from the font are kept in [`BitFontSaveOptions`].
+
+use std::{collections::HashSet, fmt::Write};
+
+use i18n_embed_fl::fl;
+use icy_engine::{AttributedChar, BitFont, TextAttribute};
+
+use crate::{bytes_per_row, TerminalResult, UnicodeTable};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitFontFormat {
+    /// Linux console font, version 1 - 8 pixel wide, 256 or 512 glyphs.
+    Psf1,
+    /// Linux console font, version 2.
+    Psf2,
+    /// Headerless DOS font (.f08, .f14, .f16 …) - 256 glyphs, one byte per line.
+    Raw,
+    /// Windows 2.0 raster font resource.
+    Fnt,
+    /// X11 Glyph Bitmap Distribution Format.
+    Bdf,
+}
+
+impl BitFontFormat {
+    pub fn from_extension(ext: &str, options: &BitFontSaveOptions) -> Option<Self> {
+        let ext = ext.to_ascii_lowercase();
+        match ext.as_str() {
+            "psf" | "psfu" => Some(if options.psf1 { Self::Psf1 } else { Self::Psf2 }),
+            "fnt" => Some(Self::Fnt),
+            "bdf" => Some(Self::Bdf),
+            _ => {
+                if ext.len() == 3 && ext.starts_with('f') && ext[1..].chars().all(|c| c.is_ascii_digit()) {
+                    Some(Self::Raw)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub fn get_label(self) -> String {
+        match self {
+            BitFontFormat::Psf1 => fl!(crate::LANGUAGE_LOADER, "font-format-psf1"),
+            BitFontFormat::Psf2 => fl!(crate::LANGUAGE_LOADER, "font-format-psf2"),
+            BitFontFormat::Raw => fl!(crate::LANGUAGE_LOADER, "font-format-raw"),
+            BitFontFormat::Fnt => fl!(crate::LANGUAGE_LOADER, "font-format-fnt"),
+            BitFontFormat::Bdf => fl!(crate::LANGUAGE_LOADER, "font-format-bdf"),
+        }
+    }
+
+    pub fn to_bytes(self, font: &BitFont, table: &UnicodeTable, options: &BitFontSaveOptions) -> TerminalResult<Vec<u8>> {
+        match self {
+            BitFontFormat::Psf1 => to_psf1_bytes(font, table, options),
+            BitFontFormat::Psf2 => Ok(to_psf2_bytes(font, table, options)),
+            BitFontFormat::Raw => to_raw_bytes(font),
+            BitFontFormat::Fnt => to_fnt_bytes(font, options),
+            BitFontFormat::Bdf => Ok(to_bdf_bytes(font, table, options)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitFontSaveOptions {
+    /// Save .psf files as PSF1 instead of PSF2.
+    pub psf1: bool,
+    /// Append the unicode table of the font to PSF fonts.
+    pub unicode_table: bool,
+    /// Use unicode code points as BDF glyph encoding instead of the glyph index.
+    pub unicode_encoding: bool,
+    /// Face name for FNT and BDF fonts, the font name is used if it's empty.
+    pub face_name: String,
+}
+
+impl Default for BitFontSaveOptions {
+    fn default() -> Self {
+        Self {
+            psf1: false,
+            unicode_table: true,
+            unicode_encoding: true,
+            face_name: String::new(),
+        }
+    }
+}
+
+impl BitFontSaveOptions {
+    fn get_face_name(&self, font: &BitFont) -> String {
+        let name = if self.face_name.is_empty() { &font.name } else { &self.face_name };
+        let name = name.trim();
+        if name.is_empty() {
+            "Untitled".to_string()
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// Returns the glyph data with `(width + 7) / 8` bytes per row.
+fn get_glyph_rows(font: &BitFont, i: usize) -> Vec<u8> {
+    let mut rows = char::from_u32(i as u32)
+        .and_then(|ch| font.get_glyph(ch))
+        .map(|glyph| glyph.data.clone())
+        .unwrap_or_default();
+    rows.resize(bytes_per_row(font.size.width) * font.size.height as usize, 0);
+    rows
+}
+
+pub(super) fn get_unicode(i: usize) -> char {
+    let ch = char::from_u32(i as u32).unwrap_or('\0');
+    if i < 256 {
+        icy_engine::ascii::CP437Converter::default().convert_to_unicode(AttributedChar::new(ch, TextAttribute::default()))
+    } else {
+        ch
+    }
+}
+
+fn check_width(font: &BitFont, format: BitFontFormat) -> TerminalResult<()> {
+    if font.size.width > 8 {
+        return Err(anyhow::anyhow!(fl!(
+            crate::LANGUAGE_LOADER,
+            "font-format-error-width",
+            format = format.get_label(),
+            width = font.size.width
+        )));
+    }
+    Ok(())
+}
+
+fn to_psf1_bytes(font: &BitFont, table: &UnicodeTable, options: &BitFontSaveOptions) -> TerminalResult<Vec<u8>> {
+    check_width(font, BitFontFormat::Psf1)?;
+    let length = if font.length as usize > 256 { 512 } else { 256 };
+    let mut mode = 0;
+    if length == 512 {
+        mode |= 0x01;
+    }
+    if options.unicode_table {
+        mode |= 0x02;
+    }
+    let mut result = vec![0x36, 0x04, mode, font.size.height as u8];
+    for i in 0..length {
+        result.extend(get_glyph_rows(font, i));
+    }
+    if options.unicode_table {
+        for i in 0..length {
+            for ch in table.get(i) {
+                // PSF1 tables only hold the basic multilingual plane
+                if let Ok(ch) = u16::try_from(*ch as u32) {
+                    result.extend(ch.to_le_bytes());
+                }
+            }
+            result.extend(0xFFFFu16.to_le_bytes());
+        }
+    }
+    Ok(result)
+}
+
+fn to_psf2_bytes(font: &BitFont, table: &UnicodeTable, options: &BitFontSaveOptions) -> Vec<u8> {
+    let char_size = bytes_per_row(font.size.width) * font.size.height as usize;
+    let header = [
+        0x864A_B572,                      // magic
+        0,                                // version
+        32,                               // header size
+        u32::from(options.unicode_table), // flags
+        font.length as u32,               // glyph count
+        char_size as u32,                 // bytes per glyph
+        font.size.height as u32,          // height
+        font.size.width as u32,           // width
+    ];
+    let mut result = Vec::new();
+    for value in header {
+        result.extend(value.to_le_bytes());
+    }
+    for i in 0..font.length as usize {
+        result.extend(get_glyph_rows(font, i));
+    }
+    if options.unicode_table {
+        for i in 0..font.length as usize {
+            for ch in table.get(i) {
+                let mut buf = [0; 4];
+                result.extend(ch.encode_utf8(&mut buf).as_bytes());
+            }
+            result.push(0xFF);
+        }
+    }
+    result
+}
+
+fn to_raw_bytes(font: &BitFont) -> TerminalResult<Vec<u8>> {
+    check_width(font, BitFontFormat::Raw)?;
+    let mut result = Vec::new();
+    for i in 0..256 {
+        result.extend(get_glyph_rows(font, i));
+    }
+    Ok(result)
+}
+
+fn to_fnt_bytes(font: &BitFont, options: &BitFontSaveOptions) -> TerminalResult<Vec<u8>> {
+    check_width(font, BitFontFormat::Fnt)?;
+    const HEADER_SIZE: usize = 118;
+    let first_char = 0u8;
+    let last_char = ((font.length as usize).min(256) - 1) as u8;
+    // one extra entry for the sentinel glyph
+    let char_count = usize::from(last_char - first_char) + 2;
+    let height = font.size.height as usize;
+    let bitmap_offset = HEADER_SIZE + char_count * 4;
+    let face_offset = bitmap_offset + char_count * height;
+    let face_name = options.get_face_name(font);
+    let size = face_offset + face_name.len() + 1;
+    let copyright = b"Created with icy draw";
+
+    let mut result = Vec::with_capacity(size);
+    result.extend(0x0200u16.to_le_bytes()); // version
+    result.extend((size as u32).to_le_bytes());
+    let mut copyright_field = [0u8; 60];
+    copyright_field[..copyright.len()].copy_from_slice(copyright);
+    result.extend(copyright_field);
+    result.extend(0u16.to_le_bytes()); // type: raster
+    result.extend((height as u16).to_le_bytes()); // points
+    result.extend(96u16.to_le_bytes()); // vertical resolution
+    result.extend(96u16.to_le_bytes()); // horizontal resolution
+    result.extend((height as u16).to_le_bytes()); // ascent
+    result.extend(0u16.to_le_bytes()); // internal leading
+    result.extend(0u16.to_le_bytes()); // external leading
+    result.extend([0, 0, 0]); // italic, underline, strike out
+    result.extend(400u16.to_le_bytes()); // weight
+    result.push(255); // OEM charset
+    result.extend((font.size.width as u16).to_le_bytes()); // pixel width
+    result.extend((height as u16).to_le_bytes()); // pixel height
+    result.push(0x30); // fixed pitch, modern family
+    result.extend((font.size.width as u16).to_le_bytes()); // average width
+    result.extend((font.size.width as u16).to_le_bytes()); // max width
+    result.extend([first_char, last_char, b'?' - first_char, b' ' - first_char]);
+    result.extend(0u16.to_le_bytes()); // width bytes
+    result.extend(0u32.to_le_bytes()); // device
+    result.extend((face_offset as u32).to_le_bytes());
+    result.extend(0u32.to_le_bytes()); // bits pointer
+    result.extend((bitmap_offset as u32).to_le_bytes());
+    result.push(0); // reserved
+
+    for i in 0..char_count {
+        result.extend((font.size.width as u16).to_le_bytes());
+        result.extend(((bitmap_offset + i * height) as u16).to_le_bytes());
+    }
+    for i in 0..char_count {
+        if i + 1 < char_count {
+            result.extend(get_glyph_rows(font, usize::from(first_char) + i));
+        } else {
+            result.extend(std::iter::repeat(0).take(height));
+        }
+    }
+    result.extend(face_name.as_bytes());
+    result.push(0);
+    Ok(result)
+}
+
+fn to_bdf_bytes(font: &BitFont, table: &UnicodeTable, options: &BitFontSaveOptions) -> Vec<u8> {
+    let width = font.size.width;
+    let height = font.size.height;
+    let bytes_per_line = bytes_per_row(width);
+    let face_name = options.get_face_name(font);
+    let descent = height / 4;
+
+    let mut result = String::new();
+    let registry = if options.unicode_encoding { "iso10646-1" } else { "ibm-cp437" };
+    let _ = writeln!(result, "STARTFONT 2.1");
+    let _ = writeln!(
+        result,
+        "FONT -icy-{}-medium-r-normal--{height}-{}-75-75-c-{}-{registry}",
+        face_name.replace('-', " "),
+        height * 10,
+        width * 10
+    );
+    let _ = writeln!(result, "SIZE {height} 75 75");
+    let _ = writeln!(result, "FONTBOUNDINGBOX {width} {height} 0 {}", -descent);
+    let _ = writeln!(result, "STARTPROPERTIES 4");
+    let _ = writeln!(result, "FAMILY_NAME \"{face_name}\"");
+    let _ = writeln!(result, "FONT_ASCENT {}", height - descent);
+    let _ = writeln!(result, "FONT_DESCENT {descent}");
+    let _ = writeln!(result, "SPACING \"C\"");
+    let _ = writeln!(result, "ENDPROPERTIES");
+    let _ = writeln!(result, "CHARS {}", font.length);
+    let mut used_code_points = HashSet::new();
+    for i in 0..font.length as usize {
+        if options.unicode_encoding {
+            // glyphs without (or with an already used) code point are stored unencoded with their index
+            match table.to_unicode(i) {
+                Some(ch) if used_code_points.insert(ch) => {
+                    let _ = writeln!(result, "STARTCHAR U+{:04X}", ch as u32);
+                    let _ = writeln!(result, "ENCODING {}", ch as u32);
+                }
+                _ => {
+                    let _ = writeln!(result, "STARTCHAR glyph{i}");
+                    let _ = writeln!(result, "ENCODING -1 {i}");
+                }
+            }
+        } else {
+            let _ = writeln!(result, "STARTCHAR char{i}");
+            let _ = writeln!(result, "ENCODING {i}");
+        }
+        let _ = writeln!(result, "SWIDTH {} 0", width * 1000 / height);
+        let _ = writeln!(result, "DWIDTH {width} 0");
+        let _ = writeln!(result, "BBX {width} {height} 0 {}", -descent);
+        let _ = writeln!(result, "BITMAP");
+        for row in get_glyph_rows(font, i).chunks(bytes_per_line) {
+            for b in row {
+                let _ = write!(result, "{b:02X}");
+            }
+            let _ = writeln!(result);
+        }
+        let _ = writeln!(result, "ENDCHAR");
+    }
+    let _ = writeln!(result, "ENDFONT");
+    result.into_bytes()
+}
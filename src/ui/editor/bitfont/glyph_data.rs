@@ -0,0 +1,84 @@
+//! Pixel access for glyph data. Glyphs up to 8 pixels wide use one byte per row, wider glyphs
+//! use `(width + 7) / 8` bytes per row like PSF2 - the leftmost pixel is the highest bit.
+
+use icy_engine::{BitFont, Size};
+
+/// The engine renders glyphs from one byte per row, wider glyphs are drawn by the editor itself.
+pub fn is_wide_font(font: &BitFont) -> bool {
+    font.size.width > 8
+}
+
+pub fn bytes_per_row(width: i32) -> usize {
+    (width.max(1) as usize + 7) / 8
+}
+
+pub fn get_glyph_pixel(data: &[u8], width: i32, x: i32, y: i32) -> bool {
+    if x < 0 || y < 0 || x >= width {
+        return false;
+    }
+    let offset = y as usize * bytes_per_row(width) + x as usize / 8;
+    match data.get(offset) {
+        Some(b) => b & (128 >> (x % 8)) != 0,
+        None => false,
+    }
+}
+
+pub fn set_glyph_pixel(data: &mut [u8], width: i32, x: i32, y: i32, value: bool) {
+    if x < 0 || y < 0 || x >= width {
+        return;
+    }
+    let offset = y as usize * bytes_per_row(width) + x as usize / 8;
+    if let Some(b) = data.get_mut(offset) {
+        if value {
+            *b |= 128 >> (x % 8);
+        } else {
+            *b &= !(128 >> (x % 8));
+        }
+    }
+}
+
+/// Creates glyph data of the given size, every pixel is taken from `get_pixel`.
+pub fn map_glyph_pixels(size: Size, get_pixel: impl Fn(i32, i32) -> bool) -> Vec<u8> {
+    let mut result = vec![0; bytes_per_row(size.width) * size.height.max(0) as usize];
+    for y in 0..size.height {
+        for x in 0..size.width {
+            if get_pixel(x, y) {
+                set_glyph_pixel(&mut result, size.width, x, y, true);
+            }
+        }
+    }
+    result
+}
+
+/// Converts glyph data to a new size, pixels outside of the new size are cut off.
+pub fn resize_glyph_data(data: &[u8], old_size: Size, new_size: Size) -> Vec<u8> {
+    map_glyph_pixels(new_size, |x, y| y < old_size.height && get_glyph_pixel(data, old_size.width, x, y))
+}
+
+/// Returns a copy of the font with the new glyph size and 256 or 512 glyphs.
+pub fn resize_bit_font(font: &BitFont, size: Size, large: bool) -> BitFont {
+    let mut result = font.clone();
+    for glyph in result.glyphs.values_mut() {
+        glyph.data = resize_glyph_data(&glyph.data, font.size, size);
+    }
+    result.size = size;
+
+    if large != (font.length as usize > 256) {
+        if large {
+            result.length = 512;
+            if let Some(template) = result.glyphs.values().next().cloned() {
+                for i in 256..512 {
+                    if let Some(ch) = char::from_u32(i) {
+                        let mut glyph = template.clone();
+                        glyph.data = vec![0; bytes_per_row(size.width) * size.height as usize];
+                        result.glyphs.entry(ch).or_insert(glyph);
+                    }
+                }
+            }
+        } else {
+            result.length = 256;
+            result.glyphs.retain(|ch, _| (*ch as u32) < 256);
+        }
+    }
+    result
+}
@@ -0,0 +1,158 @@
+//! Glyph import for the font editor. Glyphs are either rasterized from a TrueType/OpenType font
+//! or sliced out of an image containing a grid of glyphs. Slot `n` of the bit font gets the CP437
+//! character `n` from a TrueType font or the `n`th cell of the grid (left to right, top to bottom).
+
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use icy_engine::{BitFont, Size};
+use image::RgbaImage;
+
+use super::{
+    formats::get_unicode,
+    glyph_data::{bytes_per_row, resize_glyph_data, set_glyph_pixel},
+};
+
+pub enum GlyphSource {
+    TrueType(FontVec),
+    SpriteSheet(RgbaImage),
+}
+
+impl GlyphSource {
+    pub fn from_bytes(ext: &str, data: Vec<u8>) -> anyhow::Result<Self> {
+        if ext == "ttf" || ext == "otf" {
+            let font = FontVec::try_from_vec(data).map_err(|err| anyhow::anyhow!("{err}"))?;
+            Ok(GlyphSource::TrueType(font))
+        } else {
+            Ok(GlyphSource::SpriteSheet(image::load_from_memory(&data)?.to_rgba8()))
+        }
+    }
+}
+
+pub struct GlyphImporter {
+    pub source: GlyphSource,
+    pub size: Size,
+    /// Pixels with a coverage/luminance above the threshold are set.
+    pub threshold: u8,
+    pub invert: bool,
+
+    /// Font size in pixels for TrueType fonts.
+    pub font_size: f32,
+    /// Baseline position inside the cell for TrueType fonts.
+    pub baseline: i32,
+
+    /// Grid layout of sprite sheets.
+    pub columns: i32,
+    pub origin: (i32, i32),
+    pub cell_size: (i32, i32),
+    pub spacing: (i32, i32),
+
+    /// Per glyph pixel offset.
+    pub nudge: Vec<(i32, i32)>,
+}
+
+impl GlyphImporter {
+    pub fn new(source: GlyphSource, size: Size) -> Self {
+        let cell_size = match &source {
+            GlyphSource::SpriteSheet(img) => ((img.width() / 16) as i32, (img.height() / 16) as i32),
+            GlyphSource::TrueType(_) => (size.width, size.height),
+        };
+        let mut result = Self {
+            source,
+            size,
+            threshold: 128,
+            invert: false,
+            font_size: size.height as f32,
+            baseline: size.height * 3 / 4,
+            columns: 16,
+            origin: (0, 0),
+            cell_size,
+            spacing: (0, 0),
+            nudge: vec![(0, 0); 512],
+        };
+        result.fit_font_size();
+        result
+    }
+
+    pub fn is_true_type(&self) -> bool {
+        matches!(self.source, GlyphSource::TrueType(_))
+    }
+
+    /// Chooses the font size and baseline so that ascent and descent fill the cell height.
+    pub fn fit_font_size(&mut self) {
+        if let GlyphSource::TrueType(font) = &self.source {
+            self.font_size = self.size.height as f32;
+            self.baseline = font.as_scaled(PxScale::from(self.font_size)).ascent().round() as i32;
+        }
+    }
+
+    /// Returns the glyph rows of slot `i`, `None` if the source doesn't contain that glyph.
+    pub fn get_glyph(&self, i: usize) -> Option<Vec<u8>> {
+        let (nx, ny) = self.nudge.get(i).copied().unwrap_or_default();
+        let mut data = vec![0u8; bytes_per_row(self.size.width) * self.size.height as usize];
+        let mut set_pixel = |x: i32, y: i32| {
+            set_glyph_pixel(&mut data, self.size.width, x + nx, y + ny, true);
+        };
+
+        match &self.source {
+            GlyphSource::TrueType(font) => {
+                let ch = get_unicode(i);
+                let id = font.glyph_id(ch);
+                if id.0 == 0 && ch != ' ' {
+                    return None;
+                }
+                let scaled = font.as_scaled(PxScale::from(self.font_size));
+                let left = (self.size.width as f32 - scaled.h_advance(id)) / 2.0;
+                let glyph = id.with_scale_and_position(PxScale::from(self.font_size), ab_glyph::point(left, self.baseline as f32));
+                if let Some(outline) = font.outline_glyph(glyph) {
+                    let bounds = outline.px_bounds();
+                    let threshold = f32::from(self.threshold) / 255.0;
+                    outline.draw(|x, y, coverage| {
+                        if (coverage >= threshold) != self.invert {
+                            set_pixel(bounds.min.x as i32 + x as i32, bounds.min.y as i32 + y as i32);
+                        }
+                    });
+                }
+            }
+            GlyphSource::SpriteSheet(img) => {
+                let (col, row) = (i as i32 % self.columns.max(1), i as i32 / self.columns.max(1));
+                let left = self.origin.0 + col * (self.cell_size.0 + self.spacing.0);
+                let top = self.origin.1 + row * (self.cell_size.1 + self.spacing.1);
+                if left >= img.width() as i32 || top >= img.height() as i32 {
+                    return None;
+                }
+                for y in 0..self.size.height.min(self.cell_size.1) {
+                    for x in 0..self.size.width.min(self.cell_size.0) {
+                        let (px, py) = (left + x, top + y);
+                        if px < 0 || py < 0 || px >= img.width() as i32 || py >= img.height() as i32 {
+                            continue;
+                        }
+                        let p = img.get_pixel(px as u32, py as u32).0;
+                        let luminance = (u32::from(p[0]) * 299 + u32::from(p[1]) * 587 + u32::from(p[2]) * 114) / 1000;
+                        let is_set = p[3] >= 128 && luminance >= u32::from(self.threshold);
+                        if is_set != self.invert {
+                            set_pixel(x, y);
+                        }
+                    }
+                }
+            }
+        }
+        Some(data)
+    }
+
+    /// Creates a copy of the font with all glyphs the source provides replaced.
+    pub fn import_into(&self, font: &BitFont) -> BitFont {
+        let mut result = font.clone();
+        result.size = self.size;
+        for glyph in result.glyphs.values_mut() {
+            glyph.data = resize_glyph_data(&glyph.data, font.size, self.size);
+        }
+        for i in 0..font.length as usize {
+            let Some(ch) = char::from_u32(i as u32) else {
+                continue;
+            };
+            if let (Some(data), Some(glyph)) = (self.get_glyph(i), result.get_glyph_mut(ch)) {
+                glyph.data = data;
+            }
+        }
+        result
+    }
+}
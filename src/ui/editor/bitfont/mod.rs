@@ -1,11 +1,24 @@
+mod diff;
+mod export;
+mod formats;
+mod glyph_data;
+mod import;
+mod tools;
 mod undo;
+mod unicode_table;
+pub use diff::*;
+pub use export::*;
+pub use formats::*;
+pub use glyph_data::*;
+pub use import::*;
+pub use unicode_table::*;
 
-use std::{path::Path, sync::Arc};
+use std::{collections::HashSet, path::Path, sync::Arc};
 
 use eframe::{
     egui::{self, Id, Layout, RichText, Sense},
     emath::Align2,
-    epaint::{mutex::Mutex, Color32, FontFamily, FontId, Pos2, Rect, Rounding, Vec2},
+    epaint::{mutex::Mutex, Color32, FontFamily, FontId, Pos2, Rect, Rounding, Stroke, Vec2},
 };
 use i18n_embed_fl::fl;
 use icy_engine::{
@@ -16,7 +29,19 @@ use icy_engine_egui::{show_terminal_area, BufferView};
 
 use crate::{model::Tool, to_message, AnsiEditor, ClipboardHandler, Document, DocumentOptions, Message, TerminalResult, UndoHandler, SETTINGS};
 
-use self::undo::UndoOperation;
+use self::{
+    tools::{flood_fill, move_selection, GlyphPixels, GlyphSelection, GlyphTool, BITFONT_GLYPH_PIXELS},
+    undo::UndoOperation,
+};
+
+/// Pixels of the reference glyph shine through unset pixels of the edited glyph in this color.
+const REFERENCE_COLOR: Color32 = Color32::from_rgb(0x20, 0x40, 0x70);
+
+/// Frame of glyphs that differ from the compared font.
+const DIFF_COLOR: Color32 = Color32::from_rgb(0xE0, 0x70, 0x20);
+
+/// Frame of glyphs marked for copying or patching.
+const MARK_COLOR: Color32 = Color32::from_rgb(0x40, 0xC0, 0x40);
 
 pub struct BitFontEditor {
     id: usize,
@@ -26,6 +51,7 @@ pub struct BitFontEditor {
 
     width: i32,
     height: i32,
+    large_font: bool,
 
     buffer_view: Arc<Mutex<BufferView>>,
 
@@ -34,7 +60,27 @@ pub struct BitFontEditor {
     redo_stack: Vec<Box<dyn UndoOperation>>,
     old_data: Option<Vec<u8>>,
 
+    tool: GlyphTool,
+    drag_start: Option<(i32, i32)>,
+    drag_cur: Option<(i32, i32)>,
+    drag_value: bool,
+    preview_data: Option<Vec<u8>>,
+    selection: Option<GlyphSelection>,
+    moving_selection: bool,
+    reference_glyph: Option<char>,
+
+    unicode_table: UnicodeTable,
+    /// Table written by the last `get_bytes`, registered for the font once the save succeeded.
+    saved_unicode_table: Option<UnicodeTable>,
+    unicode_text: String,
+    unicode_text_glyph: Option<char>,
+    unicode_error: Option<String>,
+
+    compare_font: Option<BitFont>,
+    marked_glyphs: HashSet<char>,
+
     send_update_message: bool,
+    pub save_options: BitFontSaveOptions,
 }
 
 pub enum DrawGlyphStyle {
@@ -51,6 +97,8 @@ impl BitFontEditor {
         buffer_view.interactive = false;
         let buffer_view = Arc::new(Mutex::new(buffer_view));
         let size = font.size;
+        let large_font = font.length as usize > 256;
+        let unicode_table = UnicodeTable::from_cp437(font.length as usize);
         let last_updated_font = font.clone();
         Self {
             id,
@@ -60,14 +108,137 @@ impl BitFontEditor {
             font,
             width: size.width,
             height: size.height,
+            large_font,
             selected_char_opt: Some('A'),
             undo_stack: Arc::new(Mutex::new(Vec::new())),
             redo_stack: Vec::new(),
             old_data: None,
+            tool: GlyphTool::Pencil,
+            drag_start: None,
+            drag_cur: None,
+            drag_value: true,
+            preview_data: None,
+            selection: None,
+            moving_selection: false,
+            reference_glyph: None,
+            unicode_table,
+            saved_unicode_table: None,
+            unicode_text: String::new(),
+            unicode_text_glyph: None,
+            unicode_error: None,
+            compare_font: None,
+            marked_glyphs: HashSet::new(),
             send_update_message: false,
+            save_options: BitFontSaveOptions::default(),
+        }
+    }
+
+    pub fn get_font(&self) -> &BitFont {
+        &self.font
+    }
+
+    /// Replaces the unicode table without undo. Documents only pick the table up once the font is saved.
+    pub fn load_unicode_table(&mut self, table: UnicodeTable) {
+        self.unicode_table = table;
+        self.unicode_text_glyph = None;
+    }
+
+    fn get_unicode_table(&self) -> UnicodeTable {
+        let mut table = self.unicode_table.clone();
+        table.resize(self.font.length as usize);
+        table
+    }
+
+    fn apply_unicode_text(&mut self) -> EngineResult<()> {
+        let Some(ch) = self.selected_char_opt else {
+            return Ok(());
+        };
+        match parse_code_points(&self.unicode_text) {
+            Ok(chars) => {
+                self.unicode_error = None;
+                if self.unicode_table.get(ch as usize) != chars.as_slice() {
+                    let mut new_table = self.get_unicode_table();
+                    new_table.set(ch as usize, chars);
+                    let op = undo::SetUnicodeTable::new(self.unicode_table.clone(), new_table);
+                    self.push_undo(Box::new(op))?;
+                }
+            }
+            Err(part) => self.unicode_error = Some(part),
+        }
+        Ok(())
+    }
+
+    fn reset_unicode_table(&mut self) -> EngineResult<()> {
+        let new_table = UnicodeTable::from_cp437(self.font.length as usize);
+        if new_table != self.unicode_table {
+            let op = undo::SetUnicodeTable::new(self.unicode_table.clone(), new_table);
+            self.push_undo(Box::new(op))?;
+        }
+        Ok(())
+    }
+
+    pub fn set_compare_font(&mut self, font: Option<BitFont>) {
+        self.compare_font = font;
+        self.marked_glyphs.clear();
+    }
+
+    fn get_differences(&self) -> HashSet<char> {
+        match &self.compare_font {
+            Some(other) => diff_glyphs(&self.font, other).into_iter().collect(),
+            None => HashSet::new(),
         }
     }
 
+    fn toggle_mark(&mut self, ch: char) {
+        if !self.marked_glyphs.remove(&ch) {
+            self.marked_glyphs.insert(ch);
+        }
+    }
+
+    fn get_marked_glyphs(&self) -> Vec<char> {
+        let mut glyphs: Vec<char> = self.marked_glyphs.iter().copied().collect();
+        glyphs.sort_unstable();
+        glyphs
+    }
+
+    /// The marked glyphs of the edited font.
+    pub fn get_marked_patch(&self) -> GlyphPatch {
+        GlyphPatch::from_font(&self.font, &self.get_marked_glyphs())
+    }
+
+    pub fn apply_patch(&mut self, patch: &GlyphPatch) -> EngineResult<()> {
+        let op = undo::PatchGlyphs::new(fl!(crate::LANGUAGE_LOADER, "undo-bitfont-apply-patch"), patch.get_glyphs(self.font.size));
+        self.push_undo(Box::new(op))
+    }
+
+    /// Copies the marked glyphs from the compared font in one undo step.
+    fn copy_marked_glyphs(&mut self) -> EngineResult<()> {
+        let Some(other) = &self.compare_font else {
+            return Ok(());
+        };
+        let patch = GlyphPatch::from_font(other, &self.get_marked_glyphs());
+        let op = undo::PatchGlyphs::new(fl!(crate::LANGUAGE_LOADER, "undo-bitfont-copy-glyphs"), patch.get_glyphs(self.font.size));
+        self.push_undo(Box::new(op))
+    }
+
+    pub fn get_font_height(&self) -> i32 {
+        self.font.size.height
+    }
+
+    pub fn import_glyphs(&mut self, importer: &GlyphImporter) -> EngineResult<()> {
+        let new_font = importer.import_into(&self.font);
+        let op = undo::ReplaceFont::new(fl!(crate::LANGUAGE_LOADER, "undo-bitfont-import"), self.font.clone(), new_font);
+        self.push_undo(Box::new(op))
+    }
+
+    /// Sets the font and resets the size settings to the size of the font.
+    fn set_font(&mut self, font: BitFont) {
+        self.width = font.size.width;
+        self.height = font.size.height;
+        self.large_font = font.length as usize > 256;
+        self.font = font;
+    }
+
     pub fn draw_glyph(ui: &mut egui::Ui, font: &BitFont, style: DrawGlyphStyle, ch: char) -> egui::Response {
         let scale = 3.;
         let (id, stroke_rect) = ui.allocate_space(Vec2::new(scale * font.size.width as f32, scale * font.size.height as f32));
@@ -92,7 +263,7 @@ impl BitFontEditor {
         if let Some(glyph) = font.get_glyph(ch) {
             for y in 0..s.height {
                 for x in 0..s.width {
-                    if glyph.data[y as usize] & (128 >> x) != 0 {
+                    if get_glyph_pixel(&glyph.data, s.width, x, y) {
                         painter.rect_filled(
                             Rect::from_min_size(
                                 Pos2::new(stroke_rect.left() + x as f32 * scale, stroke_rect.top() + y as f32 * scale),
@@ -108,14 +279,48 @@ impl BitFontEditor {
         response
     }
 
+    /// Tiles the selected glyph like the tile area buffer, used for glyphs the engine can't render.
+    fn show_wide_tile_area(&self, ui: &mut egui::Ui) {
+        let scale = 2.;
+        let s = self.font.size;
+        let tiles = self.buffer_view.lock().get_buffer().get_size();
+        let (_, stroke_rect) = ui.allocate_space(Vec2::new(scale * (s.width * tiles.width) as f32, scale * (s.height * tiles.height) as f32));
+        let painter = ui.painter_at(stroke_rect);
+        painter.rect_filled(stroke_rect, Rounding::ZERO, Color32::BLACK);
+        let Some(glyph) = self.font.get_glyph(self.selected_char_opt.unwrap_or(' ')) else {
+            return;
+        };
+        let col = Color32::from_rgb(0xAA, 0xAA, 0xAA);
+        for y in 0..s.height {
+            for x in 0..s.width {
+                if !get_glyph_pixel(&glyph.data, s.width, x, y) {
+                    continue;
+                }
+                for ty in 0..tiles.height {
+                    for tx in 0..tiles.width {
+                        let pos = stroke_rect.min + Vec2::new((tx * s.width + x) as f32 * scale, (ty * s.height + y) as f32 * scale);
+                        painter.rect_filled(Rect::from_min_size(pos, Vec2::new(scale, scale)), Rounding::ZERO, col);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn update_tile_area(&mut self) {
         let lock = &mut self.buffer_view.lock();
         let buf = lock.get_buffer_mut();
         buf.set_font(0, self.font.clone());
 
+        // keep the tile area about the same size for wide glyphs
+        let tiles = Size::new(if is_wide_font(&self.font) { 5 } else { 10 }, 10);
+        if buf.get_size() != tiles {
+            buf.set_size(tiles);
+            buf.layers[0].set_size(tiles);
+        }
+
         let ch = self.selected_char_opt.unwrap_or(' ');
-        for y in 0..buf.get_width() {
-            for x in 0..buf.get_height() {
+        for y in 0..buf.get_height() {
+            for x in 0..buf.get_width() {
                 buf.layers[0].set_char(
                     (x, y),
                     icy_engine::AttributedChar {
@@ -131,7 +336,7 @@ impl BitFontEditor {
 
     pub fn edit_glyph(&mut self) -> impl egui::Widget + '_ {
         move |ui: &mut egui::Ui| {
-            let scale = 20.;
+            let scale = if is_wide_font(&self.font) { 16. } else { 20. };
             let border = 2.;
 
             let left_ruler = 20.0;
@@ -147,68 +352,96 @@ impl BitFontEditor {
             painter.rect_filled(stroke_rect, Rounding::ZERO, Color32::DARK_GRAY);
 
             let s = self.font.size;
+            let get_pixel_pos = |pos: Pos2| {
+                (
+                    ((pos.x - (2. + left_ruler + stroke_rect.left())) / (border + scale)).floor() as i32,
+                    ((pos.y - (2. + top_ruler + stroke_rect.top())) / (border + scale)).floor() as i32,
+                )
+            };
+
+            match self.tool {
+                GlyphTool::Pencil => {
+                    if response.drag_started_by(egui::PointerButton::Primary) || response.drag_started_by(egui::PointerButton::Secondary) {
+                        self.start_edit();
+                    }
 
-            /*   if response.clicked() {
-                if let Some(pos) = response.hover_pos() {
-                    if let Some(number) = self.selected_char_opt {
-                        if let Some(glyph) = self.font.get_glyph_mut(number) {
-                            println!("click!");
-                            let y = ((pos.y - stroke_rect.top()) / (scale + border)) as usize;
-                            let x = ((pos.x - stroke_rect.left()) / (scale + border)) as usize;
-                            if glyph.data[y] & (128 >> x) != 0 {
-                                println!("unset!");
-                                glyph.data[y] &= !(128 >> x);
-                            } else {
-                                println!("set!");
-                                glyph.data[y] |= 128 >> x;
+                    if response.drag_released_by(egui::PointerButton::Primary) || response.drag_released_by(egui::PointerButton::Secondary) {
+                        self.end_edit();
+                    }
+
+                    let value = if response.dragged_by(egui::PointerButton::Primary) {
+                        Some(true)
+                    } else if response.dragged_by(egui::PointerButton::Secondary) {
+                        Some(false)
+                    } else {
+                        None
+                    };
+                    if let (Some(value), Some(pos)) = (value, response.hover_pos()) {
+                        if let Some(number) = self.selected_char_opt {
+                            if let Some(glyph) = self.font.get_glyph_mut(number) {
+                                let (x, y) = get_pixel_pos(pos);
+                                if x >= 0 && y >= 0 && y < s.height && x < s.width {
+                                    set_glyph_pixel(&mut glyph.data, s.width, x, y, value);
+                                    self.update_tile_area();
+                                    response.mark_changed();
+                                }
                             }
-                            self.is_dirty = true;
-                            response.mark_changed();
                         }
                     }
                 }
-            } else { */
-            if response.drag_started_by(egui::PointerButton::Primary) || response.drag_started_by(egui::PointerButton::Secondary) {
-                self.start_edit();
-            }
-
-            if response.drag_released_by(egui::PointerButton::Primary) || response.drag_released_by(egui::PointerButton::Secondary) {
-                self.end_edit();
-            }
-
-            if response.dragged_by(egui::PointerButton::Primary) {
-                if let Some(pos) = response.hover_pos() {
-                    if let Some(number) = self.selected_char_opt {
-                        if let Some(glyph) = self.font.get_glyph_mut(number) {
-                            let y = ((pos.y - left_ruler - stroke_rect.top()) / (scale + border)) as usize;
-                            let x = ((pos.x - top_ruler - stroke_rect.left()) / (scale + border)) as usize;
-                            if y < glyph.data.len() && x < 8 {
-                                glyph.data[y] |= 128 >> x;
-                                self.update_tile_area();
-                                response.mark_changed();
+                GlyphTool::Fill => {
+                    if response.clicked() || response.secondary_clicked() {
+                        if let Some(pos) = response.interact_pointer_pos() {
+                            let value = response.clicked();
+                            if let Err(err) = self.fill_selected_glyph(get_pixel_pos(pos), value) {
+                                log::error!("error while filling glyph: {err}");
                             }
+                            response.mark_changed();
                         }
                     }
                 }
-            }
+                _ => {
+                    if response.drag_started_by(egui::PointerButton::Primary) || response.drag_started_by(egui::PointerButton::Secondary) {
+                        let origin = ui.input(|i| i.pointer.press_origin()).or(response.interact_pointer_pos());
+                        if let Some(pos) = origin {
+                            let pos = get_pixel_pos(pos);
+                            self.start_edit();
+                            self.drag_start = Some(pos);
+                            self.drag_cur = Some(pos);
+                            self.drag_value = response.drag_started_by(egui::PointerButton::Primary);
+                            self.moving_selection = self.tool == GlyphTool::Select && self.selection.map_or(false, |sel| sel.contains(pos));
+                        }
+                    }
 
-            if response.dragged_by(egui::PointerButton::Secondary) {
-                if let Some(pos) = response.hover_pos() {
-                    if let Some(number) = self.selected_char_opt {
-                        if let Some(glyph) = self.font.get_glyph_mut(number) {
-                            let y = ((pos.y - left_ruler - stroke_rect.top()) / (scale + border)) as usize;
-                            let x = ((pos.x - top_ruler - stroke_rect.left()) / (scale + border)) as usize;
-                            if y < glyph.data.len() && x < 8 {
-                                glyph.data[y] &= !(128 >> x);
-                                self.update_tile_area();
-                                response.mark_changed();
-                            }
+                    if response.dragged() && self.drag_start.is_some() {
+                        if let Some(pos) = response.interact_pointer_pos() {
+                            self.drag_cur = Some(get_pixel_pos(pos));
+                            self.update_drag_preview();
                         }
                     }
+
+                    if response.drag_released() {
+                        if let Err(err) = self.end_tool_drag() {
+                            log::error!("error while drawing glyph: {err}");
+                        }
+                        response.mark_changed();
+                    } else if response.clicked() && self.tool == GlyphTool::Select {
+                        self.selection = None;
+                    }
                 }
             }
+
             if let Some(number) = self.selected_char_opt {
-                if let Some(glyph) = self.font.get_glyph_mut(number) {
+                let data = match &self.preview_data {
+                    Some(data) => Some(data.clone()),
+                    None => self.font.get_glyph(number).map(|glyph| glyph.data.clone()),
+                };
+                let reference_data = self
+                    .reference_glyph
+                    .filter(|ch| *ch != number)
+                    .and_then(|ch| self.font.get_glyph(ch))
+                    .map(|glyph| glyph.data.clone());
+                if let Some(data) = data {
                     painter.rect_filled(
                         Rect::from_min_size(
                             Pos2::new(stroke_rect.left(), stroke_rect.top()),
@@ -277,34 +510,122 @@ impl BitFontEditor {
                                 ),
                                 Vec2::new(scale, scale),
                             );
-                            let col = if glyph.data[y as usize] & (128 >> x) != 0 {
-                                if let Some(pos) = response.hover_pos() {
-                                    if rect.contains(pos) {
-                                        Color32::WHITE
-                                    } else {
-                                        Color32::GRAY
-                                    }
+                            let is_hovered = response.hover_pos().map_or(false, |pos| rect.contains(pos));
+                            let col = if get_glyph_pixel(&data, s.width, x, y) {
+                                if is_hovered {
+                                    Color32::WHITE
                                 } else {
                                     Color32::GRAY
                                 }
-                            } else if let Some(pos) = response.hover_pos() {
-                                if rect.contains(pos) {
-                                    Color32::DARK_GRAY
-                                } else {
-                                    Color32::BLACK
-                                }
+                            } else if is_hovered {
+                                Color32::DARK_GRAY
+                            } else if reference_data.as_ref().map_or(false, |reference| get_glyph_pixel(reference, s.width, x, y)) {
+                                REFERENCE_COLOR
                             } else {
                                 Color32::BLACK
                             };
                             painter.rect_filled(rect, Rounding::ZERO, col);
                         }
                     }
+
+                    if let Some(selection) = self.get_displayed_selection() {
+                        let rect = Rect::from_min_size(
+                            Pos2::new(
+                                1. + left_ruler + stroke_rect.left() + selection.x as f32 * (border + scale),
+                                1. + top_ruler + stroke_rect.top() + selection.y as f32 * (border + scale),
+                            ),
+                            Vec2::new(selection.width as f32 * (border + scale), selection.height as f32 * (border + scale)),
+                        );
+                        painter.rect_stroke(rect, Rounding::ZERO, Stroke::new(2.0, Color32::YELLOW));
+                    }
                 }
             }
             response
         }
     }
 
+    /// The selection as it's shown while the user drags it around.
+    fn get_displayed_selection(&self) -> Option<GlyphSelection> {
+        let mut selection = self.selection?;
+        if self.moving_selection {
+            if let (Some(start), Some(cur)) = (self.drag_start, self.drag_cur) {
+                selection.x += cur.0 - start.0;
+                selection.y += cur.1 - start.1;
+            }
+        }
+        Some(selection)
+    }
+
+    fn clamp_pixel_pos(&self, pos: (i32, i32)) -> (i32, i32) {
+        (pos.0.clamp(0, self.font.size.width - 1), pos.1.clamp(0, self.font.size.height - 1))
+    }
+
+    /// Applies the current tool to the glyph data the drag started with.
+    fn get_tool_result(&self) -> Option<Vec<u8>> {
+        let (Some(start), Some(cur), Some(old_data)) = (self.drag_start, self.drag_cur, &self.old_data) else {
+            return None;
+        };
+        let width = self.font.size.width;
+        match self.tool {
+            GlyphTool::Select => {
+                if !self.moving_selection {
+                    return None;
+                }
+                let selection = self.selection?;
+                Some(move_selection(old_data, width, selection, (cur.0 - start.0, cur.1 - start.1)))
+            }
+            tool => {
+                let mut data = old_data.clone();
+                for (x, y) in tool.get_shape(start, cur) {
+                    set_glyph_pixel(&mut data, width, x, y, self.drag_value);
+                }
+                Some(data)
+            }
+        }
+    }
+
+    fn update_drag_preview(&mut self) {
+        if self.tool == GlyphTool::Select && !self.moving_selection {
+            if let (Some(start), Some(cur)) = (self.drag_start, self.drag_cur) {
+                self.selection = Some(GlyphSelection::from_points(self.clamp_pixel_pos(start), self.clamp_pixel_pos(cur)));
+            }
+        }
+        self.preview_data = self.get_tool_result();
+    }
+
+    fn end_tool_drag(&mut self) -> EngineResult<()> {
+        let data = self.get_tool_result();
+        if self.moving_selection {
+            self.selection = self.get_displayed_selection();
+        }
+        self.drag_start = None;
+        self.drag_cur = None;
+        self.moving_selection = false;
+        self.preview_data = None;
+        let old_data = self.old_data.take();
+        if let (Some(ch), Some(data), Some(old_data)) = (self.selected_char_opt, data, old_data) {
+            if data != old_data {
+                let op = undo::DrawGlyph::new(ch, self.tool.get_undo_description(), data, old_data);
+                self.push_undo(Box::new(op))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_selected_glyph(&mut self, pos: (i32, i32), value: bool) -> EngineResult<()> {
+        if let Some(number) = self.selected_char_opt {
+            if let Some(glyph) = self.font.get_glyph(number) {
+                let mut data = glyph.data.clone();
+                flood_fill(&mut data, self.font.size.width, pos, value);
+                if data != glyph.data {
+                    let op = undo::DrawGlyph::new(number, GlyphTool::Fill.get_undo_description(), data, glyph.data.clone());
+                    self.push_undo(Box::new(op))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn push_undo(&mut self, mut op: Box<dyn UndoOperation>) -> EngineResult<()> {
         op.redo(self)?;
         self.undo_stack.lock().push(op);
@@ -379,18 +700,25 @@ impl BitFontEditor {
 
     fn resize_font(&mut self) -> EngineResult<()> {
         let old_font = self.font.clone();
-        let mut new_font = self.font.clone();
+        let new_font = resize_bit_font(&self.font, Size::new(self.width, self.height), self.large_font);
 
-        for glyph in new_font.glyphs.values_mut() {
-            glyph.data.resize(self.height as usize, 0);
-        }
-        new_font.size = Size::new(self.width, self.height);
-
-        let op = undo::ResizeFont::new(old_font, new_font);
+        let op = undo::ReplaceFont::new(fl!(crate::LANGUAGE_LOADER, "undo-bitfont-resize"), old_font, new_font);
         self.push_undo(Box::new(op))?;
+        self.selection = None;
+        if self.selected_char_opt.map_or(false, |ch| self.font.get_glyph(ch).is_none()) {
+            self.selected_char_opt = Some('A');
+            self.update_tile_area();
+        }
+        if self.reference_glyph.map_or(false, |ch| self.font.get_glyph(ch).is_none()) {
+            self.reference_glyph = None;
+        }
         Ok(())
     }
 
+    fn is_resized(&self) -> bool {
+        self.width != self.font.size.width || self.height != self.font.size.height || self.large_font != (self.font.length as usize > 256)
+    }
+
     fn start_edit(&mut self) {
         if let Some(number) = self.selected_char_opt {
             if let Some(glyph) = self.font.get_glyph_mut(number) {
@@ -424,6 +752,21 @@ impl ClipboardHandler for BitFontEditor {
 
     fn copy(&mut self) -> EngineResult<()> {
         if let Some(ch) = self.selected_char_opt {
+            // the engine glyph clipboard data has one byte per row, wide glyphs are copied as a whole region
+            let selection = self.selection.or_else(|| {
+                is_wide_font(&self.font).then_some(GlyphSelection {
+                    x: 0,
+                    y: 0,
+                    width: self.font.size.width,
+                    height: self.font.size.height,
+                })
+            });
+            if let Some(selection) = selection {
+                if let Some(glyph) = self.font.get_glyph(ch) {
+                    push_data(BITFONT_GLYPH_PIXELS, &selection.copy(&glyph.data, self.font.size.width).to_clipboard_data())?;
+                }
+                return Ok(());
+            }
             if let Some(data) = self.font.get_clipboard_data(ch) {
                 push_data(BITFONT_GLYPH, &data)?;
             }
@@ -436,10 +779,32 @@ impl ClipboardHandler for BitFontEditor {
             return false;
         }
 
-        pop_data(BITFONT_GLYPH).is_some()
+        pop_data(BITFONT_GLYPH_PIXELS).is_some() || pop_data(BITFONT_GLYPH).is_some()
     }
 
     fn paste(&mut self) -> EngineResult<()> {
+        // the clipboard holds either a region or a whole glyph, whatever was copied last
+        if let Some(pixels) = pop_data(BITFONT_GLYPH_PIXELS).and_then(|data| GlyphPixels::from_clipboard_data(&data)) {
+            let Some(ch) = self.selected_char_opt else {
+                return Ok(());
+            };
+            let Some(glyph) = self.font.get_glyph(ch) else {
+                return Ok(());
+            };
+            let pos = self.selection.map_or((0, 0), |sel| (sel.x, sel.y));
+            let mut data = glyph.data.clone();
+            pixels.paste(&mut data, self.font.size.width, pos);
+            let selection = GlyphSelection {
+                x: pos.0,
+                y: pos.1,
+                width: pixels.width,
+                height: pixels.height,
+            };
+            let op = undo::DrawGlyph::new(ch, fl!(crate::LANGUAGE_LOADER, "undo-paste-glyph"), data, glyph.data.clone());
+            self.push_undo(Box::new(op))?;
+            self.selection = Some(selection);
+            return Ok(());
+        }
         if let Some(data) = pop_data(BITFONT_GLYPH) {
             let (_, g) = Glyph::from_clipbard_data(&data);
             if let Some(ch) = self.selected_char_opt {
@@ -503,6 +868,15 @@ impl Document for BitFontEditor {
 
     fn show_ui(&mut self, ui: &mut eframe::egui::Ui, _cur_tool: &mut Box<dyn Tool>, _selected_tool: usize, _options: &DocumentOptions) -> Option<Message> {
         let mut message = None;
+        if self.unicode_text_glyph != self.selected_char_opt {
+            self.unicode_text_glyph = self.selected_char_opt;
+            self.unicode_text = self
+                .selected_char_opt
+                .map(|ch| format_code_points(self.unicode_table.get(ch as usize)))
+                .unwrap_or_default();
+            self.unicode_error = None;
+        }
+        let differences = self.get_differences();
         ui.add_space(16.);
         ui.vertical_centered(|ui| {
             ui.horizontal(|ui| {
@@ -511,6 +885,27 @@ impl Document for BitFontEditor {
 
                 ui.vertical(|ui| {
                     ui.add_space(20.);
+                    ui.horizontal(|ui| {
+                        for tool in GlyphTool::ALL {
+                            ui.selectable_value(&mut self.tool, tool, tool.get_icon()).on_hover_text(tool.get_label());
+                        }
+                    });
+                    if self.selection.is_some() && ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-deselect")).clicked() {
+                        self.selection = None;
+                    }
+                    if let Some(reference) = self.reference_glyph {
+                        ui.horizontal(|ui| {
+                            ui.label(fl!(
+                                crate::LANGUAGE_LOADER,
+                                "font-editor-reference",
+                                glyph = format!("{0}/0x{0:02X}", reference as u32)
+                            ));
+                            if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-clear_reference")).clicked() {
+                                self.reference_glyph = None;
+                            }
+                        });
+                    }
+                    ui.add_space(8.);
                     ui.horizontal(|ui| {
                         if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-clear")).clicked() {
                             message = to_message(self.clear_selected_glyph());
@@ -561,7 +956,7 @@ impl Document for BitFontEditor {
                         ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
                             ui.label(fl!(crate::LANGUAGE_LOADER, "new-file-width"));
                         });
-                        ui.add(egui::Slider::new(&mut self.width, 2..=8));
+                        ui.add(egui::Slider::new(&mut self.width, 2..=16));
                         ui.end_row();
 
                         ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
@@ -569,15 +964,104 @@ impl Document for BitFontEditor {
                         });
                         ui.add(egui::Slider::new(&mut self.height, 2..=19));
                         ui.end_row();
+
+                        ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(fl!(crate::LANGUAGE_LOADER, "font-editor-glyph_count"));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut self.large_font, false, "256");
+                            ui.radio_value(&mut self.large_font, true, "512");
+                        });
+                        ui.end_row();
                     });
 
-                    if (self.width != self.font.size.width || self.height != self.font.size.height) && ui.button("Resize").clicked() {
+                    if self.is_resized() && ui.button("Resize").clicked() {
                         message = to_message(self.resize_font());
                     }
+                    ui.add_space(8.);
+                    ui.horizontal(|ui| {
+                        if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-import")).clicked() {
+                            message = Some(Message::ShowImportGlyphsDialog);
+                        }
+                        if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-export_image")).clicked() {
+                            message = Some(Message::ShowExportFontDialog);
+                        }
+                    });
+
+                    ui.add_space(8.);
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "font-editor-unicode"));
+                    ui.horizontal(|ui| {
+                        let response = ui.add(egui::TextEdit::singleline(&mut self.unicode_text).desired_width(160.));
+                        let set_clicked = ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-unicode-set")).clicked();
+                        if set_clicked || response.lost_focus() {
+                            message = to_message(self.apply_unicode_text());
+                        }
+                    })
+                    .response
+                    .on_hover_text(fl!(crate::LANGUAGE_LOADER, "font-editor-unicode-tooltip"));
+                    if let Some(part) = &self.unicode_error {
+                        ui.colored_label(Color32::RED, fl!(crate::LANGUAGE_LOADER, "font-editor-unicode-error", code = part.clone()));
+                    }
+                    if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-unicode-reset")).clicked() {
+                        message = to_message(self.reset_unicode_table());
+                    }
+
+                    ui.add_space(8.);
+                    ui.label(fl!(crate::LANGUAGE_LOADER, "font-editor-compare"))
+                        .on_hover_text(fl!(crate::LANGUAGE_LOADER, "font-editor-compare-tooltip"));
+                    let has_marks = !self.marked_glyphs.is_empty();
+                    if let Some(other) = &self.compare_font {
+                        ui.label(fl!(
+                            crate::LANGUAGE_LOADER,
+                            "font-editor-compare-font",
+                            name = other.name.clone(),
+                            count = differences.len()
+                        ));
+                        ui.horizontal(|ui| {
+                            if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-compare-mark")).clicked() {
+                                self.marked_glyphs = differences.clone();
+                            }
+                            if ui
+                                .add_enabled(has_marks, egui::Button::new(fl!(crate::LANGUAGE_LOADER, "font-editor-compare-copy")))
+                                .clicked()
+                            {
+                                message = to_message(self.copy_marked_glyphs());
+                            }
+                            if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-compare-stop")).clicked() {
+                                self.set_compare_font(None);
+                            }
+                        });
+                    } else if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-compare-with")).clicked() {
+                        message = Some(Message::ShowCompareFontDialog);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(has_marks, egui::Button::new(fl!(crate::LANGUAGE_LOADER, "font-editor-patch-save")))
+                            .clicked()
+                        {
+                            message = Some(Message::ShowGlyphPatchDialog(crate::GlyphPatchMode::Save));
+                        }
+                        if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-patch-apply")).clicked() {
+                            message = Some(Message::ShowGlyphPatchDialog(crate::GlyphPatchMode::Apply));
+                        }
+                        if ui
+                            .add_enabled(has_marks, egui::Button::new(fl!(crate::LANGUAGE_LOADER, "font-editor-patch-files")))
+                            .clicked()
+                        {
+                            message = Some(Message::ShowGlyphPatchDialog(crate::GlyphPatchMode::PatchDirectory));
+                        }
+                    });
+                    if has_marks && ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-compare-clear_marks")).clicked() {
+                        self.marked_glyphs.clear();
+                    }
                 });
 
                 ui.vertical(|ui| {
                     ui.heading(fl!(crate::LANGUAGE_LOADER, "font-editor-tile_area"));
+                    if is_wide_font(&self.font) {
+                        self.show_wide_tile_area(ui);
+                        return;
+                    }
                     let mut scale = unsafe { SETTINGS.get_scale() };
                     if self.buffer_view.lock().get_buffer().use_aspect_ratio() {
                         scale.y *= 1.35;
@@ -598,37 +1082,87 @@ impl Document for BitFontEditor {
 
         ui.label(fl!(crate::LANGUAGE_LOADER, "font-editor-table", length = (self.font.length - 1).to_string()));
         egui::ScrollArea::vertical().show(ui, |ui| {
-            ui.horizontal_wrapped(|ui| {
-                for i in 0..self.font.length {
-                    let ch = unsafe { char::from_u32_unchecked(i as u32) };
-                    let mut style = DrawGlyphStyle::Normal;
-                    if let Some(ch2) = self.selected_char_opt {
-                        if ch == ch2 {
-                            style = DrawGlyphStyle::Selected
+            let length = self.font.length as usize;
+            // 512 glyph fonts are shown as two pages of 256 glyphs
+            let pages = (length + 255) / 256;
+            for page in 0..pages {
+                if pages > 1 {
+                    let (start, end) = (page * 256, length.min((page + 1) * 256) - 1);
+                    ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "font-editor-table-page", start = start, end = end)).small());
+                }
+                ui.horizontal_wrapped(|ui| {
+                    for i in page * 256..length.min((page + 1) * 256) {
+                        let ch = unsafe { char::from_u32_unchecked(i as u32) };
+                        let mut style = DrawGlyphStyle::Normal;
+                        if let Some(ch2) = self.selected_char_opt {
+                            if ch == ch2 {
+                                style = DrawGlyphStyle::Selected
+                            }
                         }
-                    }
-                    let response = BitFontEditor::draw_glyph(ui, &self.font, style, ch);
-                    if response.clicked() {
-                        self.selected_char_opt = Some(ch);
-                        self.update_tile_area();
-                    }
-
-                    response.on_hover_ui(|ui| {
-                        ui.horizontal(|ui| {
-                            ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "font-view-char_label")).small());
-                            ui.label(RichText::new(format!("{0}/0x{0:02X}", i)).small().color(Color32::WHITE));
+                        let response = BitFontEditor::draw_glyph(ui, &self.font, style, ch);
+                        if self.marked_glyphs.contains(&ch) {
+                            ui.painter().rect_stroke(response.rect.expand(1.), Rounding::ZERO, Stroke::new(2., MARK_COLOR));
+                        } else if differences.contains(&ch) {
+                            ui.painter().rect_stroke(response.rect.expand(1.), Rounding::ZERO, Stroke::new(1., DIFF_COLOR));
+                        }
+                        if response.clicked() {
+                            if ui.input(|i| i.modifiers.command) {
+                                self.toggle_mark(ch);
+                            } else {
+                                self.selected_char_opt = Some(ch);
+                                self.update_tile_area();
+                            }
+                        }
+                        response.context_menu(|ui| {
+                            if ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-use_as_reference")).clicked() {
+                                self.reference_glyph = Some(ch);
+                                ui.close_menu();
+                            }
+                            if self.reference_glyph.is_some() && ui.button(fl!(crate::LANGUAGE_LOADER, "font-editor-clear_reference")).clicked() {
+                                self.reference_glyph = None;
+                                ui.close_menu();
+                            }
+                            let label = if self.marked_glyphs.contains(&ch) {
+                                fl!(crate::LANGUAGE_LOADER, "font-editor-unmark_glyph")
+                            } else {
+                                fl!(crate::LANGUAGE_LOADER, "font-editor-mark_glyph")
+                            };
+                            if ui.button(label).clicked() {
+                                self.toggle_mark(ch);
+                                ui.close_menu();
+                            }
                         });
-                        ui.horizontal(|ui| {
-                            ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "font-view-ascii_label")).small());
-                            ui.label(
-                                RichText::new(format!("'{0}'", unsafe { char::from_u32_unchecked(i as u32) }))
-                                    .small()
-                                    .color(Color32::WHITE),
-                            );
+
+                        response.on_hover_ui(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "font-view-char_label")).small());
+                                ui.label(RichText::new(format!("{0}/0x{0:02X}", i)).small().color(Color32::WHITE));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "font-view-ascii_label")).small());
+                                ui.label(
+                                    RichText::new(format!("'{0}'", unsafe { char::from_u32_unchecked(i as u32) }))
+                                        .small()
+                                        .color(Color32::WHITE),
+                                );
+                            });
+                            let chars = self.unicode_table.get(i);
+                            if !chars.is_empty() {
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "font-editor-unicode_label")).small());
+                                    ui.label(RichText::new(format_code_points(chars)).small().color(Color32::WHITE));
+                                });
+                            }
+                            if let Some(other) = &self.compare_font {
+                                if differences.contains(&ch) {
+                                    ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "font-editor-compare-differs", name = other.name.clone())).small());
+                                    BitFontEditor::draw_glyph(ui, other, DrawGlyphStyle::Normal, ch);
+                                }
+                            }
                         });
-                    });
-                }
-            })
+                    }
+                });
+            }
         });
 
         if message.is_none() && self.send_update_message {
@@ -640,8 +1174,17 @@ impl Document for BitFontEditor {
         message
     }
 
-    fn get_bytes(&mut self, _path: &Path) -> TerminalResult<Vec<u8>> {
-        self.font.to_psf2_bytes()
+    fn supports_extension(&self, ext: &str) -> bool {
+        BitFontFormat::from_extension(ext, &self.save_options).is_some()
+    }
+
+    fn get_bytes(&mut self, path: &Path) -> TerminalResult<Vec<u8>> {
+        let ext = path.extension().unwrap_or_default().to_str().unwrap_or_default();
+        let format = BitFontFormat::from_extension(ext, &self.save_options).unwrap_or(BitFontFormat::Psf2);
+        let table = self.get_unicode_table();
+        let bytes = format.to_bytes(&self.font, &table, &self.save_options)?;
+        self.saved_unicode_table = (matches!(format, BitFontFormat::Psf1 | BitFontFormat::Psf2) && self.save_options.unicode_table).then_some(table);
+        Ok(bytes)
     }
 
     fn get_ansi_editor_mut(&mut self) -> Option<&mut AnsiEditor> {
@@ -652,8 +1195,15 @@ impl Document for BitFontEditor {
         None
     }
 
+    fn get_bitfont_editor_mut(&mut self) -> Option<&mut BitFontEditor> {
+        Some(self)
+    }
+
     fn inform_save(&mut self) {
         self.original_font = self.font.clone();
+        if let Some(table) = self.saved_unicode_table.take() {
+            set_unicode_table(&self.font, &table);
+        }
     }
 
     fn destroy(&self, gl: &glow::Context) -> Option<Message> {
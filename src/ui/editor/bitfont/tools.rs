@@ -0,0 +1,246 @@
+//! Drawing tools of the glyph editor. Tools work on the rows of a glyph and return the new rows,
+//! the editor turns the result into an undo operation.
+
+use i18n_embed_fl::fl;
+
+use super::glyph_data::{bytes_per_row, get_glyph_pixel, set_glyph_pixel};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlyphTool {
+    Pencil,
+    Line,
+    Rectangle,
+    FilledRectangle,
+    Ellipse,
+    FilledEllipse,
+    Fill,
+    Select,
+}
+
+impl GlyphTool {
+    pub const ALL: [GlyphTool; 8] = [
+        GlyphTool::Pencil,
+        GlyphTool::Line,
+        GlyphTool::Rectangle,
+        GlyphTool::FilledRectangle,
+        GlyphTool::Ellipse,
+        GlyphTool::FilledEllipse,
+        GlyphTool::Fill,
+        GlyphTool::Select,
+    ];
+
+    pub fn get_icon(self) -> &'static str {
+        match self {
+            GlyphTool::Pencil => "✏",
+            GlyphTool::Line => "╱",
+            GlyphTool::Rectangle => "□",
+            GlyphTool::FilledRectangle => "■",
+            GlyphTool::Ellipse => "○",
+            GlyphTool::FilledEllipse => "●",
+            GlyphTool::Fill => "💧",
+            GlyphTool::Select => "⛶",
+        }
+    }
+
+    pub fn get_label(self) -> String {
+        match self {
+            GlyphTool::Pencil => fl!(crate::LANGUAGE_LOADER, "font-editor-tool-pencil"),
+            GlyphTool::Line => fl!(crate::LANGUAGE_LOADER, "font-editor-tool-line"),
+            GlyphTool::Rectangle => fl!(crate::LANGUAGE_LOADER, "font-editor-tool-rectangle"),
+            GlyphTool::FilledRectangle => fl!(crate::LANGUAGE_LOADER, "font-editor-tool-filled_rectangle"),
+            GlyphTool::Ellipse => fl!(crate::LANGUAGE_LOADER, "font-editor-tool-ellipse"),
+            GlyphTool::FilledEllipse => fl!(crate::LANGUAGE_LOADER, "font-editor-tool-filled_ellipse"),
+            GlyphTool::Fill => fl!(crate::LANGUAGE_LOADER, "font-editor-tool-fill"),
+            GlyphTool::Select => fl!(crate::LANGUAGE_LOADER, "font-editor-tool-select"),
+        }
+    }
+
+    pub fn get_undo_description(self) -> String {
+        match self {
+            GlyphTool::Pencil => fl!(crate::LANGUAGE_LOADER, "undo-bitfont-edit"),
+            GlyphTool::Line => fl!(crate::LANGUAGE_LOADER, "undo-bitfont-line"),
+            GlyphTool::Rectangle | GlyphTool::FilledRectangle => fl!(crate::LANGUAGE_LOADER, "undo-bitfont-rectangle"),
+            GlyphTool::Ellipse | GlyphTool::FilledEllipse => fl!(crate::LANGUAGE_LOADER, "undo-bitfont-ellipse"),
+            GlyphTool::Fill => fl!(crate::LANGUAGE_LOADER, "undo-bitfont-fill"),
+            GlyphTool::Select => fl!(crate::LANGUAGE_LOADER, "undo-bitfont-move-selection"),
+        }
+    }
+
+    /// Returns the pixels of the shape spanned by the two points.
+    pub fn get_shape(self, from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+        let (min_x, max_x) = (from.0.min(to.0), from.0.max(to.0));
+        let (min_y, max_y) = (from.1.min(to.1), from.1.max(to.1));
+        let mut result = Vec::new();
+        match self {
+            GlyphTool::Line => {
+                // Bresenham
+                let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+                let (sx, sy) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+                let (mut x, mut y) = from;
+                let mut err = dx + dy;
+                loop {
+                    result.push((x, y));
+                    if (x, y) == to {
+                        break;
+                    }
+                    let e2 = 2 * err;
+                    if e2 >= dy {
+                        err += dy;
+                        x += sx;
+                    }
+                    if e2 <= dx {
+                        err += dx;
+                        y += sy;
+                    }
+                }
+            }
+            GlyphTool::Rectangle | GlyphTool::FilledRectangle => {
+                for y in min_y..=max_y {
+                    for x in min_x..=max_x {
+                        if self == GlyphTool::FilledRectangle || x == min_x || x == max_x || y == min_y || y == max_y {
+                            result.push((x, y));
+                        }
+                    }
+                }
+            }
+            GlyphTool::Ellipse | GlyphTool::FilledEllipse => {
+                let center = ((min_x + max_x + 1) as f32 / 2.0, (min_y + max_y + 1) as f32 / 2.0);
+                let radius = ((max_x - min_x + 1) as f32 / 2.0, (max_y - min_y + 1) as f32 / 2.0);
+                let is_inside = |x: i32, y: i32| {
+                    let dx = (x as f32 + 0.5 - center.0) / radius.0;
+                    let dy = (y as f32 + 0.5 - center.1) / radius.1;
+                    dx * dx + dy * dy <= 1.0
+                };
+                for y in min_y..=max_y {
+                    for x in min_x..=max_x {
+                        if !is_inside(x, y) {
+                            continue;
+                        }
+                        let is_border = !is_inside(x - 1, y) || !is_inside(x + 1, y) || !is_inside(x, y - 1) || !is_inside(x, y + 1);
+                        if self == GlyphTool::FilledEllipse || is_border {
+                            result.push((x, y));
+                        }
+                    }
+                }
+            }
+            GlyphTool::Pencil | GlyphTool::Fill | GlyphTool::Select => {}
+        }
+        result
+    }
+}
+
+/// Sets all pixels connected to `pos` that have the same value as `pos` to `value`.
+pub fn flood_fill(data: &mut [u8], width: i32, pos: (i32, i32), value: bool) {
+    let height = (data.len() / bytes_per_row(width)) as i32;
+    if pos.0 < 0 || pos.1 < 0 || pos.0 >= width || pos.1 >= height {
+        return;
+    }
+    let target = get_glyph_pixel(data, width, pos.0, pos.1);
+    if target == value {
+        return;
+    }
+    let mut stack = vec![pos];
+    while let Some((x, y)) = stack.pop() {
+        if x < 0 || y < 0 || x >= width || y >= height || get_glyph_pixel(data, width, x, y) != target {
+            continue;
+        }
+        set_glyph_pixel(data, width, x, y, value);
+        stack.extend([(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]);
+    }
+}
+
+/// A rectangular block of glyph pixels, line by line.
+#[derive(Clone, Debug, Default)]
+pub struct GlyphPixels {
+    pub width: i32,
+    pub height: i32,
+    pub pixels: Vec<bool>,
+}
+
+/// Selected rectangle of the edited glyph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlyphSelection {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl GlyphSelection {
+    pub fn from_points(from: (i32, i32), to: (i32, i32)) -> Self {
+        Self {
+            x: from.0.min(to.0),
+            y: from.1.min(to.1),
+            width: (from.0 - to.0).abs() + 1,
+            height: (from.1 - to.1).abs() + 1,
+        }
+    }
+
+    pub fn contains(&self, pos: (i32, i32)) -> bool {
+        pos.0 >= self.x && pos.1 >= self.y && pos.0 < self.x + self.width && pos.1 < self.y + self.height
+    }
+
+    pub fn copy(&self, data: &[u8], width: i32) -> GlyphPixels {
+        let mut pixels = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                pixels.push(get_glyph_pixel(data, width, self.x + x, self.y + y));
+            }
+        }
+        GlyphPixels {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+
+    pub fn clear(&self, data: &mut [u8], width: i32) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                set_glyph_pixel(data, width, self.x + x, self.y + y, false);
+            }
+        }
+    }
+}
+
+/// Clipboard format of a copied glyph region, a whole glyph is copied as `BITFONT_GLYPH`.
+pub const BITFONT_GLYPH_PIXELS: &str = "icy_draw/bitfont_glyph_pixels";
+
+impl GlyphPixels {
+    /// Width and height as little endian i32 followed by one byte per pixel.
+    pub fn to_clipboard_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(8 + self.pixels.len());
+        data.extend(self.width.to_le_bytes());
+        data.extend(self.height.to_le_bytes());
+        data.extend(self.pixels.iter().map(|p| u8::from(*p)));
+        data
+    }
+
+    pub fn from_clipboard_data(data: &[u8]) -> Option<Self> {
+        let width = i32::from_le_bytes(data.get(0..4)?.try_into().ok()?);
+        let height = i32::from_le_bytes(data.get(4..8)?.try_into().ok()?);
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+        let pixels: Vec<bool> = data.get(8..8 + (width * height) as usize)?.iter().map(|p| *p != 0).collect();
+        Some(Self { width, height, pixels })
+    }
+
+    pub fn paste(&self, data: &mut [u8], width: i32, pos: (i32, i32)) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let value = self.pixels[(y * self.width + x) as usize];
+                set_glyph_pixel(data, width, pos.0 + x, pos.1 + y, value);
+            }
+        }
+    }
+}
+
+/// Moves the selected pixels by the offset, the area they leave is cleared.
+pub fn move_selection(data: &[u8], width: i32, selection: GlyphSelection, offset: (i32, i32)) -> Vec<u8> {
+    let pixels = selection.copy(data, width);
+    let mut result = data.to_vec();
+    selection.clear(&mut result, width);
+    pixels.paste(&mut result, width, (selection.x + offset.0, selection.y + offset.1));
+    result
+}
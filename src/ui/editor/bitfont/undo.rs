@@ -1,7 +1,7 @@
 use i18n_embed_fl::fl;
 use icy_engine::{BitFont, EngineResult, Glyph};
 
-use crate::BitFontEditor;
+use crate::{bytes_per_row, get_glyph_pixel, map_glyph_pixels, BitFontEditor, UnicodeTable};
 
 pub trait UndoOperation: Send {
     fn get_description(&self) -> String;
@@ -49,7 +49,7 @@ impl UndoOperation for Paste {
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
-        let len = edit_state.font.size.height as usize;
+        let len = bytes_per_row(edit_state.font.size.width) * edit_state.font.size.height as usize;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
             self.old_data = glyph.data.clone();
             glyph.data = self.glyph.data.clone();
@@ -75,15 +75,17 @@ impl UndoOperation for FlipY {
     }
 
     fn undo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
-            glyph.data = glyph.data.iter().rev().copied().collect();
+            glyph.data = map_glyph_pixels(size, |x, y| get_glyph_pixel(&glyph.data, size.width, x, size.height - 1 - y));
         }
         Ok(())
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
-            glyph.data = glyph.data.iter().rev().copied().collect();
+            glyph.data = map_glyph_pixels(size, |x, y| get_glyph_pixel(&glyph.data, size.width, x, size.height - 1 - y));
         }
         Ok(())
     }
@@ -105,21 +107,17 @@ impl UndoOperation for FlipX {
     }
 
     fn undo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
-        let w = 8 - edit_state.font.size.width;
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
-            for i in 0..glyph.data.len() {
-                glyph.data[i] = glyph.data[i].reverse_bits() << w;
-            }
+            glyph.data = map_glyph_pixels(size, |x, y| get_glyph_pixel(&glyph.data, size.width, size.width - 1 - x, y));
         }
         Ok(())
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
-        let w = 8 - edit_state.font.size.width;
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
-            for i in 0..glyph.data.len() {
-                glyph.data[i] = glyph.data[i].reverse_bits() << w;
-            }
+            glyph.data = map_glyph_pixels(size, |x, y| get_glyph_pixel(&glyph.data, size.width, size.width - 1 - x, y));
         }
         Ok(())
     }
@@ -149,10 +147,10 @@ impl UndoOperation for DownGlyph {
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
             self.old_data = glyph.data.clone();
-            glyph.data.insert(0, 0);
-            glyph.data.pop();
+            glyph.data = map_glyph_pixels(size, |x, y| get_glyph_pixel(&self.old_data, size.width, x, y - 1));
         }
         Ok(())
     }
@@ -182,10 +180,10 @@ impl UndoOperation for UpGlyph {
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
             self.old_data = glyph.data.clone();
-            glyph.data.remove(0);
-            glyph.data.push(0);
+            glyph.data = map_glyph_pixels(size, |x, y| get_glyph_pixel(&self.old_data, size.width, x, y + 1));
         }
         Ok(())
     }
@@ -215,11 +213,10 @@ impl UndoOperation for RightGlyph {
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
             self.old_data = glyph.data.clone();
-            for i in 0..glyph.data.len() {
-                glyph.data[i] >>= 1;
-            }
+            glyph.data = map_glyph_pixels(size, |x, y| get_glyph_pixel(&self.old_data, size.width, x - 1, y));
         }
         Ok(())
     }
@@ -249,11 +246,10 @@ impl UndoOperation for LeftGlyph {
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
             self.old_data = glyph.data.clone();
-            for i in 0..glyph.data.len() {
-                glyph.data[i] <<= 1;
-            }
+            glyph.data = map_glyph_pixels(size, |x, y| get_glyph_pixel(&self.old_data, size.width, x + 1, y));
         }
         Ok(())
     }
@@ -307,19 +303,17 @@ impl UndoOperation for InverseGlyph {
     }
 
     fn undo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
-            for i in 0..glyph.data.len() {
-                glyph.data[i] ^= 0xFF;
-            }
+            glyph.data = map_glyph_pixels(size, |x, y| !get_glyph_pixel(&glyph.data, size.width, x, y));
         }
         Ok(())
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        let size = edit_state.font.size;
         if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
-            for i in 0..glyph.data.len() {
-                glyph.data[i] ^= 0xFF;
-            }
+            glyph.data = map_glyph_pixels(size, |x, y| !get_glyph_pixel(&glyph.data, size.width, x, y));
         }
         Ok(())
     }
@@ -357,29 +351,142 @@ impl UndoOperation for Edit {
     }
 }
 
-pub struct ResizeFont {
+/// Replaces the whole font, used by operations that change the font size or many glyphs at once.
+pub struct ReplaceFont {
+    description: String,
     old_font: BitFont,
     new_font: BitFont,
 }
 
-impl ResizeFont {
-    pub(crate) fn new(old_font: BitFont, new_font: BitFont) -> Self {
-        Self { old_font, new_font }
+impl ReplaceFont {
+    pub(crate) fn new(description: String, old_font: BitFont, new_font: BitFont) -> Self {
+        Self {
+            description,
+            old_font,
+            new_font,
+        }
     }
 }
 
-impl UndoOperation for ResizeFont {
+impl UndoOperation for ReplaceFont {
     fn get_description(&self) -> String {
-        fl!(crate::LANGUAGE_LOADER, "undo-bitfont-resize")
+        self.description.clone()
     }
 
     fn undo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
-        edit_state.font = self.old_font.clone();
+        edit_state.set_font(self.old_font.clone());
         Ok(())
     }
 
     fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
-        edit_state.font = self.new_font.clone();
+        edit_state.set_font(self.new_font.clone());
+        Ok(())
+    }
+}
+
+pub struct DrawGlyph {
+    ch: char,
+    description: String,
+    old_data: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl DrawGlyph {
+    pub(crate) fn new(ch: char, description: String, data: Vec<u8>, old_data: Vec<u8>) -> Self {
+        Self {
+            ch,
+            description,
+            data,
+            old_data,
+        }
+    }
+}
+
+impl UndoOperation for DrawGlyph {
+    fn get_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn undo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
+            glyph.data = self.old_data.clone();
+        }
+        Ok(())
+    }
+
+    fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        if let Some(glyph) = edit_state.font.get_glyph_mut(self.ch) {
+            glyph.data = self.data.clone();
+        }
+        Ok(())
+    }
+}
+
+pub struct SetUnicodeTable {
+    old_table: UnicodeTable,
+    new_table: UnicodeTable,
+}
+
+impl SetUnicodeTable {
+    pub(crate) fn new(old_table: UnicodeTable, new_table: UnicodeTable) -> Self {
+        Self { old_table, new_table }
+    }
+}
+
+impl UndoOperation for SetUnicodeTable {
+    fn get_description(&self) -> String {
+        fl!(crate::LANGUAGE_LOADER, "undo-bitfont-unicode")
+    }
+
+    fn undo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        edit_state.load_unicode_table(self.old_table.clone());
+        Ok(())
+    }
+
+    fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        edit_state.load_unicode_table(self.new_table.clone());
+        Ok(())
+    }
+}
+
+pub struct PatchGlyphs {
+    description: String,
+    glyphs: Vec<(char, Vec<u8>)>,
+    old_glyphs: Vec<(char, Vec<u8>)>,
+}
+
+impl PatchGlyphs {
+    pub(crate) fn new(description: String, glyphs: Vec<(char, Vec<u8>)>) -> Self {
+        Self {
+            description,
+            glyphs,
+            old_glyphs: Vec::new(),
+        }
+    }
+}
+
+impl UndoOperation for PatchGlyphs {
+    fn get_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn undo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        for (ch, data) in &self.old_glyphs {
+            if let Some(glyph) = edit_state.font.get_glyph_mut(*ch) {
+                glyph.data = data.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn redo(&mut self, edit_state: &mut BitFontEditor) -> EngineResult<()> {
+        self.old_glyphs.clear();
+        for (ch, data) in &self.glyphs {
+            if let Some(glyph) = edit_state.font.get_glyph_mut(*ch) {
+                self.old_glyphs.push((*ch, glyph.data.clone()));
+                glyph.data = data.clone();
+            }
+        }
         Ok(())
     }
 }
@@ -0,0 +1,240 @@
+//! Unicode mapping tables of bit fonts. Every glyph can stand for one or more unicode code points,
+//! the table is read from and written to PSF fonts. Fonts with a table that differs from CP437 are
+//! registered when a font file is loaded or saved, documents using exactly that font convert their
+//! text with it on UTF-8 export and on the clipboard.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use icy_engine::{BitFont, Buffer, BufferType, Position, TextPane, UnicodeConverter};
+
+use super::formats::get_unicode;
+
+lazy_static::lazy_static! {
+    static ref UNICODE_TABLES: Mutex<HashMap<FontKey, UnicodeTable>> = Mutex::new(HashMap::new());
+}
+
+/// Fonts are identified by name and glyph checksum, a font with the same name but other glyphs
+/// (like an unsaved edit) doesn't get the table.
+type FontKey = (String, u32);
+
+fn get_font_key(font: &BitFont) -> FontKey {
+    (font.name.clone(), font.get_checksum())
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UnicodeTable {
+    glyphs: Vec<Vec<char>>,
+}
+
+impl UnicodeTable {
+    /// The table of CP437 fonts, glyphs above 255 are mapped to the code point with the same number.
+    pub fn from_cp437(length: usize) -> Self {
+        Self {
+            glyphs: (0..length).map(|i| vec![get_unicode(i)]).collect(),
+        }
+    }
+
+    /// Reads the unicode table of a PSF1 or PSF2 font, `None` if the font doesn't have one.
+    pub fn from_psf_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() >= 4 && data[0] == 0x36 && data[1] == 0x04 {
+            let mode = data[2];
+            if mode & 0x06 == 0 {
+                return None;
+            }
+            let length = if mode & 0x01 != 0 { 512 } else { 256 };
+            let mut offset = 4 + length * data[3] as usize;
+            let mut glyphs = vec![Vec::new(); length];
+            for glyph in &mut glyphs {
+                let mut in_sequence = false;
+                while offset + 1 < data.len() {
+                    let value = u16::from_le_bytes([data[offset], data[offset + 1]]);
+                    offset += 2;
+                    match value {
+                        0xFFFF => break,
+                        // sequences of combining characters aren't supported
+                        0xFFFE => in_sequence = true,
+                        _ if !in_sequence => glyph.extend(char::from_u32(u32::from(value))),
+                        _ => {}
+                    }
+                }
+            }
+            return Some(Self { glyphs });
+        }
+
+        if data.len() >= 32 && data[0..4] == [0x72, 0xB5, 0x4A, 0x86] {
+            let read_u32 = |offset: usize| u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]) as usize;
+            let (header_size, flags, length, char_size) = (read_u32(8), read_u32(12), read_u32(16), read_u32(20));
+            if flags & 0x01 == 0 {
+                return None;
+            }
+            let mut offset = header_size + length * char_size;
+            let mut glyphs = vec![Vec::new(); length];
+            for glyph in &mut glyphs {
+                let start = offset;
+                while offset < data.len() && data[offset] != 0xFF {
+                    offset += 1;
+                }
+                let entry = data.get(start..offset).unwrap_or_default();
+                offset += 1;
+                let entry = match entry.iter().position(|b| *b == 0xFE) {
+                    Some(pos) => &entry[..pos],
+                    None => entry,
+                };
+                glyph.extend(String::from_utf8_lossy(entry).chars().filter(|ch| *ch != char::REPLACEMENT_CHARACTER));
+            }
+            return Some(Self { glyphs });
+        }
+        None
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> &[char] {
+        self.glyphs.get(i).map(|chars| chars.as_slice()).unwrap_or_default()
+    }
+
+    pub fn set(&mut self, i: usize, chars: Vec<char>) {
+        if i >= self.glyphs.len() {
+            self.glyphs.resize(i + 1, Vec::new());
+        }
+        self.glyphs[i] = chars;
+    }
+
+    pub fn resize(&mut self, length: usize) {
+        let old_length = self.glyphs.len();
+        self.glyphs.resize(length, Vec::new());
+        for i in old_length..length {
+            self.glyphs[i] = vec![get_unicode(i)];
+        }
+    }
+
+    /// The primary code point of the glyph.
+    pub fn to_unicode(&self, i: usize) -> Option<char> {
+        self.get(i).first().copied()
+    }
+
+    /// The first glyph that stands for the code point.
+    pub fn from_unicode(&self, ch: char) -> Option<usize> {
+        self.glyphs.iter().position(|chars| chars.contains(&ch))
+    }
+}
+
+/// Returns true if the unicode table of a PSF font contains sequences of combining characters,
+/// `UnicodeTable` only keeps the single code points.
+pub fn has_psf_sequences(data: &[u8]) -> bool {
+    if data.len() >= 4 && data[0] == 0x36 && data[1] == 0x04 {
+        if data[2] & 0x06 == 0 {
+            return false;
+        }
+        let length = if data[2] & 0x01 != 0 { 512 } else { 256 };
+        let table = data.get(4 + length * data[3] as usize..).unwrap_or_default();
+        return table.chunks_exact(2).any(|value| value == [0xFE, 0xFF]);
+    }
+    if data.len() >= 32 && data[0..4] == [0x72, 0xB5, 0x4A, 0x86] {
+        let read_u32 = |offset: usize| u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]) as usize;
+        let (header_size, flags, length, char_size) = (read_u32(8), read_u32(12), read_u32(16), read_u32(20));
+        if flags & 0x01 == 0 {
+            return false;
+        }
+        // 0xFE never appears in UTF-8, it only starts a sequence
+        let table = data.get(header_size + length * char_size..).unwrap_or_default();
+        return table.contains(&0xFE);
+    }
+    false
+}
+
+/// Formats code points as `U+00C4 U+0041`.
+pub fn format_code_points(chars: &[char]) -> String {
+    chars.iter().map(|ch| format!("U+{:04X}", *ch as u32)).collect::<Vec<_>>().join(" ")
+}
+
+/// Parses a whitespace separated list of code points, either `U+XXXX`, `0xXXXX` or the character itself.
+pub fn parse_code_points(text: &str) -> Result<Vec<char>, String> {
+    let mut result = Vec::new();
+    for part in text.split_whitespace() {
+        let hex = part
+            .strip_prefix("U+")
+            .or_else(|| part.strip_prefix("u+"))
+            .or_else(|| part.strip_prefix("0x"))
+            .or_else(|| part.strip_prefix("0X"));
+        let ch = match hex {
+            Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+            None => {
+                let mut chars = part.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Some(ch),
+                    _ => None,
+                }
+            }
+        };
+        match ch {
+            Some(ch) => result.push(ch),
+            None => return Err(part.to_string()),
+        }
+    }
+    Ok(result)
+}
+
+/// Registers the table for the font, tables equal to CP437 are removed.
+pub fn set_unicode_table(font: &BitFont, table: &UnicodeTable) {
+    if let Ok(mut tables) = UNICODE_TABLES.lock() {
+        if *table == UnicodeTable::from_cp437(table.len()) {
+            tables.remove(&get_font_key(font));
+        } else {
+            tables.insert(get_font_key(font), table.clone());
+        }
+    }
+}
+
+/// Registers the unicode table of a PSF font file.
+pub fn register_psf_unicode_table(font: &BitFont, data: &[u8]) {
+    if let Some(table) = UnicodeTable::from_psf_bytes(data) {
+        set_unicode_table(font, &table);
+    }
+}
+
+fn has_unicode_table(font: &BitFont) -> bool {
+    UNICODE_TABLES.lock().map_or(false, |tables| tables.contains_key(&get_font_key(font)))
+}
+
+/// Converts a glyph of the font to unicode, `None` if the font has no registered table.
+pub fn font_to_unicode(font: Option<&BitFont>, ch: char) -> Option<char> {
+    let tables = UNICODE_TABLES.lock().ok()?;
+    tables.get(&get_font_key(font?))?.to_unicode(ch as usize)
+}
+
+/// Converts unicode to a glyph of the font, `None` if the font has no registered table or no glyph for it.
+pub fn font_from_unicode(font: Option<&BitFont>, ch: char) -> Option<char> {
+    let tables = UNICODE_TABLES.lock().ok()?;
+    tables.get(&get_font_key(font?))?.from_unicode(ch).and_then(|i| char::from_u32(i as u32))
+}
+
+/// Returns a unicode copy of a CP437 buffer if one of its fonts has a registered unicode table.
+pub fn apply_unicode_tables(buf: &Buffer) -> Option<Buffer> {
+    if buf.buffer_type != BufferType::CP437 || !buf.font_iter().any(|(_, font)| has_unicode_table(font)) {
+        return None;
+    }
+    let converter = icy_engine::ascii::CP437Converter::default();
+    let mut result = buf.clone();
+    result.buffer_type = BufferType::Unicode;
+    for layer in &mut result.layers {
+        for y in 0..layer.get_height() {
+            for x in 0..layer.get_width() {
+                let pos = Position::new(x, y);
+                let mut ch = layer.get_char(pos);
+                if ch.is_transparent() {
+                    continue;
+                }
+                ch.ch = font_to_unicode(buf.get_font(ch.get_font_page()), ch.ch).unwrap_or_else(|| converter.convert_to_unicode(ch));
+                layer.set_char(pos, ch);
+            }
+        }
+    }
+    Some(result)
+}
@@ -0,0 +1,180 @@
+//! Generates TheDraw block and color fonts out of bit font glyphs. Every set pixel becomes a block
+//! character, the recipe chooses the characters, the row colors and an optional shadow.
+
+use i18n_embed_fl::fl;
+use icy_engine::{BitFont, FontGlyph, FontType, Size, TheDrawFont};
+
+use super::TDF_NAME_LENGTH;
+use crate::get_glyph_pixel;
+
+pub const FULL_BLOCK: u8 = 0xDB;
+const UPPER_HALF_BLOCK: u8 = 0xDF;
+const LOWER_HALF_BLOCK: u8 = 0xDC;
+pub const LIGHT_SHADE: u8 = 0xB0;
+
+#[derive(Clone)]
+pub struct TdfRecipe {
+    /// Block or color - outline fonts can't be generated.
+    pub font_type: FontType,
+    /// Character of a set pixel.
+    pub pixel_char: u8,
+    /// Two pixel rows per character cell drawn with half blocks.
+    pub half_blocks: bool,
+    /// Removes the empty columns left and right of every glyph.
+    pub proportional: bool,
+    /// Foreground colors from the top to the bottom row, stretched over the glyph height.
+    pub gradient: Vec<u8>,
+    pub background: u8,
+    /// Shadow offset in character cells, (0, 0) disables the shadow.
+    pub shadow_offset: (i32, i32),
+    pub shadow_char: u8,
+    pub shadow_color: u8,
+}
+
+impl Default for TdfRecipe {
+    fn default() -> Self {
+        Self {
+            font_type: FontType::Color,
+            pixel_char: FULL_BLOCK,
+            half_blocks: true,
+            proportional: true,
+            gradient: vec![15, 11, 3, 9, 1],
+            background: 0,
+            shadow_offset: (0, 0),
+            shadow_char: LIGHT_SHADE,
+            shadow_color: 8,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Cell {
+    ch: u8,
+    fg: u8,
+    bg: u8,
+}
+
+impl TdfRecipe {
+    /// Creates a font with the printable ASCII glyphs '!' to '~' of the bit font.
+    pub fn generate(&self, font: &BitFont, name: &str) -> TheDrawFont {
+        let mut result = TheDrawFont::new(get_tdf_font_name(name), self.font_type, 1);
+        for ch in '!'..='~' {
+            if let Some(glyph) = self.generate_glyph(font, ch) {
+                result.set_glyph(ch, glyph);
+            }
+        }
+        result
+    }
+
+    fn get_color(&self, row: i32, rows: i32) -> u8 {
+        if self.gradient.is_empty() {
+            return 7;
+        }
+        let i = row.max(0) as usize * self.gradient.len() / rows.max(1) as usize;
+        self.gradient[i.min(self.gradient.len() - 1)]
+    }
+
+    fn generate_glyph(&self, font: &BitFont, ch: char) -> Option<FontGlyph> {
+        let data = &font.get_glyph(ch)?.data;
+        let size = font.size;
+        let is_set = |x: i32, y: i32| y < size.height && get_glyph_pixel(data, size.width, x, y);
+
+        let columns: Vec<i32> = (0..size.width).filter(|x| (0..size.height).any(|y| is_set(*x, y))).collect();
+        let (left, right) = if self.proportional {
+            (*columns.first()?, *columns.last()? + 1)
+        } else if columns.is_empty() {
+            return None;
+        } else {
+            (0, size.width)
+        };
+        let width = right - left;
+        let rows = if self.half_blocks { (size.height + 1) / 2 } else { size.height };
+
+        let get_pixel_char = |x: i32, row: i32| {
+            if self.half_blocks {
+                match (is_set(left + x, row * 2), is_set(left + x, row * 2 + 1)) {
+                    (true, true) => Some(self.pixel_char),
+                    (true, false) => Some(UPPER_HALF_BLOCK),
+                    (false, true) => Some(LOWER_HALF_BLOCK),
+                    (false, false) => None,
+                }
+            } else if is_set(left + x, row) {
+                Some(self.pixel_char)
+            } else {
+                None
+            }
+        };
+
+        // a shadow to the left or top moves the glyph itself
+        let (dx, dy) = self.shadow_offset;
+        let (origin_x, origin_y) = (-dx.min(0), -dy.min(0));
+        let (grid_width, grid_height) = (width + dx.abs(), rows + dy.abs());
+        let mut grid: Vec<Option<Cell>> = vec![None; (grid_width * grid_height) as usize];
+        let mut set_cell = |x: i32, y: i32, cell: Cell| {
+            if x >= 0 && y >= 0 && x < grid_width && y < grid_height {
+                grid[(x + y * grid_width) as usize] = Some(cell);
+            }
+        };
+
+        if self.shadow_offset != (0, 0) {
+            let shadow = Cell {
+                ch: self.shadow_char,
+                fg: self.shadow_color,
+                bg: self.background,
+            };
+            for row in 0..rows {
+                for x in 0..width {
+                    if get_pixel_char(x, row).is_some() {
+                        set_cell(origin_x + x + dx, origin_y + row + dy, shadow);
+                    }
+                }
+            }
+        }
+        for row in 0..rows {
+            let fg = self.get_color(row, rows);
+            for x in 0..width {
+                if let Some(ch) = get_pixel_char(x, row) {
+                    set_cell(origin_x + x, origin_y + row, Cell { ch, fg, bg: self.background });
+                }
+            }
+        }
+
+        let mut data = Vec::new();
+        for y in 0..grid_height {
+            if y > 0 {
+                data.push(13);
+            }
+            for x in 0..grid_width {
+                let cell = grid[(x + y * grid_width) as usize].unwrap_or(Cell {
+                    ch: b' ',
+                    fg: 7,
+                    bg: self.background,
+                });
+                data.push(cell.ch);
+                if matches!(self.font_type, FontType::Color) {
+                    // ice colors: background in the high nibble
+                    data.push((cell.bg & 0x0F) << 4 | (cell.fg & 0x0F));
+                }
+            }
+        }
+        Some(FontGlyph {
+            size: Size::new(grid_width, grid_height),
+            data,
+        })
+    }
+}
+
+/// Makes a TDF compatible font name out of any name.
+pub fn get_tdf_font_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|ch| ch.is_ascii() && !ch.is_ascii_control())
+        .take(TDF_NAME_LENGTH)
+        .collect();
+    let name = name.trim();
+    if name.is_empty() {
+        fl!(crate::LANGUAGE_LOADER, "tdf-editor-new_font_name")
+    } else {
+        name.to_string()
+    }
+}
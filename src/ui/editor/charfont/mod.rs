@@ -1,3 +1,6 @@
+mod generator;
+pub use generator::*;
+
 use std::{path::Path, sync::Arc};
 
 use eframe::{
@@ -6,7 +9,7 @@ use eframe::{
 };
 use egui::{load::SizedTexture, Image, Rect, TextureHandle};
 use i18n_embed_fl::fl;
-use icy_engine::{AttributedChar, BitFont, Buffer, EngineResult, FontGlyph, Layer, Size, TextAttribute, TextPane, TheDrawFont};
+use icy_engine::{AttributedChar, BitFont, Buffer, EngineResult, FontGlyph, FontType, Layer, Size, TextAttribute, TextPane, TheDrawFont};
 use icy_engine_egui::{show_terminal_area, BufferView};
 
 use crate::{
@@ -15,6 +18,31 @@ use crate::{
     SETTINGS,
 };
 
+/// TheDraw stores font names as pascal strings in a 12 byte field.
+pub const TDF_NAME_LENGTH: usize = 12;
+
+/// Checks if a font name can be stored in a TDF file, returns the error message if not.
+pub fn validate_tdf_font_name(name: &str) -> Option<String> {
+    if name.trim().is_empty() {
+        return Some(fl!(crate::LANGUAGE_LOADER, "tdf-editor-name_error-empty"));
+    }
+    if !name.chars().all(|ch| ch.is_ascii() && !ch.is_ascii_control()) {
+        return Some(fl!(crate::LANGUAGE_LOADER, "tdf-editor-name_error-ascii"));
+    }
+    if name.len() > TDF_NAME_LENGTH {
+        return Some(fl!(crate::LANGUAGE_LOADER, "tdf-editor-name_error-length", max = TDF_NAME_LENGTH));
+    }
+    None
+}
+
+fn get_font_type_label(font_type: FontType) -> String {
+    match font_type {
+        FontType::Outline => fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_outline"),
+        FontType::Block => fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_block"),
+        FontType::Color => fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_color"),
+    }
+}
+
 pub struct CharFontEditor {
     id: usize,
     font: BitFont,
@@ -32,6 +60,8 @@ pub struct CharFontEditor {
     outline_selection: crate::SelectOutlineDialog,
     draw_outline_bg: bool,
     opt_cheat_sheet: Option<TextureHandle>,
+    font_name: String,
+    font_name_error: Option<String>,
 }
 
 impl ClipboardHandler for CharFontEditor {
@@ -99,10 +129,27 @@ impl Document for CharFontEditor {
     fn get_bytes(&mut self, _path: &Path) -> TerminalResult<Vec<u8>> {
         self.undostack_len += 1;
         self.save_old_selected_char();
-        TheDrawFont::create_font_bundle(&self.fonts)
+        for font in &self.fonts {
+            if let Some(err) = validate_tdf_font_name(&font.name) {
+                return Err(anyhow::anyhow!(fl!(
+                    crate::LANGUAGE_LOADER,
+                    "tdf-editor-save_error-name",
+                    name = font.name.clone(),
+                    error = err
+                )));
+            }
+        }
+        let bytes = TheDrawFont::create_font_bundle(&self.fonts)?;
+
+        // read the bundle back so a file TheDraw can't load is never written
+        match TheDrawFont::from_tdf_bytes(&bytes) {
+            Ok(fonts) if fonts.len() == self.fonts.len() => Ok(bytes),
+            _ => Err(anyhow::anyhow!(fl!(crate::LANGUAGE_LOADER, "tdf-editor-save_error-bundle"))),
+        }
     }
 
     fn show_ui(&mut self, ui: &mut egui::Ui, cur_tool: &mut Box<dyn Tool>, selected_tool: usize, options: &DocumentOptions) -> Option<Message> {
+        let mut message = None;
         SidePanel::left("side_panel").default_width(200.0).show_inside(ui, |ui| {
             ui.add_space(4.0);
 
@@ -111,12 +158,9 @@ impl Document for CharFontEditor {
                     ui.style_mut().wrap = Some(false);
 
                     for i in 0..self.fonts.len() {
-                        if ui.selectable_value(&mut self.selected_font, i, &self.fonts[i].name).clicked() {
-                            self.save_old_selected_char();
-                            self.selected_font = i;
-                            self.old_selected_char_opt = None;
-                            self.selected_char_opt = None;
-                            self.show_selected_char();
+                        let label = format!("{} ({})", self.fonts[i].name, get_font_type_label(self.fonts[i].font_type));
+                        if ui.selectable_label(self.selected_font == i, label).clicked() {
+                            self.select_font(i);
                         }
                     }
                 });
@@ -124,37 +168,51 @@ impl Document for CharFontEditor {
             ui.separator();
 
             ui.horizontal(|ui| {
-                /*if ui.button("+").clicked() {
-                    self.fonts.push(TheDrawFont::new(
-                        "New Font",
-                        icy_engine::FontType::Color,
-                        1,
-                    ));
-                    self.selected_font = self.fonts.len() - 1;
-                    self.selected_char_opt = None;
+                ui.menu_button("+", |ui| {
+                    for font_type in [FontType::Outline, FontType::Block, FontType::Color] {
+                        if ui.button(get_font_type_label(font_type)).clicked() {
+                            self.add_font(TheDrawFont::new(fl!(crate::LANGUAGE_LOADER, "tdf-editor-new_font_name"), font_type, 1));
+                            ui.close_menu();
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(fl!(crate::LANGUAGE_LOADER, "tdf-editor-add_button"));
+
+                if ui
+                    .add_enabled(self.fonts.len() > 1, Button::new("🗑"))
+                    .on_hover_text(fl!(crate::LANGUAGE_LOADER, "tdf-editor-delete_button"))
+                    .clicked()
+                {
                     self.old_selected_char_opt = None;
-                    self.show_selected_char();
-                    self.undostack_len += 1;
-                }*/
-
-                if ui.add_enabled(self.fonts.len() > 1, Button::new("🗑")).clicked() {
                     self.fonts.remove(self.selected_font);
-                    self.selected_font = 0;
-                    self.selected_char_opt = None;
-                    self.old_selected_char_opt = None;
-                    self.show_selected_char();
+                    self.select_font(self.selected_font.min(self.fonts.len() - 1));
                     self.undostack_len += 1;
                 }
 
+                if ui
+                    .add_enabled(self.selected_font > 0, Button::new("⬆"))
+                    .on_hover_text(fl!(crate::LANGUAGE_LOADER, "tdf-editor-move_up_button"))
+                    .clicked()
+                {
+                    self.move_font(self.selected_font, self.selected_font - 1);
+                }
+
+                if ui
+                    .add_enabled(self.selected_font + 1 < self.fonts.len(), Button::new("⬇"))
+                    .on_hover_text(fl!(crate::LANGUAGE_LOADER, "tdf-editor-move_down_button"))
+                    .clicked()
+                {
+                    self.move_font(self.selected_font, self.selected_font + 1);
+                }
+
                 if ui.button(fl!(crate::LANGUAGE_LOADER, "tdf-editor-clone_button")).clicked() {
-                    self.fonts.push(self.fonts[self.selected_font].clone());
-                    self.selected_font = self.fonts.len() - 1;
-                    self.selected_char_opt = None;
-                    self.old_selected_char_opt = None;
-                    self.show_selected_char();
-                    self.undostack_len += 1;
+                    self.add_font(self.fonts[self.selected_font].clone());
                 }
             });
+            if ui.button(fl!(crate::LANGUAGE_LOADER, "tdf-editor-generate_button")).clicked() {
+                message = Some(Message::ShowGenerateTdfFontDialog);
+            }
         });
 
         TopBottomPanel::top("char_top_panel").exact_height(60.).show_inside(ui, |ui| {
@@ -170,33 +228,24 @@ impl Document for CharFontEditor {
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         ui.label(fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_name_label"));
                     });
-                    if ui
-                        .add(
-                            TextEdit::singleline(&mut self.fonts[self.selected_font].name)
-                                .min_size(Vec2::new(200.0, 22.))
-                                .char_limit(12),
-                        )
-                        .changed()
-                    {
-                        self.undostack_len += 1;
-                    }
+                    ui.vertical(|ui| {
+                        if ui.add(TextEdit::singleline(&mut self.font_name).min_size(Vec2::new(200.0, 22.))).changed() {
+                            self.font_name_error = validate_tdf_font_name(&self.font_name);
+                            if self.font_name_error.is_none() {
+                                self.fonts[self.selected_font].name = self.font_name.clone();
+                                self.undostack_len += 1;
+                            }
+                        }
+                        if let Some(err) = &self.font_name_error {
+                            ui.colored_label(ui.style().visuals.error_fg_color, err);
+                        }
+                    });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         ui.label(fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_label"));
                     });
 
-                    let text = match self.fonts[self.selected_font].font_type {
-                        icy_engine::FontType::Outline => {
-                            fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_outline")
-                        }
-                        icy_engine::FontType::Block => {
-                            fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_block")
-                        }
-                        icy_engine::FontType::Color => {
-                            fl!(crate::LANGUAGE_LOADER, "tdf-editor-font_type_color")
-                        }
-                    };
-                    ui.label(text);
+                    ui.label(get_font_type_label(self.fonts[self.selected_font].font_type));
 
                     ui.end_row();
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -395,7 +444,7 @@ impl Document for CharFontEditor {
             self.render_outline_preview();
         }
 
-        None
+        message
     }
 
     fn get_ansi_editor_mut(&mut self) -> Option<&mut AnsiEditor> {
@@ -406,6 +455,10 @@ impl Document for CharFontEditor {
         self.ansi_editor.get_ansi_editor()
     }
 
+    fn get_char_font_editor_mut(&mut self) -> Option<&mut CharFontEditor> {
+        Some(self)
+    }
+
     fn destroy(&self, gl: &glow::Context) -> Option<Message> {
         self.ansi_editor.destroy(gl);
         None
@@ -452,11 +505,45 @@ impl CharFontEditor {
             opt_cheat_sheet: None,
             draw_outline_bg: true,
             last_update_preview_attr: TextAttribute::default(),
+            font_name: String::new(),
+            font_name_error: None,
         };
         res.show_selected_char();
         res
     }
 
+    fn select_font(&mut self, i: usize) {
+        self.save_old_selected_char();
+        self.selected_font = i;
+        self.old_selected_char_opt = None;
+        self.selected_char_opt = None;
+        self.show_selected_char();
+    }
+
+    pub fn add_generated_font(&mut self, font: TheDrawFont) {
+        self.add_font(font);
+    }
+
+    fn add_font(&mut self, font: TheDrawFont) {
+        self.save_old_selected_char();
+        self.old_selected_char_opt = None;
+        self.fonts.push(font);
+        self.select_font(self.fonts.len() - 1);
+        self.undostack_len += 1;
+    }
+
+    /// Moves a font inside the bundle, the selection moves along with it.
+    fn move_font(&mut self, from: usize, to: usize) {
+        self.save_old_selected_char();
+        self.fonts.swap(from, to);
+        if self.selected_font == from {
+            self.selected_font = to;
+        } else if self.selected_font == to {
+            self.selected_font = from;
+        }
+        self.undostack_len += 1;
+    }
+
     pub fn show_char_selector(&mut self, ui: &mut egui::Ui) {
         egui::ScrollArea::vertical().show(ui, |ui| {
             ui.horizontal_wrapped(|ui| {
@@ -513,6 +600,8 @@ impl CharFontEditor {
         {
             self.save_old_selected_char();
             let font = &self.fonts[self.selected_font];
+            self.font_name = font.name.clone();
+            self.font_name_error = None;
             self.ansi_editor.outline_font_mode = matches!(font.font_type, icy_engine::FontType::Outline);
             let lock = &mut self.ansi_editor.buffer_view.lock();
 
@@ -22,6 +22,8 @@ lazy_static::lazy_static! {
     pub static ref DELETE_SVG: Image<'static> = Image::new(egui::include_image!("../../data/icons/delete.svg"));
     pub static ref VISIBLE_SVG: Image<'static> = Image::new(egui::include_image!("../../data/icons/visible.svg"));
     pub static ref INVISIBLE_SVG: Image<'static> = Image::new(egui::include_image!("../../data/icons/invisible.svg"));
+    pub static ref LOCK_SVG: Image<'static> = Image::new(egui::include_image!("../../data/icons/lock.svg"));
+    pub static ref LOCK_OPEN_SVG: Image<'static> = Image::new(egui::include_image!("../../data/icons/lock_open.svg"));
     pub static ref ANCHOR_SVG: Image<'static> = Image::new(egui::include_image!("../../data/icons/anchor.svg"));
 
     pub static ref PLAY_SVG: Image<'static> = Image::new(egui::include_image!("../../data/icons/play.svg")).fit_to_exact_size(SIZE);
@@ -64,13 +64,10 @@ impl<'a> MainWindow<'a> {
     }
 
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        let mut fnt = crate::model::font_imp::FontTool {
-            selected_font: Arc::new(Mutex::new(0)),
-            fonts: Arc::new(Mutex::new(Vec::new())),
-            sizes: Vec::new(),
-        };
+        let mut fnt = crate::model::font_imp::FontTool::default();
         fnt.load_fonts();
         fnt.install_watcher();
+        let text_box = crate::model::text_box_imp::TextBoxTool::new(fnt.fonts.clone(), fnt.selected_font.clone());
         egui_extras::install_image_loaders(&cc.egui_ctx);
 
         let tools: Vec<Box<dyn Tool>> = vec![
@@ -90,6 +87,7 @@ impl<'a> MainWindow<'a> {
             Box::new(crate::model::fill_imp::FillTool::new()),
             Box::new(fnt),
             Box::<crate::model::move_layer_imp::MoveLayer>::default(),
+            Box::new(text_box),
         ];
 
         let ctx: &egui::Context = &cc.egui_ctx;
@@ -172,8 +170,13 @@ impl<'a> MainWindow<'a> {
                 }
                 let file_name_str = file_name.unwrap_or_default().to_str().unwrap_or_default().to_string();
                 if let Ok(font) = BitFont::from_bytes(file_name_str, data) {
+                    crate::register_psf_unicode_table(&font, data);
                     let id = self.create_id();
-                    add_child(&mut self.document_tree, Some(full_path), Box::new(BitFontEditor::new(&self.gl, id, font)));
+                    let mut editor = BitFontEditor::new(&self.gl, id, font);
+                    if let Some(table) = crate::UnicodeTable::from_psf_bytes(data) {
+                        editor.load_unicode_table(table);
+                    }
+                    add_child(&mut self.document_tree, Some(full_path), Box::new(editor));
                     return;
                 }
             }
@@ -195,6 +198,14 @@ impl<'a> MainWindow<'a> {
                 return;
             }
 
+            if is_image_extension(&ext) {
+                match image::load_from_memory(data) {
+                    Ok(image) => self.open_dialog(crate::ImageConvertDialog::new_document(image.to_rgba8(), full_path)),
+                    Err(err) => self.show_error(format!("{err}")),
+                }
+                return;
+            }
+
             if "tdf" == ext {
                 let file_name = path.file_name();
                 if file_name.is_none() {
@@ -212,7 +223,8 @@ impl<'a> MainWindow<'a> {
             Ok(mut buf) => {
                 let id = self.create_id();
                 buf.is_terminal_buffer = false;
-                let editor = AnsiEditor::new(&self.gl, id, buf);
+                let mut editor = AnsiEditor::new(&self.gl, id, buf);
+                editor.saved_selections = crate::read_saved_selections(data);
                 add_child(&mut self.document_tree, Some(full_path), Box::new(editor));
             }
             Err(err) => {
@@ -552,6 +564,10 @@ pub fn is_font_extensions(ext: &str) -> bool {
         || "fon" == ext
 }
 
+pub fn is_image_extension(ext: &str) -> bool {
+    "png" == ext || "jpg" == ext || "jpeg" == ext || "gif" == ext || "bmp" == ext || "webp" == ext || "tga" == ext
+}
+
 pub fn button_with_shortcut(ui: &mut Ui, enabled: bool, label: impl Into<String>, shortcut: impl Into<String>) -> Response {
     let title = label.into();
     let button = Button::new(title).shortcut_text(shortcut.into());
@@ -6,11 +6,12 @@ use eframe::{
     epaint::Vec2,
 };
 use egui::mutex::Mutex;
+use i18n_embed_fl::fl;
 use icy_engine::{util::pop_data, BitFont, EngineResult, IceMode, Layer, PaletteMode, SauceData, Size, TextPane, TheDrawFont};
 
 use crate::{
     util::autosave::{self},
-    AnsiEditor, MainWindow, NewFileDialog, SaveFileDialog, SelectCharacterDialog, SelectOutlineDialog, Settings, MRU_FILES, PLUGINS, SETTINGS,
+    AnsiEditor, MainWindow, ModifySelection, NewFileDialog, SaveFileDialog, SelectCharacterDialog, SelectOutlineDialog, Settings, MRU_FILES, PLUGINS, SETTINGS,
 };
 
 #[derive(Clone)]
@@ -19,6 +20,11 @@ pub enum Message {
     OpenFileDialog,
     SaveFile,
     SaveFileAs,
+    ShowImportGlyphsDialog,
+    ShowExportFontDialog,
+    ShowCompareFontDialog,
+    ShowGlyphPatchDialog(crate::GlyphPatchMode),
+    ShowGenerateTdfFontDialog,
     ExportFile,
     ShowOutlineDialog,
     CloseWindow,
@@ -29,14 +35,32 @@ pub enum Message {
     RaiseLayer(usize),
     LowerLayer(usize),
     ToggleLayerVisibility(usize),
+    SoloLayer(usize),
+    ToggleGhostLayer(usize),
+    SetGhostOpacity(f32),
     SelectLayer(usize),
     DuplicateLayer(usize),
     MergeLayerDown(usize),
+    GroupLayer(usize),
+    ToggleLayerGroupVisibility(String, usize),
+    ToggleLayerGroupLock(String, usize),
+    RaiseLayerGroup(String, usize),
+    LowerLayerGroup(String, usize),
+    MergeLayerGroup(String, usize),
+    UngroupLayers(String, usize),
+    RenameLayerGroup(String, usize),
 
     Undo,
     Redo,
     EditSauce,
     SetCanvasSize,
+    ShowFindReplaceDialog,
+    ShowSaveSelectionDialog,
+    ApplySavedSelection(usize, crate::model::select_imp::SelectionModifier),
+    DeleteSavedSelection(usize),
+    SelectionFromLayer(usize, crate::model::select_imp::SelectionModifier),
+    ShowModifySelectionDialog(ModifySelection),
+    SelectSimilar,
     SelectAll,
     SelectNothing,
     DeleteSelection,
@@ -99,6 +123,7 @@ pub enum Message {
     StampLayerDown,
     RotateLayer,
     MakeLayerTransparent,
+    FreeTransform,
 
     ToggleFullScreen,
 
@@ -218,6 +243,47 @@ impl<'a> MainWindow<'a> {
                     self.open_dialog(SaveFileDialog::new(path));
                 }
             }
+            Message::ShowImportGlyphsDialog => {
+                if let Some(doc) = self.get_active_document() {
+                    if let Some(editor) = doc.lock().get_bitfont_editor_mut() {
+                        let font = editor.get_font();
+                        let (size, glyph_count) = (font.size, font.length as usize);
+                        self.open_dialog(crate::ImportGlyphsDialog::new(size, glyph_count));
+                    }
+                }
+            }
+            Message::ShowExportFontDialog => {
+                let font = self
+                    .get_active_document()
+                    .and_then(|doc| doc.lock().get_bitfont_editor_mut().map(|editor| editor.get_font().clone()));
+                if let Some(font) = font {
+                    let mut documents = Vec::new();
+                    self.enumerate_documents(|_, tab| {
+                        if let Some(editor) = tab.doc.lock().get_ansi_editor() {
+                            let title = match tab.get_path() {
+                                Some(path) => path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                                None => fl!(crate::LANGUAGE_LOADER, "unsaved-title"),
+                            };
+                            documents.push((title, editor.buffer_view.lock().get_buffer().clone()));
+                        }
+                    });
+                    self.open_dialog(crate::ExportFontDialog::new(font, documents));
+                }
+            }
+            Message::ShowCompareFontDialog => {
+                self.open_dialog(crate::CompareFontDialog::default());
+            }
+            Message::ShowGlyphPatchDialog(mode) => {
+                let patch = self
+                    .get_active_document()
+                    .and_then(|doc| doc.lock().get_bitfont_editor_mut().map(|editor| editor.get_marked_patch()));
+                if let Some(patch) = patch {
+                    self.open_dialog(crate::GlyphPatchDialog::new(mode, patch));
+                }
+            }
+            Message::ShowGenerateTdfFontDialog => {
+                self.open_dialog(crate::GenerateTdfFontDialog::default());
+            }
             Message::ExportFile => {
                 self.run_editor_command(0, |window, editor, _| {
                     let view = editor.buffer_view.clone();
@@ -295,6 +361,14 @@ impl<'a> MainWindow<'a> {
                 }
             }
 
+            Message::ShowFindReplaceDialog => {
+                if let Some(doc) = self.get_active_document() {
+                    if let Some(editor) = doc.lock().get_ansi_editor() {
+                        self.open_dialog(crate::FindReplaceDialog::new(editor.buffer_view.clone()));
+                    }
+                }
+            }
+
             Message::EditLayer(i) => {
                 if let Some(doc) = self.get_active_document() {
                     if let Some(editor) = doc.lock().get_ansi_editor() {
@@ -380,13 +454,74 @@ impl<'a> MainWindow<'a> {
                 });
             }
 
+            Message::GroupLayer(cur_layer) => {
+                self.run_editor_command(cur_layer, |_, editor, cur_layer| {
+                    to_message(crate::layer_groups::group_layer(editor, cur_layer))
+                });
+            }
+            Message::ToggleLayerGroupVisibility(group, layer) => {
+                self.run_editor_command((group, layer), |_, editor, (group, layer)| {
+                    to_message(crate::layer_groups::toggle_group_visibility(editor, &group, layer))
+                });
+            }
+            Message::ToggleLayerGroupLock(group, layer) => {
+                self.run_editor_command((group, layer), |_, editor, (group, layer)| {
+                    to_message(crate::layer_groups::toggle_group_lock(editor, &group, layer))
+                });
+            }
+            Message::RaiseLayerGroup(group, layer) => {
+                self.run_editor_command((group, layer), |_, editor, (group, layer)| {
+                    to_message(crate::layer_groups::raise_group(editor, &group, layer))
+                });
+            }
+            Message::LowerLayerGroup(group, layer) => {
+                self.run_editor_command((group, layer), |_, editor, (group, layer)| {
+                    to_message(crate::layer_groups::lower_group(editor, &group, layer))
+                });
+            }
+            Message::MergeLayerGroup(group, layer) => {
+                self.run_editor_command((group, layer), |_, editor, (group, layer)| {
+                    to_message(crate::layer_groups::merge_group(editor, &group, layer))
+                });
+            }
+            Message::UngroupLayers(group, layer) => {
+                self.run_editor_command((group, layer), |_, editor, (group, layer)| {
+                    to_message(crate::layer_groups::ungroup(editor, &group, layer))
+                });
+            }
+            Message::RenameLayerGroup(group, layer) => {
+                self.open_dialog(crate::RenameLayerGroupDialog::new(group, layer));
+            }
+
             Message::ToggleLayerVisibility(cur_layer) => {
                 self.run_editor_command(cur_layer, |_, editor: &mut crate::AnsiEditor, cur_layer| {
+                    editor.end_solo_layer();
                     let mut lock = editor.buffer_view.lock();
                     to_message(lock.get_edit_state_mut().toggle_layer_visibility(cur_layer))
                 });
             }
 
+            Message::SoloLayer(cur_layer) => {
+                self.run_editor_command(cur_layer, |_, editor, cur_layer| {
+                    editor.toggle_solo_layer(cur_layer);
+                    None
+                });
+            }
+
+            Message::ToggleGhostLayer(cur_layer) => {
+                self.run_editor_command(cur_layer, |_, editor, cur_layer| {
+                    editor.toggle_ghost_layer(cur_layer);
+                    None
+                });
+            }
+
+            Message::SetGhostOpacity(opacity) => {
+                self.run_editor_command(opacity, |_, editor, opacity| {
+                    editor.set_ghost_opacity(opacity);
+                    None
+                });
+            }
+
             Message::SelectLayer(cur_layer) => {
                 self.run_editor_command(cur_layer, |_, editor, cur_layer| {
                     editor.set_cur_layer_index(cur_layer);
@@ -452,7 +587,18 @@ impl<'a> MainWindow<'a> {
 
             Message::Paste => {
                 if let Some(doc) = self.get_active_document() {
-                    self.handle_result(doc.lock().paste());
+                    let image = if doc.lock().get_ansi_editor().is_some() && pop_data(icy_engine::util::BUFFER_DATA).is_none() {
+                        crate::pop_clipboard_image()
+                    } else {
+                        None
+                    };
+                    if let Some(image) = image {
+                        if let Some(editor) = doc.lock().get_ansi_editor() {
+                            self.open_dialog(crate::ImageConvertDialog::new(image, editor.buffer_view.lock().get_buffer()));
+                        }
+                    } else {
+                        self.handle_result(doc.lock().paste());
+                    }
                 }
             }
 
@@ -663,6 +809,22 @@ impl<'a> MainWindow<'a> {
                 });
             }
 
+            Message::FreeTransform => {
+                let selected_tool = self.document_behavior.get_selected_tool();
+                if selected_tool != crate::PASTE_TOOL {
+                    self.run_editor_command(0, |_, editor, _| to_message(editor.float_selection()));
+                    if let Some(doc) = self.get_active_document() {
+                        if let Some(editor) = doc.lock().get_ansi_editor() {
+                            if editor.buffer_view.lock().get_edit_state_mut().has_floating_layer() {
+                                self.document_behavior.tools.lock()[crate::PASTE_TOOL] =
+                                    Box::new(crate::model::paste_tool::PasteTool::new_transform(selected_tool));
+                                self.document_behavior.set_selected_tool(crate::PASTE_TOOL);
+                            }
+                        }
+                    }
+                }
+            }
+
             Message::MakeLayerTransparent => {
                 self.run_editor_command(0, |_, editor, _| {
                     let mut lock = editor.buffer_view.lock();
@@ -847,6 +1009,41 @@ impl<'a> MainWindow<'a> {
                 unsafe { MRU_FILES.clear_recent_files() };
             }
 
+            Message::ShowSaveSelectionDialog => {
+                if let Some(doc) = self.get_active_document() {
+                    if let Some(editor) = doc.lock().get_ansi_editor() {
+                        self.open_dialog(crate::SaveSelectionDialog::new(editor.saved_selections.len()));
+                    }
+                }
+            }
+            Message::ApplySavedSelection(index, operation) => {
+                self.run_editor_command((index, operation), |_, editor, (index, operation)| {
+                    editor.apply_saved_selection(index, operation);
+                    None
+                });
+            }
+            Message::DeleteSavedSelection(index) => {
+                self.run_editor_command(index, |_, editor, index| {
+                    editor.delete_saved_selection(index);
+                    None
+                });
+            }
+            Message::SelectionFromLayer(layer, operation) => {
+                self.run_editor_command((layer, operation), |_, editor, (layer, operation)| {
+                    editor.selection_from_layer(layer, operation);
+                    None
+                });
+            }
+            Message::ShowModifySelectionDialog(modify) => {
+                self.open_dialog(crate::ModifySelectionDialog::new(modify));
+            }
+            Message::SelectSimilar => {
+                self.run_editor_command(0, |_, editor, _| {
+                    editor.select_similar();
+                    None
+                });
+            }
+
             Message::InverseSelection => {
                 self.run_editor_command(0, |_, editor, _| to_message(editor.buffer_view.lock().get_edit_state_mut().inverse_selection()));
             }
@@ -32,6 +32,9 @@ pub struct Settings {
 
     #[serde(default)]
     scale: Vec2,
+
+    #[serde(default)]
+    tdf_font_layouts: Vec<TdfFontLayout>,
 }
 
 impl Default for Settings {
@@ -46,10 +49,43 @@ impl Default for Settings {
             marker_settings: Default::default(),
             save_options: Default::default(),
             scale: Vec2::splat(2.0),
+            tdf_font_layouts: Default::default(),
         }
     }
 }
 
+/// Spacing and kerning of a TheDraw font. TDF files have no room for it, so it's stored by font name.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TdfFontLayout {
+    pub font_name: String,
+    /// Added to the font spacing between two glyphs.
+    pub letter_spacing: i32,
+    /// Overrides the width of the space character.
+    pub space_width: Option<i32>,
+    /// Added to the font height between two lines.
+    pub line_spacing: i32,
+    /// Offsets between two characters.
+    pub kerning: Vec<(char, char, i32)>,
+}
+
+impl TdfFontLayout {
+    pub fn get_kerning(&self, left: char, right: char) -> i32 {
+        self.kerning
+            .iter()
+            .find(|(a, b, _)| *a == left && *b == right)
+            .map_or(0, |(_, _, offset)| *offset)
+    }
+
+    pub fn set_kerning(&mut self, left: char, right: char, offset: i32) {
+        self.kerning.retain(|(a, b, _)| *a != left || *b != right);
+        self.kerning.push((left, right, offset));
+    }
+
+    fn is_default(&self) -> bool {
+        self.letter_spacing == 0 && self.space_width.is_none() && self.line_spacing == 0 && self.kerning.is_empty()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct KeyBindings {
     pub key_bindings: Vec<(String, eframe::egui::Key, Modifiers)>,
@@ -185,6 +221,23 @@ impl Settings {
         unsafe { SETTINGS.character_set }
     }
 
+    pub fn get_tdf_font_layout(font_name: &str) -> TdfFontLayout {
+        unsafe { SETTINGS.tdf_font_layouts.iter().find(|layout| layout.font_name == font_name).cloned() }.unwrap_or_else(|| TdfFontLayout {
+            font_name: font_name.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Only changes the settings in memory, the caller saves them once the edit is finished.
+    pub fn set_tdf_font_layout(layout: TdfFontLayout) {
+        unsafe {
+            SETTINGS.tdf_font_layouts.retain(|l| l.font_name != layout.font_name);
+            if !layout.is_default() {
+                SETTINGS.tdf_font_layouts.push(layout);
+            }
+        }
+    }
+
     pub(crate) fn get_font_diretory() -> TerminalResult<PathBuf> {
         if let Some(proj_dirs) = ProjectDirs::from("com", "GitHub", "icy_draw") {
             let dir = proj_dirs.config_dir().join("data/fonts");
@@ -425,6 +478,7 @@ pub static mut SETTINGS: Settings = Settings {
         guide_color: Color::new(0xAB, 0xAB, 0xAB),
     },
     scale: Vec2::splat(2.0),
+    tdf_font_layouts: Vec::new(),
 };
 
 #[derive(Debug, Clone)]
@@ -196,6 +196,12 @@ pub fn create_font_image(
     selected_attribute: TextAttribute,
     selected: usize,
 ) -> TextureHandle {
+    let buffer = create_font_buffer(font, buffer_width, attribute, selected_attribute, selected);
+    create_image(ctx, &buffer)
+}
+
+/// Creates a buffer showing all glyphs of the font, `buffer_width` glyphs per line.
+pub fn create_font_buffer(font: &BitFont, buffer_width: usize, attribute: TextAttribute, selected_attribute: TextAttribute, selected: usize) -> Buffer {
     let mut buffer = Buffer::new((buffer_width, (font.length as usize) / buffer_width));
     buffer.set_font(0, font.clone());
     for ch in 0..font.length as usize {
@@ -207,7 +213,7 @@ pub fn create_font_image(
             ),
         );
     }
-    create_image(ctx, &buffer)
+    buffer
 }
 
 pub fn create_hover_image(ctx: &Context, font: &BitFont, ch: char, color: u32) -> TextureHandle {
@@ -0,0 +1,223 @@
+//! Layer groups are stored as a path in the layer title: "Sky␟Clouds␟Layer 1" is the layer "Layer 1"
+//! inside the group "Clouds" which is nested in the group "Sky". That way groups survive every
+//! format that stores layer titles (like .icy) without needing extra data.
+//! The path is separated by the ASCII unit separator, a control character that can't be typed,
+//! so existing titles never turn into groups by accident.
+//! A group is a contiguous run of layers sharing the same path prefix.
+
+use i18n_embed_fl::fl;
+use icy_engine::{EngineResult, Layer};
+
+use crate::AnsiEditor;
+
+pub const GROUP_SEPARATOR: char = '\u{1F}';
+
+/// Splits a layer title into the group path and the layer name.
+pub fn split_group_path(title: &str) -> (&str, &str) {
+    match title.rfind(GROUP_SEPARATOR) {
+        Some(i) => (&title[..i], &title[i + 1..]),
+        None => ("", title),
+    }
+}
+
+/// Puts a layer or group name into the group, separators in the name are removed.
+pub fn join_group_path(group: &str, name: &str) -> String {
+    let name: String = name.chars().filter(|ch| *ch != GROUP_SEPARATOR).collect();
+    if group.is_empty() {
+        name
+    } else {
+        format!("{group}{GROUP_SEPARATOR}{name}")
+    }
+}
+
+/// Returns the name of a group, the last component of the group path.
+pub fn get_group_name(group: &str) -> &str {
+    split_group_path(group).1
+}
+
+/// Returns true if the title belongs to the group or one of its sub groups.
+/// Every layer is part of the root group "".
+pub fn is_in_group(title: &str, group: &str) -> bool {
+    group.is_empty() || title.len() > group.len() && title.starts_with(group) && title[group.len()..].starts_with(GROUP_SEPARATOR)
+}
+
+/// Returns the index range of the group run that contains the layer `index`.
+pub fn get_group_range(layers: &[Layer], group: &str, index: usize) -> Option<(usize, usize)> {
+    if index >= layers.len() || !is_in_group(layers[index].get_title(), group) {
+        return None;
+    }
+    let mut lo = index;
+    while lo > 0 && is_in_group(layers[lo - 1].get_title(), group) {
+        lo -= 1;
+    }
+    let mut hi = index;
+    while hi + 1 < layers.len() && is_in_group(layers[hi + 1].get_title(), group) {
+        hi += 1;
+    }
+    Some((lo, hi))
+}
+
+/// Returns the range of the direct child of `parent` containing the layer `index`.
+/// That's either the layer itself or the whole sub group it lives in.
+fn get_child_range(layers: &[Layer], parent: &str, index: usize) -> Option<(usize, usize)> {
+    let title = layers.get(index)?.get_title();
+    if !is_in_group(title, parent) {
+        return None;
+    }
+    let rest = if parent.is_empty() { title } else { &title[parent.len() + 1..] };
+    match rest.find(GROUP_SEPARATOR) {
+        Some(i) => {
+            let child = &title[..title.len() - rest.len() + i];
+            get_group_range(layers, child, index)
+        }
+        None => Some((index, index)),
+    }
+}
+
+fn get_parent_group(group: &str) -> &str {
+    split_group_path(group).0
+}
+
+fn set_layer_title(editor: &mut AnsiEditor, layer: usize, title: String) -> EngineResult<()> {
+    let mut lock = editor.buffer_view.lock();
+    let mut properties = lock.get_buffer().layers[layer].properties.clone();
+    if properties.title == title {
+        return Ok(());
+    }
+    properties.title = title;
+    lock.get_edit_state_mut().update_layer_properties(layer, properties)
+}
+
+/// Shows all layers of the group if one of them is hidden, otherwise hides them.
+pub fn toggle_group_visibility(editor: &mut AnsiEditor, group: &str, index: usize) -> EngineResult<()> {
+    editor.end_solo_layer();
+    let layers = editor.buffer_view.lock().get_buffer().layers.clone();
+    let Some((lo, hi)) = get_group_range(&layers, group, index) else {
+        return Ok(());
+    };
+    let is_visible = !layers[lo..=hi].iter().all(|l| l.get_is_visible());
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-layer-group-visibility"));
+    for (i, layer) in layers.iter().enumerate().take(hi + 1).skip(lo) {
+        if layer.properties.is_visible != is_visible {
+            let mut properties = layer.properties.clone();
+            properties.is_visible = is_visible;
+            editor.buffer_view.lock().get_edit_state_mut().update_layer_properties(i, properties)?;
+        }
+    }
+    Ok(())
+}
+
+/// Locks all layers of the group if one of them is unlocked, otherwise unlocks them.
+pub fn toggle_group_lock(editor: &mut AnsiEditor, group: &str, index: usize) -> EngineResult<()> {
+    let layers = editor.buffer_view.lock().get_buffer().layers.clone();
+    let Some((lo, hi)) = get_group_range(&layers, group, index) else {
+        return Ok(());
+    };
+    let is_locked = !layers[lo..=hi].iter().all(|l| l.properties.is_locked);
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-layer-group-lock"));
+    for (i, layer) in layers.iter().enumerate().take(hi + 1).skip(lo) {
+        if layer.properties.is_locked != is_locked {
+            let mut properties = layer.properties.clone();
+            properties.is_locked = is_locked;
+            editor.buffer_view.lock().get_edit_state_mut().update_layer_properties(i, properties)?;
+        }
+    }
+    Ok(())
+}
+
+/// Moves the whole group above the next layer or group of the same parent.
+pub fn raise_group(editor: &mut AnsiEditor, group: &str, index: usize) -> EngineResult<()> {
+    let layers = editor.buffer_view.lock().get_buffer().layers.clone();
+    let Some((lo, hi)) = get_group_range(&layers, group, index) else {
+        return Ok(());
+    };
+    let Some((_, neighbor_hi)) = get_child_range(&layers, get_parent_group(group), hi + 1) else {
+        return Ok(());
+    };
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-layer-group-move"));
+    for k in 0..neighbor_hi - hi {
+        for j in ((lo + 1 + k)..=(hi + 1 + k)).rev() {
+            editor.buffer_view.lock().get_edit_state_mut().lower_layer(j)?;
+        }
+    }
+    Ok(())
+}
+
+/// Moves the whole group below the previous layer or group of the same parent.
+pub fn lower_group(editor: &mut AnsiEditor, group: &str, index: usize) -> EngineResult<()> {
+    let layers = editor.buffer_view.lock().get_buffer().layers.clone();
+    let Some((lo, hi)) = get_group_range(&layers, group, index) else {
+        return Ok(());
+    };
+    if lo == 0 {
+        return Ok(());
+    }
+    let Some((neighbor_lo, _)) = get_child_range(&layers, get_parent_group(group), lo - 1) else {
+        return Ok(());
+    };
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-layer-group-move"));
+    for k in 0..lo - neighbor_lo {
+        for j in (lo - 1 - k)..(hi - k) {
+            editor.buffer_view.lock().get_edit_state_mut().raise_layer(j)?;
+        }
+    }
+    Ok(())
+}
+
+/// Merges all layers of the group into a single layer named after the group.
+pub fn merge_group(editor: &mut AnsiEditor, group: &str, index: usize) -> EngineResult<()> {
+    let layers = editor.buffer_view.lock().get_buffer().layers.clone();
+    let Some((lo, hi)) = get_group_range(&layers, group, index) else {
+        return Ok(());
+    };
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-layer-group-merge"));
+    for _ in lo..hi {
+        editor.buffer_view.lock().get_edit_state_mut().merge_layer_down(lo + 1)?;
+    }
+    set_layer_title(editor, lo, group.to_string())
+}
+
+/// Moves the layers of the group into the parent group.
+pub fn ungroup(editor: &mut AnsiEditor, group: &str, index: usize) -> EngineResult<()> {
+    let layers = editor.buffer_view.lock().get_buffer().layers.clone();
+    let Some((lo, hi)) = get_group_range(&layers, group, index) else {
+        return Ok(());
+    };
+    let parent = get_parent_group(group);
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-layer-group-ungroup"));
+    for (i, layer) in layers.iter().enumerate().take(hi + 1).skip(lo) {
+        let rest = &layer.get_title()[group.len() + 1..];
+        let title = if parent.is_empty() {
+            rest.to_string()
+        } else {
+            format!("{parent}{GROUP_SEPARATOR}{rest}")
+        };
+        set_layer_title(editor, i, title)?;
+    }
+    Ok(())
+}
+
+/// Renames the group for all layers of the group run.
+pub fn rename_group(editor: &mut AnsiEditor, group: &str, index: usize, new_name: &str) -> EngineResult<()> {
+    let layers = editor.buffer_view.lock().get_buffer().layers.clone();
+    let Some((lo, hi)) = get_group_range(&layers, group, index) else {
+        return Ok(());
+    };
+    let new_group = join_group_path(get_parent_group(group), new_name);
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-layer-group-rename"));
+    for (i, layer) in layers.iter().enumerate().take(hi + 1).skip(lo) {
+        let title = format!("{new_group}{}", &layer.get_title()[group.len()..]);
+        set_layer_title(editor, i, title)?;
+    }
+    Ok(())
+}
+
+/// Puts the layer into a new group inside of its current group.
+pub fn group_layer(editor: &mut AnsiEditor, index: usize) -> EngineResult<()> {
+    let title = editor.buffer_view.lock().get_buffer().layers[index].get_title().to_string();
+    let (parent, name) = split_group_path(&title);
+    let group_name = fl!(crate::LANGUAGE_LOADER, "layer_tool_default_group_name");
+    let new_title = join_group_path(&join_group_path(parent, &group_name), name);
+    let _undo = editor.begin_atomic_undo(fl!(crate::LANGUAGE_LOADER, "undo-layer-group-new"));
+    set_layer_title(editor, index, new_title)
+}
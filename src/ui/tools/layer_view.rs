@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use eframe::{
     egui::{self, CentralPanel, RichText, Sense, TextStyle, TopBottomPanel},
@@ -7,15 +7,25 @@ use eframe::{
 };
 use egui::{mutex::Mutex, Image};
 use i18n_embed_fl::fl;
+use icy_engine::{AttributedChar, Buffer, Layer, Position, TextAttribute, TextPane};
 use icy_engine_egui::BufferView;
 
-use crate::{AnsiEditor, Document, Message, ToolWindow, INVISIBLE_SVG, VISIBLE_SVG};
+use crate::{
+    layer_groups::{get_group_name, split_group_path, GROUP_SEPARATOR},
+    strip_blend_mode, AnsiEditor, Document, Message, ToolWindow, INVISIBLE_SVG, LOCK_OPEN_SVG, LOCK_SVG, VISIBLE_SVG,
+};
+
+enum LayerRow {
+    Group { path: String, depth: usize, index: usize },
+    Layer { index: usize, depth: usize },
+}
 
 pub struct LayerToolWindow {
     gl: Arc<glow::Context>,
     view_cache_id: usize,
     stack_len: usize,
     view_cache: Vec<Arc<eframe::epaint::mutex::Mutex<BufferView>>>,
+    collapsed_groups: HashSet<String>,
 }
 
 impl LayerToolWindow {
@@ -25,7 +35,156 @@ impl LayerToolWindow {
             view_cache: Vec::new(),
             view_cache_id: usize::MAX,
             stack_len: usize::MAX,
+            collapsed_groups: HashSet::new(),
+        }
+    }
+
+    /// Builds the rows of the layer tree from top to bottom, every run of layers sharing a group path gets a group header.
+    fn get_rows(editor: &AnsiEditor) -> Vec<LayerRow> {
+        let lock = editor.buffer_view.lock();
+        let layers = &lock.get_buffer().layers;
+        let mut rows = Vec::new();
+        let mut open_groups: Vec<String> = Vec::new();
+
+        for i in (0..layers.len()).rev() {
+            let (group, _) = split_group_path(layers[i].get_title());
+            let components: Vec<&str> = if group.is_empty() {
+                Vec::new()
+            } else {
+                group.split(GROUP_SEPARATOR).collect()
+            };
+
+            let mut common = 0;
+            while common < open_groups.len() && common < components.len() && open_groups[common] == components[..=common].join(&GROUP_SEPARATOR.to_string()) {
+                common += 1;
+            }
+            open_groups.truncate(common);
+            for depth in common..components.len() {
+                let path = components[..=depth].join(&GROUP_SEPARATOR.to_string());
+                rows.push(LayerRow::Group {
+                    path: path.clone(),
+                    depth,
+                    index: i,
+                });
+                open_groups.push(path);
+            }
+            rows.push(LayerRow::Layer {
+                index: i,
+                depth: components.len(),
+            });
         }
+        rows
+    }
+
+    fn show_group_row(&mut self, ui: &mut egui::Ui, editor: &AnsiEditor, path: &str, depth: usize, index: usize, result: &mut Option<Message>) {
+        let (is_visible, is_locked, contains_cur_layer) = {
+            let lock = editor.buffer_view.lock();
+            let layers = &lock.get_buffer().layers;
+            let cur_layer = lock.get_edit_state().get_current_layer().unwrap_or_default();
+            if let Some((lo, hi)) = crate::layer_groups::get_group_range(layers, path, index) {
+                (
+                    layers[lo..=hi].iter().any(|l| l.get_is_visible()),
+                    layers[lo..=hi].iter().all(|l| l.properties.is_locked),
+                    (lo..=hi).contains(&cur_layer),
+                )
+            } else {
+                (true, false, false)
+            }
+        };
+        let is_collapsed = self.collapsed_groups.contains(path);
+
+        ui.horizontal(|ui| {
+            ui.add_space(4.0 + depth as f32 * 12.0);
+            let row_height = 24.0;
+            let width = ui.available_width();
+            let (id, back_rect) = ui.allocate_space(Vec2::new(width, row_height));
+            let mut response = ui.interact(back_rect, id, Sense::click());
+            let back_painter = ui.painter_at(back_rect);
+
+            if response.hovered() {
+                back_painter.rect_filled(back_rect, Rounding::ZERO, ui.style().visuals.widgets.active.bg_fill);
+            } else if contains_cur_layer {
+                back_painter.rect_filled(back_rect, Rounding::ZERO, ui.style().visuals.faint_bg_color);
+            }
+
+            let icon_size = Vec2::new(20.0, 20.0);
+            let top = back_rect.min + Vec2::new(0.0, (row_height - icon_size.y) / 2.0);
+            let expand_rect = Rect::from_min_size(top, icon_size);
+            let visible_rect = Rect::from_min_size(top + Vec2::new(22.0, 0.0), icon_size);
+            let lock_rect = Rect::from_min_size(top + Vec2::new(44.0, 0.0), icon_size);
+
+            let expand_response = ui.interact(expand_rect, id.with("expand"), Sense::click());
+            let visible_response = ui.interact(visible_rect, id.with("visible"), Sense::click());
+            let lock_response = ui.interact(lock_rect, id.with("lock"), Sense::click());
+
+            let tint = ui.visuals().widgets.inactive.fg_stroke.color;
+            let font_id = TextStyle::Button.resolve(ui.style());
+            back_painter.text(
+                expand_rect.center(),
+                Align2::CENTER_CENTER,
+                if is_collapsed { "▶" } else { "▼" },
+                font_id.clone(),
+                tint,
+            );
+            let image: Image<'static> = if is_visible { VISIBLE_SVG.clone() } else { INVISIBLE_SVG.clone() };
+            image.tint(tint).paint_at(ui, visible_rect);
+            let image: Image<'static> = if is_locked { LOCK_SVG.clone() } else { LOCK_OPEN_SVG.clone() };
+            image.tint(tint).paint_at(ui, lock_rect);
+
+            back_painter.text(
+                lock_rect.right_center() + Vec2::new(6., 0.),
+                Align2::LEFT_CENTER,
+                get_group_name(path),
+                font_id,
+                ui.style().visuals.strong_text_color(),
+            );
+
+            if expand_response.clicked() || response.double_clicked() {
+                if is_collapsed {
+                    self.collapsed_groups.remove(path);
+                } else {
+                    self.collapsed_groups.insert(path.to_string());
+                }
+            }
+            if visible_response.clicked() {
+                *result = Some(Message::ToggleLayerGroupVisibility(path.to_string(), index));
+            }
+            if lock_response.clicked() {
+                *result = Some(Message::ToggleLayerGroupLock(path.to_string(), index));
+            }
+
+            let response_opt = response.context_menu(|ui| {
+                ui.set_width(250.);
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_rename_group")).clicked() {
+                    *result = Some(Message::RenameLayerGroup(path.to_string(), index));
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_raise_group")).clicked() {
+                    *result = Some(Message::RaiseLayerGroup(path.to_string(), index));
+                    ui.close_menu();
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_lower_group")).clicked() {
+                    *result = Some(Message::LowerLayerGroup(path.to_string(), index));
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_merge_group")).clicked() {
+                    *result = Some(Message::MergeLayerGroup(path.to_string(), index));
+                    ui.close_menu();
+                }
+                if ui.button(fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_ungroup")).clicked() {
+                    *result = Some(Message::UngroupLayers(path.to_string(), index));
+                    ui.close_menu();
+                }
+            });
+            if let Some(response_opt) = response_opt {
+                response = response_opt.response;
+            }
+            if response.clicked() && !expand_response.clicked() && !visible_response.clicked() && !lock_response.clicked() {
+                *result = Some(Message::SelectLayer(index));
+            }
+        });
     }
 
     pub fn get_buffer_view(&mut self, i: usize) -> Arc<eframe::epaint::mutex::Mutex<BufferView>> {
@@ -51,6 +210,8 @@ impl LayerToolWindow {
         };
 
         let paste_mode = editor.buffer_view.lock().get_buffer().layers.iter().position(|layer| layer.role.is_paste());
+        let ghost_layer = editor.get_ghost_layer();
+        let solo_layer = editor.get_solo_layer();
 
         TopBottomPanel::bottom("layer_bottom").show_inside(ui, |ui| {
             ui.horizontal(|ui| {
@@ -119,6 +280,19 @@ impl LayerToolWindow {
                         result = Some(Message::RemoveLayer(cur_layer));
                     }
                 }
+
+                if let Some(ghost) = &editor.ghost_layer {
+                    ui.add_space(8.0);
+                    let mut opacity = ghost.opacity * 100.0;
+                    let r = ui
+                        .add(egui::Slider::new(&mut opacity, 0.0..=100.0).suffix("%").show_value(false))
+                        .on_hover_ui(|ui| {
+                            ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "layer_tool_ghost_opacity_tooltip")).small());
+                        });
+                    if r.changed() {
+                        result = Some(Message::SetGhostOpacity(opacity / 100.0));
+                    }
+                }
             });
         });
 
@@ -129,10 +303,38 @@ impl LayerToolWindow {
                 self.stack_len = editor.undo_stack_len();
             }
 
+            let rows = if paste_mode.is_some() {
+                (0..max).rev().map(|index| LayerRow::Layer { index, depth: 0 }).collect()
+            } else {
+                Self::get_rows(editor)
+            };
+
             egui::ScrollArea::vertical().id_source("layer_view_scroll_area").show(ui, |ui| {
-                for i in (0..max).rev() {
+                // rows deeper than this belong to a collapsed group
+                let mut collapsed_depth = None;
+                for row in rows {
+                    let (i, depth) = match row {
+                        LayerRow::Group { path, depth, index } => {
+                            if collapsed_depth.map_or(false, |d| depth > d) {
+                                continue;
+                            }
+                            collapsed_depth = None;
+                            self.show_group_row(ui, editor, &path, depth, index, &mut result);
+                            if self.collapsed_groups.contains(&path) {
+                                collapsed_depth = Some(depth);
+                            }
+                            continue;
+                        }
+                        LayerRow::Layer { index, depth } => {
+                            if collapsed_depth.map_or(false, |d| depth > d) {
+                                continue;
+                            }
+                            collapsed_depth = None;
+                            (index, depth)
+                        }
+                    };
                     ui.horizontal(|ui| {
-                        ui.add_space(4.0);
+                        ui.add_space(4.0 + depth as f32 * 12.0);
                         let dims = editor.buffer_view.lock().get_buffer().get_font_dimensions();
                         let size = dims.height as f32 * 25.0;
                         let scale = row_height / size;
@@ -151,14 +353,15 @@ impl LayerToolWindow {
                             let view = self.get_buffer_view(i);
                             if redraw_layer_views {
                                 view.lock().get_buffer_mut().layers.clear();
-                                let width = editor.buffer_view.lock().get_width();
-                                view.lock().get_buffer_mut().set_width(width);
+                                let size = editor.buffer_view.lock().get_buffer().get_size();
+                                view.lock().get_buffer_mut().set_size(size);
                                 let lock = &editor.buffer_view.lock();
                                 if let Some(layer) = lock.get_buffer().layers.get(i) {
                                     let mut l = layer.clone();
                                     l.set_is_visible(true);
                                     view.lock().get_buffer_mut().set_font_table(lock.get_buffer().get_font_table());
                                     view.lock().get_buffer_mut().palette = lock.get_buffer().palette.clone();
+                                    add_checkerboard_layer(view.lock().get_buffer_mut());
                                     view.lock().get_buffer_mut().layers.push(l);
                                     view.lock().get_edit_state_mut().set_is_buffer_dirty();
                                 }
@@ -170,7 +373,11 @@ impl LayerToolWindow {
                         let (is_visible, title, color) = {
                             let lock = editor.buffer_view.lock();
                             let layer = &lock.get_buffer().layers[i];
-                            (layer.get_is_visible(), layer.get_title().to_string(), layer.properties.color.clone())
+                            (
+                                layer.get_is_visible(),
+                                strip_blend_mode(split_group_path(layer.get_title()).1).to_string(),
+                                layer.properties.color.clone(),
+                            )
                         };
                         let width = ui.available_width();
 
@@ -186,7 +393,9 @@ impl LayerToolWindow {
                         }
 
                         let stroke_rect = Rect::from_min_size(back_rect.min + Vec2::new(0.0, (row_height - 22.0) / 2.0), Vec2::new(22.0, 22.0));
-                        let visible_icon_response = ui.interact(stroke_rect, id.with("visible"), Sense::click());
+                        let visible_icon_response = ui.interact(stroke_rect, id.with("visible"), Sense::click()).on_hover_ui(|ui| {
+                            ui.label(RichText::new(fl!(crate::LANGUAGE_LOADER, "layer_tool_visibility_tooltip")).small());
+                        });
 
                         let painter = ui.painter_at(stroke_rect);
 
@@ -212,10 +421,19 @@ impl LayerToolWindow {
                         };
                         let font_id = TextStyle::Button.resolve(ui.style());
 
+                        let title = if ghost_layer == Some(i) {
+                            format!("{title} ({})", fl!(crate::LANGUAGE_LOADER, "layer_tool_ghost"))
+                        } else {
+                            title
+                        };
                         back_painter.text(stroke_rect.right_center() + Vec2::new(4., 0.), Align2::LEFT_CENTER, title, font_id, color);
 
                         if visible_icon_response.clicked() {
-                            result = Some(Message::ToggleLayerVisibility(i));
+                            if ui.input(|i| i.modifiers.alt) && paste_mode.is_none() {
+                                result = Some(Message::SoloLayer(i));
+                            } else {
+                                result = Some(Message::ToggleLayerVisibility(i));
+                            }
                         }
 
                         if paste_mode.is_none() {
@@ -242,6 +460,24 @@ impl LayerToolWindow {
                                     result = Some(Message::MergeLayerDown(i));
                                     ui.close_menu();
                                 }
+                                let mut is_ghost = ghost_layer == Some(i);
+                                if ui.checkbox(&mut is_ghost, fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_ghost_layer")).clicked() {
+                                    result = Some(Message::ToggleGhostLayer(i));
+                                    ui.close_menu();
+                                }
+                                let mut is_solo = solo_layer == Some(i);
+                                if ui.checkbox(&mut is_solo, fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_solo_layer")).clicked() {
+                                    result = Some(Message::SoloLayer(i));
+                                    ui.close_menu();
+                                }
+                                if ui.button(fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_selection_from_layer")).clicked() {
+                                    result = Some(Message::SelectionFromLayer(i, crate::model::select_imp::SelectionModifier::Replace));
+                                    ui.close_menu();
+                                }
+                                if ui.button(fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_group_layer")).clicked() {
+                                    result = Some(Message::GroupLayer(i));
+                                    ui.close_menu();
+                                }
                                 if ui.button(fl!(crate::LANGUAGE_LOADER, "layer_tool_menu_delete_layer")).clicked() {
                                     result = Some(Message::RemoveLayer(i));
                                     ui.close_menu();
@@ -292,6 +528,22 @@ impl ToolWindow for LayerToolWindow {
     }
 }
 
+/// Fills the buffer with a checkerboard below the layer thumbnail, so transparent cells stand out.
+fn add_checkerboard_layer(buf: &mut Buffer) {
+    let light = buf.palette.insert_color_rgb(0xCC, 0xCC, 0xCC);
+    let dark = buf.palette.insert_color_rgb(0x99, 0x99, 0x99);
+    let size = buf.get_size();
+    let mut layer = Layer::new("checkerboard", size);
+    for y in 0..size.height {
+        for x in 0..size.width {
+            let mut attr = TextAttribute::default();
+            attr.set_background(if (x + y) % 2 == 0 { light } else { dark });
+            layer.set_char(Position::new(x, y), AttributedChar::new(' ', attr));
+        }
+    }
+    buf.layers.push(layer);
+}
+
 pub fn medium_hover_button(ui: &mut egui::Ui, image: &Image<'_>) -> egui::Response {
     let size_points = egui::Vec2::splat(28.0);
 
@@ -1,6 +1,8 @@
 mod layer_view;
 pub use layer_view::*;
 
+pub mod layer_groups;
+
 mod char_table;
 pub use char_table::*;
 
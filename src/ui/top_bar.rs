@@ -11,7 +11,17 @@ use icy_engine::{
     FontMode, IceMode, PaletteMode,
 };
 
-use crate::{button_with_shortcut, MainWindow, Message, Settings, LATEST_VERSION, MRU_FILES, PLUGINS, SETTINGS, VERSION};
+use crate::{
+    button_with_shortcut, model::select_imp::SelectionModifier, MainWindow, Message, ModifySelection, Settings, LATEST_VERSION, MRU_FILES, PLUGINS, SETTINGS,
+    VERSION,
+};
+
+const SELECTION_OPERATIONS: [SelectionModifier; 4] = [
+    SelectionModifier::Replace,
+    SelectionModifier::Add,
+    SelectionModifier::Remove,
+    SelectionModifier::Intersect,
+];
 
 lazy_static::lazy_static! {
     pub static ref DOCK_LEFT_SVG: Image<'static> = Image::new(egui::include_image!("../../data/icons/dock_left.svg"));
@@ -44,6 +54,8 @@ impl<'a> MainWindow<'a> {
             let mut has_reference_image = false;
             let mut cur_raster = Some(Vec2::new(f32::NAN, f32::NAN));
             let mut cur_guide = Some(Vec2::new(f32::NAN, f32::NAN));
+            let mut saved_selections = Vec::new();
+            let mut cur_layer = 0;
 
             if self.last_command_update.elapsed().as_millis() > 250 {
                 let mut c = self.commands.pop().unwrap();
@@ -62,6 +74,8 @@ impl<'a> MainWindow<'a> {
                     cur_raster = editor.raster;
                     cur_guide = editor.guide;
                     has_reference_image = editor.buffer_view.lock().has_reference_image();
+                    saved_selections = editor.saved_selections.iter().map(|s| s.name.clone()).collect();
+                    cur_layer = editor.get_cur_layer_index().unwrap_or_default();
                 } else {
                     has_buffer = false;
                 }
@@ -169,6 +183,7 @@ impl<'a> MainWindow<'a> {
                     }
                 });
                 ui.separator();
+                self.commands[0].find_replace.ui(ui, &mut result);
                 ui.menu_button(fl!(crate::LANGUAGE_LOADER, "menu-area_operations"), |ui| {
                     ui.style_mut().wrap = Some(false);
                     ui.set_min_width(300.0);
@@ -225,6 +240,61 @@ impl<'a> MainWindow<'a> {
                 self.commands[0].select_all.ui(ui, &mut result);
                 self.commands[0].deselect.ui(ui, &mut result);
                 self.commands[0].inverse_selection.ui(ui, &mut result);
+                ui.add_enabled_ui(has_buffer, |ui| {
+                    ui.menu_button(fl!(crate::LANGUAGE_LOADER, "menu-modify_selection"), |ui| {
+                        ui.style_mut().wrap = Some(false);
+                        for modify in [ModifySelection::Grow, ModifySelection::Shrink, ModifySelection::Border, ModifySelection::Smooth] {
+                            if ui.button(format!("{}…", modify.get_label())).clicked() {
+                                result = Some(Message::ShowModifySelectionDialog(modify));
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        if ui.button(fl!(crate::LANGUAGE_LOADER, "menu-select_similar")).clicked() {
+                            result = Some(Message::SelectSimilar);
+                            ui.close_menu();
+                        }
+                    });
+                });
+                ui.separator();
+                if ui
+                    .add_enabled(has_buffer, egui::Button::new(fl!(crate::LANGUAGE_LOADER, "menu-save_selection")).wrap(false))
+                    .clicked()
+                {
+                    result = Some(Message::ShowSaveSelectionDialog);
+                    ui.close_menu();
+                }
+                ui.add_enabled_ui(!saved_selections.is_empty(), |ui| {
+                    ui.menu_button(fl!(crate::LANGUAGE_LOADER, "menu-saved_selections"), |ui| {
+                        ui.style_mut().wrap = Some(false);
+                        for (i, name) in saved_selections.iter().enumerate() {
+                            ui.menu_button(name, |ui| {
+                                for operation in SELECTION_OPERATIONS {
+                                    if ui.button(operation.get_label()).clicked() {
+                                        result = Some(Message::ApplySavedSelection(i, operation));
+                                        ui.close_menu();
+                                    }
+                                }
+                                ui.separator();
+                                if ui.button(fl!(crate::LANGUAGE_LOADER, "menu-delete_saved_selection")).clicked() {
+                                    result = Some(Message::DeleteSavedSelection(i));
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                    });
+                });
+                ui.add_enabled_ui(has_buffer, |ui| {
+                    ui.menu_button(fl!(crate::LANGUAGE_LOADER, "menu-selection_from_layer"), |ui| {
+                        ui.style_mut().wrap = Some(false);
+                        for operation in SELECTION_OPERATIONS {
+                            if ui.button(operation.get_label()).clicked() {
+                                result = Some(Message::SelectionFromLayer(cur_layer, operation));
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
                 ui.separator();
                 self.commands[0].erase_selection.ui(ui, &mut result);
                 self.commands[0].flip_x.ui(ui, &mut result);
@@ -233,6 +303,7 @@ impl<'a> MainWindow<'a> {
                 self.commands[0].justifyleft.ui(ui, &mut result);
                 self.commands[0].justifyright.ui(ui, &mut result);
                 self.commands[0].crop.ui(ui, &mut result);
+                self.commands[0].free_transform.ui(ui, &mut result);
             });
             ui.menu_button(fl!(crate::LANGUAGE_LOADER, "menu-colors"), |ui| {
                 ui.style_mut().wrap = Some(false);
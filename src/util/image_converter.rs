@@ -0,0 +1,516 @@
+//! Converts raster images to text cells. The image is scaled to a grid of sub pixels
+//! (one per cell for full blocks, two for half blocks, four for quarter blocks), quantized to the
+//! colors the buffer can show and then mapped to block, shade or ASCII characters.
+//! In RGB palette mode the colors are taken from the image as they are.
+
+use icy_engine::{AttributedChar, Buffer, BufferType, Color, IceMode, Palette, PaletteMode, Position, TextAttribute, TextPane};
+use image::{imageops::FilterType, RgbaImage};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ConversionMode {
+    FullBlock,
+    #[default]
+    HalfBlock,
+    QuarterBlock,
+    Shade,
+    Ascii,
+}
+
+impl ConversionMode {
+    fn get_sub_pixels(self) -> (u32, u32) {
+        match self {
+            ConversionMode::HalfBlock => (1, 2),
+            ConversionMode::QuarterBlock => (2, 2),
+            ConversionMode::FullBlock | ConversionMode::Shade | ConversionMode::Ascii => (1, 1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Dithering {
+    None,
+    #[default]
+    FloydSteinberg,
+    Ordered,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImageConverter {
+    pub mode: ConversionMode,
+    pub dithering: Dithering,
+    pub width: i32,
+    pub height: i32,
+    pub buffer_type: BufferType,
+    pub palette: Palette,
+    pub palette_mode: PaletteMode,
+    pub ice_mode: IceMode,
+}
+
+const BAYER_4X4: [[f32; 4]; 4] = [[0.0, 8.0, 2.0, 10.0], [12.0, 4.0, 14.0, 6.0], [3.0, 11.0, 1.0, 9.0], [15.0, 7.0, 13.0, 5.0]];
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+const SHADES: [(f32, char, char); 5] = [
+    (0.0, ' ', ' '),
+    (0.25, '\u{B0}', '░'),
+    (0.5, '\u{B1}', '▒'),
+    (0.75, '\u{B2}', '▓'),
+    (1.0, '\u{DB}', '█'),
+];
+/// Quarter block characters indexed by the foreground mask (bit 0 top left, 1 top right, 2 bottom left, 3 bottom right).
+const QUARTER_BLOCKS: [char; 16] = [' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█'];
+
+type Rgb = [f32; 3];
+
+impl ImageConverter {
+    pub fn new(buf: &Buffer) -> Self {
+        Self {
+            mode: ConversionMode::default(),
+            dithering: Dithering::default(),
+            width: 80,
+            height: 25,
+            buffer_type: buf.buffer_type,
+            palette: buf.palette.clone(),
+            palette_mode: buf.palette_mode,
+            ice_mode: buf.ice_mode,
+        }
+    }
+
+    /// Returns the height in cells that keeps the aspect ratio of the image for the current width.
+    /// Cells are assumed to be twice as high as wide.
+    pub fn get_height_for_width(&self, img: &RgbaImage) -> i32 {
+        if img.width() == 0 {
+            return 1;
+        }
+        ((self.width as f32 * img.height() as f32 / img.width() as f32 / 2.0).round() as i32).max(1)
+    }
+
+    /// Replaces the palette with colors picked from the image for the free palette modes,
+    /// the fixed and the RGB mode start with the DOS palette.
+    pub fn fit_palette(&mut self, img: &RgbaImage) {
+        let colors = match self.palette_mode {
+            PaletteMode::Fixed16 | PaletteMode::RGB => {
+                self.palette = Palette::dos_default();
+                return;
+            }
+            PaletteMode::Free8 => 8,
+            PaletteMode::Free16 => 16,
+        };
+        let sample = image::imageops::thumbnail(img, 64, 64);
+        let mut pixels: Vec<Rgb> = sample
+            .pixels()
+            .filter(|p| p[3] >= 128)
+            .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+            .collect();
+        if pixels.is_empty() {
+            return;
+        }
+        pixels.sort_by(|a, b| luminance(*a).total_cmp(&luminance(*b)));
+
+        // k-means, starting with colors spread over the brightness range
+        let mut centers: Vec<Rgb> = (0..colors).map(|i| pixels[i * (pixels.len() - 1) / (colors - 1).max(1)]).collect();
+        for _ in 0..10 {
+            let mut sums = vec![([0.0; 3], 0); colors];
+            for p in &pixels {
+                let nearest = (0..colors)
+                    .min_by(|a, b| distance(centers[*a], *p).total_cmp(&distance(centers[*b], *p)))
+                    .unwrap_or_default();
+                for (sum, c) in sums[nearest].0.iter_mut().zip(p) {
+                    *sum += c;
+                }
+                sums[nearest].1 += 1;
+            }
+            for (center, (sum, count)) in centers.iter_mut().zip(sums) {
+                if count > 0 {
+                    *center = sum.map(|c| c / count as f32);
+                }
+            }
+        }
+        centers.sort_by(|a, b| luminance(*a).total_cmp(&luminance(*b)));
+        let colors: Vec<Color> = centers.iter().map(|c| Color::new(c[0] as u8, c[1] as u8, c[2] as u8)).collect();
+        self.palette = Palette::from_slice(&colors);
+    }
+
+    fn get_foreground_colors(&self) -> usize {
+        let len = self.palette.len();
+        match self.palette_mode {
+            PaletteMode::Free8 => len.min(8),
+            PaletteMode::Fixed16 | PaletteMode::Free16 => len.min(16),
+            PaletteMode::RGB => len,
+        }
+    }
+
+    fn get_background_colors(&self) -> usize {
+        if matches!(self.ice_mode, IceMode::Blink) {
+            self.get_foreground_colors().min(8)
+        } else {
+            self.get_foreground_colors()
+        }
+    }
+
+    fn get_color(&self, color: usize) -> Rgb {
+        let (r, g, b) = self.palette.get_color(color as u32).get_rgb();
+        [r as f32, g as f32, b as f32]
+    }
+
+    fn get_nearest_color(&self, rgb: Rgb, colors: usize) -> usize {
+        let mut best = 0;
+        let mut best_dist = f32::MAX;
+        for i in 0..colors {
+            let dist = distance(self.get_color(i), rgb);
+            if dist < best_dist {
+                best_dist = dist;
+                best = i;
+            }
+        }
+        best
+    }
+
+    pub fn convert(&self, img: &RgbaImage) -> Buffer {
+        let width = self.width.max(1);
+        let height = self.height.max(1);
+        let (sx, sy) = self.mode.get_sub_pixels();
+        let (pw, ph) = (width as u32 * sx, height as u32 * sy);
+        let scaled = image::imageops::resize(img, pw, ph, FilterType::Triangle);
+
+        let mut pixels: Vec<Rgb> = scaled.pixels().map(|p| [p[0] as f32, p[1] as f32, p[2] as f32]).collect();
+        let alpha: Vec<bool> = scaled.pixels().map(|p| p[3] >= 128).collect();
+
+        let mut buf = Buffer::new((width, height));
+        buf.buffer_type = self.buffer_type;
+        buf.palette = self.palette.clone();
+        buf.palette_mode = self.palette_mode;
+        buf.ice_mode = self.ice_mode;
+
+        if matches!(self.palette_mode, PaletteMode::RGB) {
+            self.convert_true_color(&mut buf, &pixels, &alpha, pw as usize);
+            return buf;
+        }
+
+        let brightness: Vec<f32> = pixels.iter().map(|p| luminance(*p)).collect();
+        let indices = self.quantize(&mut pixels, pw as usize, ph as usize, &self.get_candidates());
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut cell = Vec::new();
+                let mut visible = false;
+                for dy in 0..sy as usize {
+                    for dx in 0..sx as usize {
+                        let i = (y as usize * sy as usize + dy) * pw as usize + x as usize * sx as usize + dx;
+                        cell.push(indices[i]);
+                        visible |= alpha[i];
+                    }
+                }
+                let ch = if visible {
+                    let first = y as usize * sy as usize * pw as usize + x as usize * sx as usize;
+                    self.get_cell(&cell, brightness[first])
+                } else {
+                    AttributedChar::invisible()
+                };
+                buf.layers[0].set_char(Position::new(x, y), ch);
+            }
+        }
+        buf
+    }
+
+    /// The colors a pixel can be mapped to. These are the palette colors except for shades,
+    /// where every mix of a foreground, a background and a shade character is a candidate.
+    fn get_candidates(&self) -> Vec<Rgb> {
+        let fg_colors = self.get_foreground_colors();
+        if !matches!(self.mode, ConversionMode::Shade) {
+            return (0..fg_colors).map(|color| self.get_color(color)).collect();
+        }
+        let mut result = Vec::new();
+        for fg in 0..fg_colors {
+            let f = self.get_color(fg);
+            for bg in 0..self.get_background_colors() {
+                let b = self.get_color(bg);
+                for (level, _, _) in SHADES {
+                    result.push([0, 1, 2].map(|c| f[c] * level + b[c] * (1.0 - level)));
+                }
+            }
+        }
+        result
+    }
+
+    /// Maps every pixel to a candidate index, diffusing the error with the selected dithering.
+    fn quantize(&self, pixels: &mut [Rgb], width: usize, height: usize, candidates: &[Rgb]) -> Vec<usize> {
+        // shades mix two colors, a smaller threshold keeps the pattern from getting too noisy
+        let spread = if matches!(self.mode, ConversionMode::Shade) { 32.0 } else { 64.0 };
+        let mut result = vec![0; pixels.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let mut rgb = pixels[i];
+                if matches!(self.dithering, Dithering::Ordered) {
+                    let t = (BAYER_4X4[y % 4][x % 4] / 16.0 - 0.5) * spread;
+                    rgb = rgb.map(|c| c + t);
+                }
+                let color = get_nearest(candidates, rgb);
+                result[i] = color;
+                if matches!(self.dithering, Dithering::FloydSteinberg) {
+                    let q = candidates[color];
+                    let err = [rgb[0] - q[0], rgb[1] - q[1], rgb[2] - q[2]];
+                    diffuse(pixels, width, height, x, y, err);
+                }
+            }
+        }
+        result
+    }
+
+    fn get_cell(&self, cell: &[usize], brightness: f32) -> AttributedChar {
+        let bg_colors = self.get_background_colors();
+        match self.mode {
+            ConversionMode::FullBlock => self.make_char(self.block_char(4), cell[0], 0),
+            ConversionMode::HalfBlock => {
+                let (top, bottom) = (cell[0], cell[1]);
+                if top == bottom {
+                    self.make_char(self.block_char(4), top, 0)
+                } else if bottom < bg_colors {
+                    self.make_char(self.half_block_char(true), top, bottom)
+                } else if top < bg_colors {
+                    self.make_char(self.half_block_char(false), bottom, top)
+                } else {
+                    let bg = self.get_nearest_color(self.get_color(bottom), bg_colors);
+                    self.make_char(self.half_block_char(true), top, bg)
+                }
+            }
+            ConversionMode::QuarterBlock => self.get_quarter_block(cell),
+            ConversionMode::Ascii => {
+                let ch = ASCII_RAMP[((brightness / 255.0) * (ASCII_RAMP.len() - 1) as f32).round() as usize] as char;
+                self.make_char(ch, cell[0], 0)
+            }
+            ConversionMode::Shade => {
+                // the index refers to the mixes of get_candidates
+                let shade = cell[0] % SHADES.len();
+                let bg = cell[0] / SHADES.len() % bg_colors;
+                let fg = cell[0] / SHADES.len() / bg_colors;
+                self.make_char(self.block_char(shade), fg, bg)
+            }
+        }
+    }
+
+    /// Picks the two colors that fit the four quarters best and builds the character from the mask.
+    fn get_quarter_block(&self, cell: &[usize]) -> AttributedChar {
+        let bg_colors = self.get_background_colors();
+        let mut best = (f32::MAX, 0, 0, 0);
+        let mut candidates: Vec<usize> = cell.to_vec();
+        candidates.sort_unstable();
+        candidates.dedup();
+        for &fg in &candidates {
+            for &bg in &candidates {
+                let bg = if bg < bg_colors {
+                    bg
+                } else {
+                    self.get_nearest_color(self.get_color(bg), bg_colors)
+                };
+                let mut mask = 0;
+                let mut error = 0.0;
+                for (i, &c) in cell.iter().enumerate() {
+                    let df = distance(self.get_color(c), self.get_color(fg));
+                    let db = distance(self.get_color(c), self.get_color(bg));
+                    if df < db {
+                        mask |= 1 << i;
+                        error += df;
+                    } else {
+                        error += db;
+                    }
+                }
+                let mask = self.get_representable_mask(mask);
+                if error < best.0 {
+                    best = (error, fg, bg, mask);
+                }
+            }
+        }
+        let (_, fg, bg, mask) = best;
+        let ch = self.quarter_block_char(mask);
+        self.make_char(ch, fg, bg)
+    }
+
+    /// CP437 has no quarter blocks, masks get reduced to the closest half block.
+    fn get_representable_mask(&self, mask: usize) -> usize {
+        if matches!(self.buffer_type, BufferType::Unicode) {
+            return mask;
+        }
+        let mut best = (u32::MAX, 0);
+        for m in [0, 3, 5, 10, 12, 15] {
+            let diff = (m ^ mask).count_ones();
+            if diff < best.0 {
+                best = (diff, m);
+            }
+        }
+        best.1
+    }
+
+    /// Uses the colors of the image directly, no quantization or dithering is needed.
+    fn convert_true_color(&self, buf: &mut Buffer, pixels: &[Rgb], alpha: &[bool], pixel_width: usize) {
+        let (sx, sy) = self.mode.get_sub_pixels();
+        let (sx, sy) = (sx as usize, sy as usize);
+        for y in 0..buf.get_height() {
+            for x in 0..buf.get_width() {
+                let mut cell = Vec::new();
+                let mut visible = false;
+                for dy in 0..sy {
+                    for dx in 0..sx {
+                        let i = (y as usize * sy + dy) * pixel_width + x as usize * sx + dx;
+                        cell.push(pixels[i]);
+                        visible |= alpha[i];
+                    }
+                }
+                if !visible {
+                    buf.layers[0].set_char(Position::new(x, y), AttributedChar::invisible());
+                    continue;
+                }
+                let (ch, fg, bg) = match self.mode {
+                    ConversionMode::FullBlock => (self.block_char(4), cell[0], [0.0; 3]),
+                    ConversionMode::HalfBlock => (self.half_block_char(true), cell[0], cell[1]),
+                    ConversionMode::QuarterBlock => self.get_true_color_quarter_block(&cell),
+                    ConversionMode::Shade => {
+                        // the brightest channel decides the shade, the foreground is scaled up so the mix matches the pixel
+                        let max = cell[0].iter().fold(0.0f32, |a, b| a.max(*b)).clamp(0.0, 255.0);
+                        let shade = ((max / 255.0) * (SHADES.len() - 1) as f32).ceil() as usize;
+                        let level = SHADES[shade].0.max(f32::EPSILON);
+                        (self.block_char(shade), cell[0].map(|c| c / level), [0.0; 3])
+                    }
+                    ConversionMode::Ascii => {
+                        let brightness = luminance(cell[0]).clamp(0.0, 255.0);
+                        let ch = ASCII_RAMP[((brightness / 255.0) * (ASCII_RAMP.len() - 1) as f32).round() as usize] as char;
+                        (ch, cell[0], [0.0; 3])
+                    }
+                };
+                let fg = insert_color(&mut buf.palette, fg);
+                let bg = insert_color(&mut buf.palette, bg);
+                buf.layers[0].set_char(Position::new(x, y), self.make_char(ch, fg as usize, bg as usize));
+            }
+        }
+    }
+
+    /// Splits the four quarters into two groups and uses the average color of each group.
+    fn get_true_color_quarter_block(&self, cell: &[Rgb]) -> (char, Rgb, Rgb) {
+        let mut best = (f32::MAX, 0, [0.0; 3], [0.0; 3]);
+        for fg in cell {
+            for bg in cell {
+                let mut mask = 0;
+                for (i, c) in cell.iter().enumerate() {
+                    if distance(*c, *fg) < distance(*c, *bg) {
+                        mask |= 1 << i;
+                    }
+                }
+                let mask = self.get_representable_mask(mask);
+                let fg = average(cell.iter().enumerate().filter(|(i, _)| mask & (1 << i) != 0).map(|(_, c)| *c)).unwrap_or(*fg);
+                let bg = average(cell.iter().enumerate().filter(|(i, _)| mask & (1 << i) == 0).map(|(_, c)| *c)).unwrap_or(*bg);
+                let error: f32 = cell
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| distance(*c, if mask & (1 << i) != 0 { fg } else { bg }))
+                    .sum();
+                if error < best.0 {
+                    best = (error, mask, fg, bg);
+                }
+            }
+        }
+        let (_, mask, fg, bg) = best;
+        let ch = self.quarter_block_char(mask);
+        (ch, fg, bg)
+    }
+
+    fn block_char(&self, shade: usize) -> char {
+        if matches!(self.buffer_type, BufferType::Unicode) {
+            SHADES[shade].2
+        } else {
+            SHADES[shade].1
+        }
+    }
+
+    fn quarter_block_char(&self, mask: usize) -> char {
+        if matches!(self.buffer_type, BufferType::Unicode) {
+            QUARTER_BLOCKS[mask]
+        } else {
+            match mask {
+                0 => ' ',
+                3 => '\u{DF}',
+                5 => '\u{DD}',
+                10 => '\u{DE}',
+                12 => '\u{DC}',
+                _ => '\u{DB}',
+            }
+        }
+    }
+
+    fn half_block_char(&self, upper: bool) -> char {
+        match (upper, matches!(self.buffer_type, BufferType::Unicode)) {
+            (true, true) => '▀',
+            (false, true) => '▄',
+            (true, false) => '\u{DF}',
+            (false, false) => '\u{DC}',
+        }
+    }
+
+    fn make_char(&self, ch: char, fg: usize, bg: usize) -> AttributedChar {
+        let mut attribute = TextAttribute::default();
+        attribute.set_foreground(fg as u32);
+        attribute.set_background(bg as u32);
+        AttributedChar::new(ch, attribute)
+    }
+}
+
+fn get_nearest(colors: &[Rgb], rgb: Rgb) -> usize {
+    let mut best = 0;
+    let mut best_dist = f32::MAX;
+    for (i, color) in colors.iter().enumerate() {
+        let dist = distance(*color, rgb);
+        if dist < best_dist {
+            best_dist = dist;
+            best = i;
+        }
+    }
+    best
+}
+
+fn insert_color(palette: &mut Palette, rgb: Rgb) -> u32 {
+    let [r, g, b] = rgb.map(|c| c.round().clamp(0.0, 255.0) as u8);
+    palette.insert_color_rgb(r, g, b)
+}
+
+fn average(colors: impl Iterator<Item = Rgb>) -> Option<Rgb> {
+    let mut sum = [0.0; 3];
+    let mut count = 0;
+    for c in colors {
+        for (s, c) in sum.iter_mut().zip(c) {
+            *s += c;
+        }
+        count += 1;
+    }
+    if count == 0 {
+        None
+    } else {
+        Some(sum.map(|c| c / count as f32))
+    }
+}
+
+fn luminance(rgb: Rgb) -> f32 {
+    0.299 * rgb[0] + 0.587 * rgb[1] + 0.114 * rgb[2]
+}
+
+fn distance(a: Rgb, b: Rgb) -> f32 {
+    // weighted euclidean distance, closer to perceived differences than plain rgb
+    let dr = a[0] - b[0];
+    let dg = a[1] - b[1];
+    let db = a[2] - b[2];
+    2.0 * dr * dr + 4.0 * dg * dg + 3.0 * db * db
+}
+
+fn diffuse(pixels: &mut [Rgb], width: usize, height: usize, x: usize, y: usize, err: Rgb) {
+    let mut add = |dx: isize, dy: isize, factor: f32| {
+        let nx = x as isize + dx;
+        let ny = y as isize + dy;
+        if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+            return;
+        }
+        for (p, e) in pixels[ny as usize * width + nx as usize].iter_mut().zip(err) {
+            *p += e * factor;
+        }
+    };
+    add(1, 0, 7.0 / 16.0);
+    add(-1, 1, 3.0 / 16.0);
+    add(0, 1, 5.0 / 16.0);
+    add(1, 1, 1.0 / 16.0);
+}